@@ -31,6 +31,14 @@ impl Player for EmptyBot {
 				if let Some(_u) = self.units.my.structures.get(tag) { /* your code here */ }
 			}
 			Event::RandomRaceDetected(_race) => { /* your code here */ }
+			Event::ChatMessage { message, .. } => {
+				if message.eq_ignore_ascii_case("gg") { /* your code here */ }
+			}
+			Event::Timer(_token) => { /* your code here */ }
+			Event::HiddenEnemyNearBase(tag) => {
+				if let Some(_u) = self.hidden_enemies().get(tag) { /* your code here */ }
+			}
+			_ => { /* events added in future versions */ }
 		}
 		Ok(())
 	}
@@ -98,6 +98,7 @@ pub(crate) fn main(mut bot: impl Player + DerefMut<Target = Bot> + Deref<Target
 				sc2_version: sc2_version.as_deref(),
 				realtime,
 				save_replay_as: save_replay.as_deref(),
+				..Default::default()
 			},
 		),
 		Some(Command::Human {
@@ -118,6 +119,7 @@ pub(crate) fn main(mut bot: impl Player + DerefMut<Target = Bot> + Deref<Target
 				sc2_version: sc2_version.as_deref(),
 				realtime: true,
 				save_replay_as: save_replay.as_deref(),
+				..Default::default()
 			},
 		),
 		None => run_ladder_game(
@@ -1,12 +1,11 @@
 use rust_sc2::prelude::*;
-use std::{cmp::Ordering, collections::HashSet};
+use std::cmp::Ordering;
 
 mod ex_main;
 
 #[bot]
 #[derive(Default)]
 struct ReaperRushAI {
-	reapers_retreat: HashSet<u64>,
 	last_loop_distributed: u32,
 }
 
@@ -331,13 +330,13 @@ impl ReaperRushAI {
 		};
 
 		for u in &reapers {
-			let is_retreating = self.reapers_retreat.contains(&u.tag());
+			let is_retreating = self.has_role(u.tag(), Role::Retreating);
 			if is_retreating {
 				if u.health_percentage().unwrap() > 0.75 {
-					self.reapers_retreat.remove(&u.tag());
+					self.unassign_role(u.tag());
 				}
 			} else if u.health_percentage().unwrap() < 0.5 {
-				self.reapers_retreat.insert(u.tag());
+				self.assign_role(u.tag(), Role::Retreating);
 			}
 
 			match targets.closest(u) {
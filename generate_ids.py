@@ -163,10 +163,14 @@ def gen_enum(enum, name):
 			for k, v in sorted(enum.items(), key=lambda x: x[1])
 		)
 		+ "}\n"
+		+ f"impl {name} {{\n"
+		+ "\t/// The largest id value, all variants are in range `0..=MAX_ID`.\n"
+		+ f"\tpub const MAX_ID: u32 = {max(enum.values())};\n"
+		+ "}\n"
 	)
 
 
-def generate():
+def generate(path):
 	mod = [
 		[
 			"//! Auto generated with `generate_ids.py` script from `stableid.json`",
@@ -177,30 +181,10 @@ def generate():
 		[],
 		["mod impls;"],
 	]
-	enums_latest = parse_data(
-		load((Path.home() / "Documents" / "StarCraft II" / "stableid.json").open())
-	)
-	enums_linux = enums_latest
-	# parse_data(
-	# 	load(
-	# 		(Path.home() / "Documents" / "StarCraft II" / "stableid_4.10.json").open()
-	# 	),
-	# 	version="linux505",
-	# )
-
-	for name, file, enum, enum_linux in zip(
-		ENUM_NAMES, FILE_NAMES, enums_latest, enums_linux
-	):
-		if enum == enum_linux:
-			generated = f"{HEAD}\n{gen_enum(enum, name)}"
-		else:
-			generated = (
-				f'{HEAD}\n#[cfg(target_os = "windows")]\n'
-				+ gen_enum(enum, name)
-				+ '\n#[cfg(target_os = "linux")]\n'
-				+ gen_enum(enum_linux, name)
-			)
+	enums = parse_data(load(Path(path).open()))
 
+	for name, file, enum in zip(ENUM_NAMES, FILE_NAMES, enums):
+		generated = f"{HEAD}\n{gen_enum(enum, name)}"
 		(Path.cwd() / "src" / "ids" / f"{file}.rs").write_text(generated)
 		mod[1].append(f"mod {file};")
 		mod[2].append(f"pub use {file}::{name};")
@@ -209,5 +193,20 @@ def generate():
 	)
 
 
+USAGE = """\
+Usage: python generate_ids.py [stableid.json]
+
+Regenerates `src/ids` from `stableid.json` of the latest game version
+(default: ~/Documents/StarCraft II/stableid.json).\
+"""
+
+
 if __name__ == "__main__":
-	generate()
+	if any(arg in ("-h", "--help") for arg in argv[1:]):
+		exit(USAGE)
+	latest = (
+		argv[1]
+		if len(argv) > 1
+		else Path.home() / "Documents" / "StarCraft II" / "stableid.json"
+	)
+	generate(latest)
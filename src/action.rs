@@ -1,6 +1,7 @@
 //! Data structures for executing actions and analyzing actions failure.
 
 use crate::{
+	game_data::AbilityTarget,
 	geometry::{Point2, Point3},
 	ids::AbilityId,
 	FromProto, IntoProto,
@@ -12,6 +13,7 @@ use sc2_proto::{
 	raw::{ActionRawUnitCommand_oneof_target as ProtoTarget, ActionRaw_oneof_action as ProtoRawAction},
 	sc2api::{Action as ProtoAction, ActionChat_Channel, ActionError as ProtoActionError},
 };
+use std::{error::Error, fmt};
 
 // pub(crate) type Command = (u64, (AbilityId, Target, bool));
 
@@ -19,6 +21,14 @@ use sc2_proto::{
 pub(crate) struct Commander {
 	pub commands: Vec<(AbilityId, Target, bool, Vec<u64>)>,
 	pub autocast: FxHashMap<AbilityId, Vec<u64>>,
+	// Damage projected to targets of focus fire on current step
+	pub damage: FxHashMap<u64, f32>,
+	// Tolerance of the filter of repeated orders, `None` if filter is disabled
+	pub order_filter: Option<f32>,
+	// Minimum number of frames between commands with the same ability to the same unit
+	pub throttle: u32,
+	// Game loop of last command with given ability to given unit
+	pub last_commands: FxHashMap<(u64, AbilityId), u32>,
 }
 
 /// Target of ability used by unit.
@@ -127,6 +137,28 @@ impl FromProto<&ProtoAction> for Option<Action> {
 	}
 }
 
+/// Error returned by [`Unit::try_command`](crate::unit::Unit::try_command)
+/// when ability doesn't accept given kind of target.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InvalidTarget {
+	/// Ability that was used.
+	pub ability: AbilityId,
+	/// Target that was given.
+	pub target: Target,
+	/// Kind of target accepted by ability.
+	pub expected: AbilityTarget,
+}
+impl fmt::Display for InvalidTarget {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{:?} expects target {:?}, but got {:?}",
+			self.ability, self.expected, self.target
+		)
+	}
+}
+impl Error for InvalidTarget {}
+
 /// Structure used to analyze actions failed on previous game step.
 /// Stored in [`state.action_errors`](crate::game_state::GameState::action_errors).
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -0,0 +1,128 @@
+//! Recording of all actions issued by bot to a log file.
+//!
+//! Each line of log is a JSON object describing one action, for example:
+//! ```text
+//! {"loop":224,"type":"unit_command","ability":"Attack","target":{"pos":[30.5,42.0]},"units":[4345298945],"queue":false}
+//! {"loop":230,"type":"autocast","ability":"EffectRepairSCV","units":[4345823233]}
+//! {"loop":300,"type":"chat","message":"gl hf","team":false}
+//! ```
+//! Logs can be matched with replays by game loop to see what bot intended to do.
+
+use crate::{
+	action::{Action, Target},
+	bot::Bot,
+};
+use std::{
+	fmt::Write as _,
+	fs::File,
+	io::{self, BufWriter, Write},
+	path::Path,
+};
+
+/// Writer of actions log. Enabled with [`record_actions`](Bot::record_actions).
+pub struct ActionLog {
+	writer: BufWriter<File>,
+	line: String,
+}
+impl ActionLog {
+	/// Creates new log file at given path (existing file is overwritten).
+	pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+		Ok(Self {
+			writer: BufWriter::new(File::create(path)?),
+			line: String::new(),
+		})
+	}
+	/// Writes given actions issued on given game loop and flushes them to the file.
+	pub fn write(&mut self, game_loop: u32, actions: &[Action]) -> io::Result<()> {
+		for action in actions {
+			self.line.clear();
+			format_action(&mut self.line, game_loop, action);
+			self.writer.write_all(self.line.as_bytes())?;
+		}
+		self.writer.flush()
+	}
+}
+
+impl Bot {
+	/// Starts recording all actions issued by bot to the file at given path.
+	/// Log is written at the end of every step.
+	pub fn record_actions<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+		self.action_log = Some(ActionLog::create(path)?);
+		Ok(())
+	}
+	/// Stops recording of actions.
+	pub fn stop_recording_actions(&mut self) {
+		self.action_log = None;
+	}
+	pub(crate) fn log_actions(&mut self) {
+		let game_loop = self.state.observation.game_loop();
+		if let Some(log) = &mut self.action_log {
+			if let Err(e) = log.write(game_loop, &self.actions) {
+				error!("Can't write actions log: {}", e);
+				self.action_log = None;
+			}
+		}
+	}
+}
+
+fn format_action(s: &mut String, game_loop: u32, action: &Action) {
+	let _ = write!(s, "{{\"loop\":{},", game_loop);
+	match action {
+		Action::UnitCommand(ability, target, units, queue) => {
+			let _ = write!(
+				s,
+				"\"type\":\"unit_command\",\"ability\":\"{:?}\",\"target\":",
+				ability
+			);
+			match target {
+				Target::Pos(pos) => {
+					let _ = write!(s, "{{\"pos\":[{},{}]}}", pos.x, pos.y);
+				}
+				Target::Tag(tag) => {
+					let _ = write!(s, "{{\"tag\":{}}}", tag);
+				}
+				Target::None => s.push_str("null"),
+			}
+			s.push(',');
+			format_units(s, units);
+			let _ = write!(s, ",\"queue\":{}", queue);
+		}
+		Action::ToggleAutocast(ability, units) => {
+			let _ = write!(s, "\"type\":\"autocast\",\"ability\":\"{:?}\",", ability);
+			format_units(s, units);
+		}
+		Action::CameraMove(pos) => {
+			let _ = write!(
+				s,
+				"\"type\":\"camera_move\",\"pos\":[{},{},{}]",
+				pos.x, pos.y, pos.z
+			);
+		}
+		Action::Chat(message, team_only) => {
+			s.push_str("\"type\":\"chat\",\"message\":\"");
+			for c in message.chars() {
+				match c {
+					'"' => s.push_str("\\\""),
+					'\\' => s.push_str("\\\\"),
+					c if c.is_control() => {
+						let _ = write!(s, "\\u{:04x}", c as u32);
+					}
+					c => s.push(c),
+				}
+			}
+			let _ = write!(s, "\",\"team\":{}", team_only);
+		}
+	}
+	s.push_str("}\n");
+}
+
+fn format_units(s: &mut String, units: &[u64]) {
+	s.push_str("\"units\":[");
+	for (i, tag) in units.iter().enumerate() {
+		if i > 0 {
+			s.push(',');
+		}
+		let _ = write!(s, "{}", tag);
+	}
+	s.push(']');
+}
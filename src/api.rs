@@ -2,37 +2,187 @@
 
 use crate::{
 	bot::{Locked, Rl},
+	capture::{CaptureWriter, ENV_CAPTURE},
 	client::{SC2Result, WS},
+	perf::Timing,
 };
 use protobuf::Message;
 use sc2_proto::sc2api::{Request, Response};
+use std::{
+	env,
+	error::Error,
+	fmt,
+	path::Path,
+	time::{Duration, Instant},
+};
 use tungstenite::Message::Binary;
 
+// Upper bounds of latency histogram buckets in milliseconds, the last bucket is unbounded
+const LATENCY_BUCKETS: [u64; 7] = [1, 2, 5, 10, 20, 50, 100];
+
+type RequestCheck = fn(&Request) -> bool;
+const REQUEST_KINDS: [(RequestCheck, &str); 17] = [
+	(Request::has_observation, "observation"),
+	(Request::has_step, "step"),
+	(Request::has_action, "action"),
+	(Request::has_query, "query"),
+	(Request::has_debug, "debug"),
+	(Request::has_game_info, "game_info"),
+	(Request::has_data, "data"),
+	(Request::has_create_game, "create_game"),
+	(Request::has_join_game, "join_game"),
+	(Request::has_restart_game, "restart_game"),
+	(Request::has_start_replay, "start_replay"),
+	(Request::has_leave_game, "leave_game"),
+	(Request::has_quit, "quit"),
+	(Request::has_save_replay, "save_replay"),
+	(Request::has_replay_info, "replay_info"),
+	(Request::has_available_maps, "available_maps"),
+	(Request::has_ping, "ping"),
+];
+
+pub(crate) fn request_kind(req: &Request) -> &'static str {
+	REQUEST_KINDS
+		.iter()
+		.find(|(check, _)| check(req))
+		.map_or("other", |(_, kind)| kind)
+}
+
+/// Metrics of requests sent to SC2: counts, traffic and round-trip latency by request type.
+/// Can be accessed with [`API::request_metrics`].
+#[derive(Debug, Clone, Default)]
+pub struct RequestMetrics {
+	/// Round-trip time of all requests, which waited for response.
+	pub all: Timing,
+	/// Total number of requests sent.
+	pub sent: u64,
+	/// Total size of sent requests in bytes.
+	pub bytes_sent: u64,
+	/// Total size of received responses in bytes.
+	pub bytes_received: u64,
+	/// Number of requests with round-trip time in each bucket:
+	/// `<=1ms`, `<=2ms`, `<=5ms`, `<=10ms`, `<=20ms`, `<=50ms`, `<=100ms`, `>100ms`.
+	pub histogram: [u32; LATENCY_BUCKETS.len() + 1],
+	by_kind: Vec<(&'static str, Timing)>,
+}
+impl RequestMetrics {
+	/// Returns round-trip time of requests of given type (e.g. `"observation"`, `"step"`, `"query"`).
+	pub fn by_kind(&self, kind: &str) -> Option<&Timing> {
+		self.by_kind.iter().find(|(k, _)| *k == kind).map(|(_, t)| t)
+	}
+	/// Returns round-trip times of all request types in order of their first use.
+	pub fn kinds(&self) -> impl Iterator<Item = (&'static str, &Timing)> {
+		self.by_kind.iter().map(|(k, t)| (*k, t))
+	}
+	fn add_sent(&mut self, bytes: usize) {
+		self.sent += 1;
+		self.bytes_sent += bytes as u64;
+	}
+	fn add_received(&mut self, bytes: usize) {
+		self.bytes_received += bytes as u64;
+	}
+	fn add_latency(&mut self, kind: &'static str, elapsed: Duration) {
+		self.all.add(elapsed);
+		let bucket = LATENCY_BUCKETS
+			.iter()
+			.position(|bound| elapsed <= Duration::from_millis(*bound))
+			.unwrap_or(LATENCY_BUCKETS.len());
+		self.histogram[bucket] += 1;
+		match self.by_kind.iter_mut().find(|(k, _)| *k == kind) {
+			Some((_, timing)) => timing.add(elapsed),
+			None => {
+				let mut timing = Timing::default();
+				timing.add(elapsed);
+				self.by_kind.push((kind, timing));
+			}
+		}
+	}
+}
+impl fmt::Display for RequestMetrics {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(
+			f,
+			"Requests: {}, sent {} bytes, received {} bytes",
+			self.sent, self.bytes_sent, self.bytes_received
+		)?;
+		write!(f, "  all: {}", self.all)?;
+		for (kind, timing) in &self.by_kind {
+			write!(f, "\n  {}: {}", kind, timing)?;
+		}
+		Ok(())
+	}
+}
+
 /// SC2 API. Can be accessed through [`self.api()`](crate::bot::Bot::api).
-pub struct API(Rl<WS>);
+pub struct API(Rl<WS>, Rl<RequestMetrics>, Rl<Option<CaptureWriter>>);
 impl API {
 	pub(crate) fn new(ws: WS) -> API {
-		API(Rl::new(ws))
+		API(Rl::new(ws), Default::default(), Default::default())
+	}
+	// Constructs API of bot, which is captured if `SC2_CAPTURE` is set.
+	// API of human player in `RunnerMulti` isn't captured, so it doesn't write to the same file.
+	pub(crate) fn new_captured(ws: WS) -> API {
+		let api = API::new(ws);
+		if let Some(path) = env::var_os(ENV_CAPTURE) {
+			if let Err(e) = api.start_capture(&path) {
+				error!("Can't create capture file {:?}: {}", path, e);
+			}
+		}
+		api
 	}
 
-	/// Sends request and returns a response.
-	pub fn send(&self, req: Request) -> SC2Result<Response> {
-		let mut ws = self.0.write_lock();
+	/// Starts writing all requests and responses to capture file at given path,
+	/// replacing the current capture if any (see [`capture`](crate::capture) module).
+	pub fn start_capture<P: AsRef<Path>>(&self, path: P) -> SC2Result<()> {
+		*self.2.write_lock() = Some(CaptureWriter::create(path)?);
+		Ok(())
+	}
+	/// Stops capture of requests and responses, flushing capture file.
+	pub fn stop_capture(&self) {
+		*self.2.write_lock() = None;
+	}
+	/// Checks if requests and responses are being captured.
+	pub fn is_capturing(&self) -> bool {
+		self.2.read_lock().is_some()
+	}
 
-		ws.write_message(Binary(req.write_to_bytes()?))?;
+	/// Returns round-trip time statistics of requests sent with [`send`] and [`send_request`].
+	///
+	/// [`send`]: Self::send
+	/// [`send_request`]: Self::send_request
+	pub fn request_timing(&self) -> Timing {
+		self.1.read_lock().all
+	}
+	/// Returns metrics of all requests sent to SC2.
+	pub fn request_metrics(&self) -> RequestMetrics {
+		self.1.read_lock().clone()
+	}
 
-		let msg = ws.read_message()?;
+	/// Sends request and returns a response.
+	#[cfg_attr(feature = "tracing", tracing::instrument(name = "sc2_request", skip_all))]
+	pub fn send(&self, req: Request) -> SC2Result<Response> {
+		let msg = self.round_trip(&req)?;
 
 		let mut res = Response::new();
-		res.merge_from_bytes(msg.into_data().as_slice())?;
+		res.merge_from_bytes(msg.as_slice())?;
 		Ok(res)
 	}
 
+	/// Sends request and returns a response,
+	/// or [`ResponseError`] if SC2 reported errors in the response.
+	pub fn send_checked(&self, req: Request) -> SC2Result<Response> {
+		let res = self.send(req)?;
+		if res.get_error().is_empty() {
+			Ok(res)
+		} else {
+			Err(Box::new(ResponseError(res.get_error().to_vec())))
+		}
+	}
+
 	/// Sends request, waits for the response, but ignores it (useful when response is empty).
+	#[cfg_attr(feature = "tracing", tracing::instrument(name = "sc2_request", skip_all))]
 	pub fn send_request(&self, req: Request) -> SC2Result<()> {
-		let mut ws = self.0.write_lock();
-		ws.write_message(Binary(req.write_to_bytes()?))?;
-		let _ = ws.read_message()?;
+		let _ = self.round_trip(&req)?;
 		Ok(())
 	}
 
@@ -42,17 +192,62 @@ impl API {
 	/// [`send`]: Self::send
 	/// [`send_request`]: Self::send_request
 	pub fn send_only(&self, req: Request) -> SC2Result<()> {
-		self.0.write_lock().write_message(Binary(req.write_to_bytes()?))?;
+		let bytes = req.write_to_bytes()?;
+		self.1.write_lock().add_sent(bytes.len());
+		if let Some(capture) = &mut *self.2.write_lock() {
+			capture.request(&bytes);
+		}
+		self.0.write_lock().write_message(Binary(bytes))?;
 		Ok(())
 	}
 	/// Waits for a response (useful only after [`send_only`]).
 	///
 	/// [`send_only`]: Self::send_only
+	#[cfg_attr(feature = "tracing", tracing::instrument(name = "sc2_response", skip_all))]
 	pub fn wait_response(&self) -> SC2Result<Response> {
-		let msg = self.0.write_lock().read_message()?;
+		let msg = self.0.write_lock().read_message()?.into_data();
+		self.1.write_lock().add_received(msg.len());
+		if let Some(capture) = &mut *self.2.write_lock() {
+			capture.response(&msg);
+		}
 
 		let mut res = Response::new();
-		res.merge_from_bytes(msg.into_data().as_slice())?;
+		res.merge_from_bytes(msg.as_slice())?;
 		Ok(res)
 	}
+
+	fn round_trip(&self, req: &Request) -> SC2Result<Vec<u8>> {
+		let bytes = req.write_to_bytes()?;
+		let sent = bytes.len();
+		let mut capture = self.2.write_lock();
+		if let Some(capture) = &mut *capture {
+			capture.request(&bytes);
+		}
+
+		let mut ws = self.0.write_lock();
+		let start = Instant::now();
+		ws.write_message(Binary(bytes))?;
+		let msg = ws.read_message()?.into_data();
+		let elapsed = start.elapsed();
+
+		let mut metrics = self.1.write_lock();
+		metrics.add_sent(sent);
+		metrics.add_received(msg.len());
+		metrics.add_latency(request_kind(req), elapsed);
+		if let Some(capture) = &mut *capture {
+			capture.response(&msg);
+		}
+		Ok(msg)
+	}
+}
+
+/// Errors returned by SC2 in the [`Response`] to a request sent with
+/// [`send_checked`](API::send_checked) or [`send_raw`](crate::bot::Bot::send_raw).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseError(pub Vec<String>);
+impl fmt::Display for ResponseError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "SC2 responded with errors: {}", self.0.join("; "))
+	}
 }
+impl Error for ResponseError {}
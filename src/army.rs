@@ -0,0 +1,261 @@
+//! Estimation of army strength.
+//!
+//! Enemy army is estimated by visible enemy combat units and units seen earlier,
+//! which contribute less the longer they aren't seen:
+//! ```ignore
+//! let enemy = self.enemy_army_value();
+//! let my = self.army_value(&self.units.my.units);
+//! if my.total() > enemy.total() * 1.3 {
+//!     // Attack
+//! }
+//! ```
+//!
+//! [`Units::composition`] gives more detailed view, e.g. to pick counters:
+//! ```ignore
+//! let enemy = self.enemy_army_composition();
+//! if enemy.count(UnitTypeId::Banshee) > 0 && !self.my_army_composition().detection {
+//!     // Build detection
+//! }
+//! ```
+
+use crate::{bot::Bot, game_data::Cost, ids::UnitTypeId, unit::Unit, units::Units, Event};
+use rustc_hash::FxHashMap;
+use std::{
+	iter::FromIterator,
+	ops::{Add, AddAssign},
+};
+
+/// Default value of [`decay_time`](EnemyArmyMemory::decay_time).
+const DECAY_TIME: f32 = 60.0;
+
+/// Units without weapons, which are still part of army.
+pub const SUPPORT_UNITS: [UnitTypeId; 18] = [
+	UnitTypeId::Medivac,
+	UnitTypeId::Raven,
+	UnitTypeId::WidowMine,
+	UnitTypeId::WidowMineBurrowed,
+	UnitTypeId::Observer,
+	UnitTypeId::ObserverSiegeMode,
+	UnitTypeId::WarpPrism,
+	UnitTypeId::WarpPrismPhasing,
+	UnitTypeId::HighTemplar,
+	UnitTypeId::Disruptor,
+	UnitTypeId::DisruptorPhased,
+	UnitTypeId::Overseer,
+	UnitTypeId::OverseerSiegeMode,
+	UnitTypeId::Infestor,
+	UnitTypeId::InfestorBurrowed,
+	UnitTypeId::SwarmHostMP,
+	UnitTypeId::SwarmHostBurrowedMP,
+	UnitTypeId::Viper,
+];
+
+/// Checks if unit is part of army, i.e. it's not a worker or structure and it can attack or support.
+/// Hallucinations are not counted.
+pub fn is_army_unit(u: &Unit) -> bool {
+	!(u.is_worker() || u.is_structure() || u.is_hallucination())
+		&& (u.can_attack() || SUPPORT_UNITS.contains(&u.type_id()))
+}
+
+/// Summary value of army.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ArmyValue {
+	/// Minerals spent on units.
+	pub minerals: f32,
+	/// Vespene spent on units.
+	pub vespene: f32,
+	/// Supply used by units.
+	pub supply: f32,
+	/// Number of units (remembered units are counted with their weights).
+	pub count: f32,
+}
+impl ArmyValue {
+	/// Total resources (minerals and vespene) spent on units.
+	pub fn total(&self) -> f32 {
+		self.minerals + self.vespene
+	}
+	fn add_weighted(&mut self, cost: Cost, weight: f32) {
+		self.minerals += cost.minerals as f32 * weight;
+		self.vespene += cost.vespene as f32 * weight;
+		self.supply += cost.supply * weight;
+		self.count += weight;
+	}
+}
+impl Add for ArmyValue {
+	type Output = Self;
+
+	fn add(self, other: Self) -> Self {
+		Self {
+			minerals: self.minerals + other.minerals,
+			vespene: self.vespene + other.vespene,
+			supply: self.supply + other.supply,
+			count: self.count + other.count,
+		}
+	}
+}
+impl AddAssign for ArmyValue {
+	fn add_assign(&mut self, other: Self) {
+		*self = *self + other;
+	}
+}
+
+/// Composition of units, returned by [`Units::composition`].
+#[derive(Debug, Clone, Default)]
+pub struct Composition {
+	/// Count and value of units of each type.
+	pub types: FxHashMap<UnitTypeId, ArmyValue>,
+	/// Summary value of all units.
+	pub value: ArmyValue,
+	/// Summary dps of all units against ground targets, including upgrades.
+	pub ground_dps: f32,
+	/// Summary dps of all units against air targets, including upgrades.
+	pub air_dps: f32,
+	/// At least one of units is detector.
+	pub detection: bool,
+}
+impl Composition {
+	/// Returns number of units of given type.
+	pub fn count(&self, type_id: UnitTypeId) -> usize {
+		self.types.get(&type_id).map_or(0, |value| value.count as usize)
+	}
+	/// Returns value of units of given type.
+	pub fn value_of(&self, type_id: UnitTypeId) -> ArmyValue {
+		self.types.get(&type_id).copied().unwrap_or_default()
+	}
+	/// Adds unit to the composition.
+	pub fn add(&mut self, u: &Unit) {
+		let cost = u.cost();
+		self.types.entry(u.type_id()).or_default().add_weighted(cost, 1.0);
+		self.value.add_weighted(cost, 1.0);
+		self.ground_dps += u.real_ground_weapon(&[]).0;
+		self.air_dps += u.real_air_weapon(&[]).0;
+		self.detection |= u.is_detector();
+	}
+}
+impl<'a> FromIterator<&'a Unit> for Composition {
+	fn from_iter<I: IntoIterator<Item = &'a Unit>>(iter: I) -> Self {
+		let mut composition = Self::default();
+		for u in iter {
+			composition.add(u);
+		}
+		composition
+	}
+}
+
+/// Enemy army units seen during the game.
+///
+/// Unit is forgotten when it dies. Units, which aren't visible now,
+/// are counted in [`enemy_army_value`](Bot::enemy_army_value) with weight decreasing
+/// from `1` to `0` during [`decay_time`](Self::decay_time) since they were seen last time.
+#[derive(Debug, Clone)]
+pub struct EnemyArmyMemory {
+	// Tag -> (type, last seen time)
+	units: FxHashMap<u64, (UnitTypeId, f32)>,
+	/// Time in seconds, after which unseen unit stops contributing to army value. [Default: `60.0`]
+	pub decay_time: f32,
+}
+impl Default for EnemyArmyMemory {
+	fn default() -> Self {
+		Self {
+			units: Default::default(),
+			decay_time: DECAY_TIME,
+		}
+	}
+}
+impl EnemyArmyMemory {
+	/// Returns type and last seen time (in seconds) of remembered unit with given tag.
+	pub fn get(&self, tag: u64) -> Option<(UnitTypeId, f32)> {
+		self.units.get(&tag).copied()
+	}
+	/// Number of remembered units.
+	pub fn len(&self) -> usize {
+		self.units.len()
+	}
+	/// Checks if there're no remembered units.
+	pub fn is_empty(&self) -> bool {
+		self.units.is_empty()
+	}
+	/// Forgets all remembered units.
+	pub fn clear(&mut self) {
+		self.units.clear();
+	}
+}
+
+impl Bot {
+	/// Returns value of given units, counting only [army units](is_army_unit).
+	pub fn army_value(&self, units: &Units) -> ArmyValue {
+		let mut value = ArmyValue::default();
+		for u in units.iter().filter(|u| is_army_unit(u)) {
+			value.add_weighted(self.unit_value(u.type_id()), 1.0);
+		}
+		value
+	}
+	/// Returns estimated value of enemy army: visible enemy army units
+	/// and remembered ones, weighted by time since they were seen (see [`EnemyArmyMemory`]).
+	pub fn enemy_army_value(&self) -> ArmyValue {
+		let memory = &self.enemy_army;
+		let mut value = ArmyValue::default();
+		for (type_id, last_seen) in memory.units.values() {
+			let weight = if memory.decay_time > 0.0 {
+				1.0 - (self.time - last_seen) / memory.decay_time
+			} else if self.time > *last_seen {
+				0.0
+			} else {
+				1.0
+			};
+			if weight > 0.0 {
+				value.add_weighted(self.unit_value(*type_id), weight.min(1.0));
+			}
+		}
+		value
+	}
+	/// Returns composition of own [army units](is_army_unit).
+	pub fn my_army_composition(&self) -> Composition {
+		self.units.my.units.iter().filter(|u| is_army_unit(u)).collect()
+	}
+	/// Returns composition of currently visible enemy [army units](is_army_unit).
+	/// Use [`enemy_army_value`](Self::enemy_army_value) to count remembered units too.
+	pub fn enemy_army_composition(&self) -> Composition {
+		self.units
+			.enemy
+			.units
+			.iter()
+			.filter(|u| is_army_unit(u))
+			.collect()
+	}
+	/// Returns remembered enemy army units.
+	pub fn enemy_army_memory(&self) -> &EnemyArmyMemory {
+		&self.enemy_army
+	}
+	/// Returns mutable reference to remembered enemy army units, e.g. to change decay time.
+	pub fn enemy_army_memory_mut(&mut self) -> &mut EnemyArmyMemory {
+		&mut self.enemy_army
+	}
+
+	// Units, which have zero cost in game data (e.g. sieged tank), take cost of their base type
+	fn unit_value(&self, type_id: UnitTypeId) -> Cost {
+		let cost = self.get_unit_api_cost(type_id);
+		if cost.minerals != 0 || cost.vespene != 0 {
+			return cost;
+		}
+		let alias = self
+			.game_data
+			.units
+			.get(&type_id)
+			.and_then(|data| data.unit_alias);
+		alias.map_or(cost, |alias| self.get_unit_api_cost(alias))
+	}
+
+	pub(crate) fn update_enemy_army(&mut self, events: &[Event]) {
+		let time = self.time;
+		let memory = &mut self.enemy_army;
+		for e in events {
+			if let Event::UnitDestroyed(tag, _) = e {
+				memory.units.remove(tag);
+			}
+		}
+		for u in self.units.enemy.units.iter().filter(|u| is_army_unit(u)) {
+			memory.units.insert(u.tag(), (u.type_id(), time));
+		}
+	}
+}
@@ -0,0 +1,174 @@
+//! Stalker blink micro: blinking back with broken shields, chasing and jumping over cliffs.
+//!
+//! All helpers return `true` if blink was ordered, so they can be chained with
+//! default behavior:
+//! ```ignore
+//! for stalker in self.units.my.units.of_type(UnitTypeId::Stalker) {
+//!     if !self.blink_back(&stalker) {
+//!         stalker.attack(Target::Pos(self.enemy_start), false);
+//!     }
+//! }
+//! ```
+
+use crate::{
+	action::Target,
+	bot::Bot,
+	distance::*,
+	geometry::Point2,
+	ids::{AbilityId, UnitTypeId, UpgradeId},
+	pixel_map::{ByteMap, PixelMap},
+	unit::Unit,
+};
+use std::f32::consts::TAU;
+
+/// Maximum distance of Stalker's blink.
+pub const BLINK_RANGE: f32 = 8.0;
+/// Minimum difference in terrain height between the sides of cliff.
+const CLIFF_HEIGHT: u8 = 10;
+/// Number of directions checked from each cliff edge tile.
+const EDGE_DIRECTIONS: usize = 16;
+/// Deviations from the best direction (in radians), tried when it leads to unpathable tile.
+const DEVIATIONS: [f32; 7] = [0.0, 0.4, -0.4, 0.8, -0.8, 1.2, -1.2];
+
+/// Finds pairs of pathable tiles on the opposite sides of cliffs,
+/// which are in blink range from each other.
+pub(crate) fn find_blink_edges(pathing: &PixelMap, height: &ByteMap) -> Vec<(Point2, Point2)> {
+	let (width, depth) = pathing.dim();
+	// Negative coordinates wrap around and are out of the map
+	let is_pathable = |x: isize, y: isize| {
+		pathing
+			.get((x as usize, y as usize))
+			.is_some_and(|p| p.is_empty())
+	};
+
+	let mut edges = Vec::new();
+	for (x, y) in iproduct!((0..width).step_by(2), (0..depth).step_by(2)) {
+		let (ix, iy) = (x as isize, y as isize);
+		if !is_pathable(ix, iy) {
+			continue;
+		}
+		let on_edge = iproduct!(-1..=1, -1..=1).any(|(dx, dy)| !is_pathable(ix + dx, iy + dy));
+		if !on_edge {
+			continue;
+		}
+
+		let from = Point2::new(x as f32 + 0.5, y as f32 + 0.5);
+		let from_height = height[(x, y)];
+		for i in 0..EDGE_DIRECTIONS {
+			let angle = i as f32 * TAU / EDGE_DIRECTIONS as f32;
+			let mut crossed_obstacle = false;
+			for distance in 1..=(BLINK_RANGE as usize) {
+				let to = from.towards_angle(angle, distance as f32);
+				let (tx, ty) = (to.x.floor() as isize, to.y.floor() as isize);
+				if !is_pathable(tx, ty) {
+					crossed_obstacle = true;
+					continue;
+				}
+				if crossed_obstacle {
+					let to_height = height[(tx as usize, ty as usize)];
+					if from_height.abs_diff(to_height) >= CLIFF_HEIGHT {
+						edges.push((from, Point2::new(tx as f32 + 0.5, ty as f32 + 0.5)));
+					}
+					break;
+				}
+			}
+		}
+	}
+	edges
+}
+
+impl Bot {
+	/// Checks if given unit can blink now: it's own Stalker, Blink is researched
+	/// and the ability is off cooldown.
+	///
+	/// Cooldown is checked with [`Unit::has_ability`], so Stalkers should be
+	/// in [`abilities_scope`](Self::abilities_scope) or queried with
+	/// [`query_abilities`](Self::query_abilities).
+	pub fn can_blink(&self, u: &Unit) -> bool {
+		u.type_id() == UnitTypeId::Stalker
+			&& u.is_mine()
+			&& self.has_upgrade(UpgradeId::BlinkTech)
+			&& u.has_ability(AbilityId::EffectBlinkStalker)
+	}
+	/// Pairs of pathable positions on the opposite sides of cliffs,
+	/// which are in blink range from each other. Calculated once on game start.
+	pub fn blink_edges(&self) -> &[(Point2, Point2)] {
+		&self.blink_edges
+	}
+	/// Blinks given Stalker away from the closest enemy, which can attack it,
+	/// if its shields are broken.
+	pub fn blink_back(&self, u: &Unit) -> bool {
+		if u.shield().map_or(true, |shield| shield > 0) || !self.can_blink(u) {
+			return false;
+		}
+		let threat = match self
+			.units
+			.enemy
+			.all
+			.iter()
+			.filter(|t| t.can_attack_ground() && u.in_real_range_of(t, 2.0))
+			.closest(u)
+		{
+			Some(threat) => threat.position(),
+			None => return false,
+		};
+		let pos = u.position();
+		let away = if pos == threat {
+			pos.towards(self.start_location, BLINK_RANGE)
+		} else {
+			pos.towards(threat, -BLINK_RANGE)
+		};
+		self.blink_towards(u, away)
+	}
+	/// Blinks given Stalker to the target, if target is out of Stalker's range,
+	/// but can be reached with blink.
+	pub fn blink_forward(&self, u: &Unit, target: &Unit) -> bool {
+		let range = u.real_range_vs(target) + u.radius() + target.radius();
+		let distance = u.distance(target);
+		if distance <= range || distance > range + BLINK_RANGE || !self.can_blink(u) {
+			return false;
+		}
+		let pos = u.position();
+		self.blink_towards(u, pos.towards(target.position(), distance - range + 1.0))
+	}
+	/// Blinks given Stalker up or down the cliff, if it stands near the cliff edge
+	/// and the other side of the cliff is closer to given goal.
+	pub fn blink_over_cliff(&self, u: &Unit, goal: Point2) -> bool {
+		if !self.can_blink(u) {
+			return false;
+		}
+		let pos = u.position();
+		let current = pos.distance_squared(goal);
+		let edge = self
+			.blink_edges
+			.iter()
+			.filter(|(from, to)| from.is_closer(2.0, pos) && to.distance_squared(goal) < current)
+			.min_by(|(_, a), (_, b)| {
+				a.distance_squared(goal)
+					.partial_cmp(&b.distance_squared(goal))
+					.unwrap()
+			});
+		match edge {
+			Some((_, to)) => {
+				u.command(AbilityId::EffectBlinkStalker, Target::Pos(*to), false);
+				true
+			}
+			None => false,
+		}
+	}
+	fn blink_towards(&self, u: &Unit, target: Point2) -> bool {
+		let pos = u.position();
+		let direction = target - pos;
+		match DEVIATIONS
+			.iter()
+			.map(|angle| pos + direction.rotate(*angle))
+			.find(|p| self.is_pathable(*p))
+		{
+			Some(p) => {
+				u.command(AbilityId::EffectBlinkStalker, Target::Pos(p), false);
+				true
+			}
+			None => false,
+		}
+	}
+}
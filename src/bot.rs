@@ -2,35 +2,67 @@
 
 use crate::{
 	action::{Action, ActionResult, Commander, Target},
+	action_log::ActionLog,
 	api::API,
+	army::EnemyArmyMemory,
+	blink::find_blink_edges,
 	client::SC2Result,
-	consts::{RaceValues, FRAMES_PER_SECOND, INHIBITOR_IDS, RACE_VALUES, TECH_ALIAS, UNIT_ALIAS},
+	consts::{
+		RaceValues, ABILITY_UNITS, ABILITY_UPGRADES, ENERGY_COSTS, FRAMES_PER_SECOND, INHIBITOR_IDS,
+		RACE_VALUES, TECH_ALIAS, UNIT_ALIAS, UNIT_FORMS,
+	},
 	debug::{DebugCommand, Debugger},
 	distance::*,
+	enemy_build::EnemyIntel,
 	game_data::{Cost, GameData},
 	game_info::GameInfo,
 	game_state::Effect,
 	game_state::{Alliance, GameState},
 	geometry::{Point2, Point3},
-	ids::{AbilityId, EffectId, UnitTypeId, UpgradeId},
-	player::Race,
+	hidden_enemies::HiddenEnemies,
+	ids::{AbilityId, BuffId, EffectId, UnitTypeId, UpgradeId},
+	modules::{auto_burrow::AutoBurrow, auto_gg::AutoGg, auto_repair::AutoRepair, speed_mining::SpeedMining},
+	perf::PerfStats,
+	pixel_map::PixelMap,
+	plugins::Plugins,
+	placement::{PlacementMap, ADDON_OFFSET, ADDON_STRUCTURES, PRODUCTION_STRUCTURES},
+	player::{GameSummary, Race},
+	query::{abilities_request, abilities_result, pathing_request, pathing_result, placement_request},
 	ramp::{Ramp, Ramps},
+	reservation::Reservation,
+	roles::Role,
+	supply::SupplyManager,
+	timers::Timer,
 	unit::{DataForUnit, SharedUnitData, Unit},
-	units::{AllUnits, Units},
+	units::{iter::UnitsIterator, AllUnits, Units},
 	utils::{dbscan, range_query},
-	FromProto, IntoProto,
+	wall::{self, NaturalWall},
+	workers::WorkerManager,
+	FromProto,
 };
 use indexmap::IndexSet;
-use num_traits::ToPrimitive;
+use ndarray::Array2;
 use rand::prelude::*;
 use rustc_hash::{FxHashMap, FxHashSet, FxHasher};
-use sc2_proto::{
-	query::{RequestQueryBuildingPlacement, RequestQueryPathing},
-	sc2api::Request,
+use sc2_proto::sc2api::{Request, Response};
+use std::{
+	fmt,
+	hash::BuildHasherDefault,
+	ops::{Deref, DerefMut},
+	process::Child,
 };
-use std::{fmt, hash::BuildHasherDefault, process::Child};
 
 type FxIndexSet<T> = IndexSet<T, BuildHasherDefault<FxHasher>>;
+// (ability, bits of position coordinates, builder, check resources)
+pub(crate) type PlacementKey = (AbilityId, (u32, u32), Option<u64>, bool);
+
+pub(crate) fn placement_key(
+	(ability, pos, builder): (AbilityId, Point2, Option<u64>),
+	check_resources: bool,
+) -> PlacementKey {
+	let pos = (pos.x.to_bits(), pos.y.to_bits());
+	(ability, pos, builder, check_resources)
+}
 
 #[cfg(feature = "enemies_cache")]
 use crate::{consts::BURROWED_IDS, unit::DisplayType};
@@ -189,6 +221,92 @@ pub struct Expansion {
 	/// Tag of townhall placed on the expansion. (Only for occupied ones)
 	pub base: Option<u64>,
 }
+impl Expansion {
+	/// Checks if expansion is free.
+	pub fn is_free(&self) -> bool {
+		self.alliance.is_neutral()
+	}
+	/// Checks if expansion is taken by you.
+	pub fn is_mine(&self) -> bool {
+		self.alliance.is_mine()
+	}
+	/// Checks if expansion is taken by opponent.
+	pub fn is_enemy(&self) -> bool {
+		self.alliance.is_enemy()
+	}
+}
+
+/// Collection of all expansions on the map, sorted by pathing distance to bot's start location.
+/// Stored in [`expansions`](Bot::expansions) field of bot and updated each step.
+///
+/// Dereferences to `Vec<Expansion>`, so it can be indexed and iterated as usual.
+#[derive(Debug, Clone, Default)]
+pub struct Expansions(Vec<Expansion>);
+impl Expansions {
+	/// Returns iterator over free expansions.
+	pub fn free(&self) -> impl Iterator<Item = &Expansion> {
+		self.0.iter().filter(|exp| exp.is_free())
+	}
+	/// Returns iterator over expansions taken by you.
+	pub fn mine(&self) -> impl Iterator<Item = &Expansion> {
+		self.0.iter().filter(|exp| exp.is_mine())
+	}
+	/// Returns iterator over expansions taken by opponent.
+	pub fn enemy(&self) -> impl Iterator<Item = &Expansion> {
+		self.0.iter().filter(|exp| exp.is_enemy())
+	}
+	/// Returns expansion which has given townhall tag as its base.
+	pub fn of_base(&self, base: u64) -> Option<&Expansion> {
+		self.0.iter().find(|exp| exp.base == Some(base))
+	}
+	/// Returns expansion, which owns mineral field or vespene geyser with given tag.
+	pub fn of_resource(&self, tag: u64) -> Option<&Expansion> {
+		self.0
+			.iter()
+			.find(|exp| exp.minerals.contains(&tag) || exp.geysers.contains(&tag))
+	}
+}
+impl From<Vec<Expansion>> for Expansions {
+	fn from(expansions: Vec<Expansion>) -> Self {
+		Self(expansions)
+	}
+}
+impl Deref for Expansions {
+	type Target = Vec<Expansion>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+impl DerefMut for Expansions {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+impl<'a> IntoIterator for &'a Expansions {
+	type Item = &'a Expansion;
+	type IntoIter = std::slice::Iter<'a, Expansion>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.iter()
+	}
+}
+impl<'a> IntoIterator for &'a mut Expansions {
+	type Item = &'a mut Expansion;
+	type IntoIter = std::slice::IterMut<'a, Expansion>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.iter_mut()
+	}
+}
+impl IntoIterator for Expansions {
+	type Item = Expansion;
+	type IntoIter = std::vec::IntoIter<Expansion>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.into_iter()
+	}
+}
 
 /// Additional options for [`find_placement`](Bot::find_placement).
 #[derive(Clone, Copy)]
@@ -201,6 +319,16 @@ pub struct PlacementOptions {
 	pub random: bool,
 	/// Filter positions where addon can fit. [Default: `false`]
 	pub addon: bool,
+	/// Don't place buildings on free expansion locations,
+	/// ignored when placing townhalls. [Default: `false`]
+	pub avoid_expansions: bool,
+	/// Minimum gap in tiles between production structures
+	/// (barracks, factories, starports, gateways, stargates and robotics facilities),
+	/// so units can walk out of them freely. [Default: `0`]
+	pub production_gap: usize,
+	/// Keep addon space for all terran production structures,
+	/// both existing and new ones. [Default: `false`]
+	pub terran_addons: bool,
 }
 impl Default for PlacementOptions {
 	fn default() -> Self {
@@ -209,6 +337,9 @@ impl Default for PlacementOptions {
 			step: 2,
 			random: false,
 			addon: false,
+			avoid_expansions: false,
+			production_gap: 0,
+			terran_addons: false,
 		}
 	}
 }
@@ -232,8 +363,10 @@ pub struct CountOptions<'a> {
 	/// - `None` - don't count alias
 	/// - `Unit` - count unit-alias, used when unit has 2 forms
 	/// - `Tech` - count tech-alias, used when unit has more than 2 forms (usually structures)
+	/// - `Auto` - count upgraded and alternative forms (e.g. lairs and hives as hatcheries),
+	///   but not vice versa
 	///
-	/// [Default: `None`]
+	/// [Default: `Auto`]
 	pub alias: UnitAlias,
 }
 impl<'a> CountOptions<'a> {
@@ -265,6 +398,11 @@ impl<'a> CountOptions<'a> {
 		self.alias = UnitAlias::Tech;
 		self
 	}
+	/// Sets alias to `None`, so only units of exactly given type are counted.
+	pub fn exact(&mut self) -> &mut Self {
+		self.alias = UnitAlias::None;
+		self
+	}
 	/// Counts units of given type and returns the result.
 	pub fn count(&self, unit_id: UnitTypeId) -> usize {
 		let bot = self.bot;
@@ -280,12 +418,7 @@ impl<'a> CountOptions<'a> {
 				if self.enemies {
 					Box::new(|id| bot.enemies_ordered.get(&id).copied().unwrap_or(0))
 				} else {
-					Box::new(|id| {
-						bot.game_data.units[&id]
-							.ability
-							.and_then(|ability| bot.orders.get(&ability).copied())
-							.unwrap_or(0)
-					})
+					Box::new(|id| bot.ordered_count(id))
 				}
 			}
 			Completion::All => {
@@ -295,13 +428,7 @@ impl<'a> CountOptions<'a> {
 							+ bot.enemies_ordered.get(&id).copied().unwrap_or(0)
 					})
 				} else {
-					Box::new(|id| {
-						bot.current_units.get(&id).copied().unwrap_or(0)
-							+ bot.game_data.units[&id]
-								.ability
-								.and_then(|ability| bot.orders.get(&ability).copied())
-								.unwrap_or(0)
-					})
+					Box::new(|id| bot.current_units.get(&id).copied().unwrap_or(0) + bot.ordered_count(id))
 				}
 			}
 		};
@@ -314,6 +441,24 @@ impl<'a> CountOptions<'a> {
 						.get(&unit_id)
 						.map_or(0, |alias| alias.iter().copied().map(count).sum::<usize>())
 			}
+			UnitAlias::Auto => {
+				// Forms are morphed from existing units, which are already counted while morphing,
+				// so only complete forms are added
+				let current = |id| {
+					if self.enemies {
+						bot.enemies_current.get(&id).copied().unwrap_or(0)
+					} else {
+						bot.current_units.get(&id).copied().unwrap_or(0)
+					}
+				};
+				let forms = match self.completion {
+					Completion::Ordered => 0,
+					_ => UNIT_FORMS
+						.get(&unit_id)
+						.map_or(0, |forms| forms.iter().copied().map(current).sum::<usize>()),
+				};
+				count(unit_id) + forms
+			}
 		}
 	}
 }
@@ -356,10 +501,14 @@ pub enum UnitAlias {
 	Unit,
 	/// Count tech-alias, used when unit has more than 2 forms (usually structures).
 	Tech,
+	/// Count upgraded and alternative forms of unit, e.g. hatcheries are counted
+	/// with lairs and hives, supply depots with lowered ones. Goes only one way,
+	/// so lairs are counted without hatcheries.
+	Auto,
 }
 impl Default for UnitAlias {
 	fn default() -> Self {
-		Self::None
+		Self::Auto
 	}
 }
 
@@ -379,6 +528,59 @@ impl Default for Completion {
 	}
 }
 
+/// Own units, for which available abilities are queried every step.
+/// Set with [`abilities_scope`](Bot::abilities_scope) field.
+///
+/// Abilities of units outside of the scope aren't known, so [`Unit::abilities`] returns `None`
+/// and [`Unit::has_ability`] returns `false` for them, unless they're queried with
+/// [`query_abilities`](Bot::query_abilities).
+#[derive(Debug, Clone, Default)]
+pub enum AbilitiesScope {
+	/// All own units and structures.
+	#[default]
+	All,
+	/// Units and structures with energy.
+	Casters,
+	/// Structures only.
+	Structures,
+	/// Don't query abilities every step.
+	/// Abilities of needed units can be queried on demand with [`query_abilities`](Bot::query_abilities).
+	None,
+	/// Units and structures of given types.
+	Types(FxHashSet<UnitTypeId>),
+	/// Units and structures with given tags.
+	Tags(FxHashSet<u64>),
+	/// Units in at least one of given scopes.
+	Any(Vec<AbilitiesScope>),
+}
+impl AbilitiesScope {
+	/// Checks if abilities of given unit are queried in this scope.
+	pub fn contains(&self, u: &Unit) -> bool {
+		match self {
+			Self::All => true,
+			Self::None => false,
+			Self::Casters => u.energy_max().is_some_and(|energy| energy > 0),
+			Self::Structures => u.is_structure(),
+			Self::Types(types) => types.contains(&u.type_id()),
+			Self::Tags(tags) => tags.contains(&u.tag()),
+			Self::Any(scopes) => scopes.iter().any(|scope| scope.contains(u)),
+		}
+	}
+}
+
+/// Result of pathing query, returned by [`query_pathing`](Bot::query_pathing).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PathingResult {
+	/// Length of path from start to goal or `None` if there's no path.
+	pub distance: Option<f32>,
+}
+impl PathingResult {
+	/// Checks if goal is reachable from start.
+	pub fn is_reachable(&self) -> bool {
+		self.distance.is_some()
+	}
+}
+
 /// Main bot struct.
 /// Structs with [`#[bot]`][b] attribute will get all it's fields and methods
 /// through [`Deref`] and [`DerefMut`] traits.
@@ -391,6 +593,7 @@ pub struct Bot {
 	pub(crate) api: Option<API>,
 	pub(crate) game_step: Rs<LockU32>,
 	pub(crate) game_left: bool,
+	pub(crate) game_summary: Option<GameSummary>,
 	#[doc(hidden)]
 	pub disable_fog: bool,
 	/// Actual race of your bot.
@@ -403,7 +606,7 @@ pub struct Bot {
 	pub enemy_player_id: u32,
 	/// Opponent id on ladder, filled in `--OpponentId`.
 	pub opponent_id: String,
-	actions: Vec<Action>,
+	pub(crate) actions: Vec<Action>,
 	commander: Rw<Commander>,
 	/// Debug API
 	pub debug: Debugger,
@@ -419,6 +622,9 @@ pub struct Bot {
 	/// Structured collection of units.
 	pub units: AllUnits,
 	pub(crate) abilities_units: Rw<FxHashMap<u64, FxHashSet<AbilityId>>>,
+	/// Units, for which available abilities are queried every step.
+	/// Querying fewer units reduces step time with big armies. [Default: `All`]
+	pub abilities_scope: AbilitiesScope,
 	/// Orders of owned units counted by ability.
 	pub orders: FxHashMap<AbilityId, usize>,
 	/// Ready owned units counted by unit type.
@@ -455,13 +661,35 @@ pub struct Bot {
 	techlab_tags: Rw<FxHashSet<u64>>,
 	reactor_tags: Rw<FxHashSet<u64>>,
 	/// All expansions.
-	pub expansions: Vec<Expansion>,
+	pub expansions: Expansions,
 	max_cooldowns: Rw<FxHashMap<UnitTypeId, f32>>,
 	last_units_health: Rw<FxHashMap<u64, u32>>,
+	buffs_appeared: Rw<FxHashMap<(u64, BuffId), u32>>,
 	/// Obstacles on map which block vision of ground units, but still pathable.
 	pub vision_blockers: Vec<Point2>,
 	/// Ramps on map.
 	pub ramps: Ramps,
+	/// Settings of automatic supply management, disabled by default.
+	pub supply_manager: SupplyManager,
+	/// Settings of automatic worker distribution, disabled by default.
+	pub worker_manager: WorkerManager,
+	pub(crate) speed_mining: Option<SpeedMining>,
+	pub(crate) auto_repair: Option<AutoRepair>,
+	pub(crate) auto_burrow: Option<AutoBurrow>,
+	pub(crate) auto_gg: Option<AutoGg>,
+	pub(crate) plugins: Plugins,
+	pub(crate) timers: Vec<Timer>,
+	pub(crate) enemy_intel: EnemyIntel,
+	pub(crate) hidden_enemies: HiddenEnemies,
+	pub(crate) enemy_army: EnemyArmyMemory,
+	pub(crate) action_log: Option<ActionLog>,
+	pub(crate) perf: PerfStats,
+	pub(crate) reservations: Vec<Reservation>,
+	pub(crate) roles: FxHashMap<u64, Role>,
+	last_seen: Array2<Option<u32>>,
+	power_map: Array2<bool>,
+	pub(crate) blink_edges: Vec<(Point2, Point2)>,
+	pub(crate) placement_cache: Rw<FxHashMap<PlacementKey, ActionResult>>,
 	enemy_upgrades: Rw<FxHashSet<UpgradeId>>,
 	pub(crate) owned_tags: FxHashSet<u64>,
 	pub(crate) under_construction: FxHashSet<u64>,
@@ -470,10 +698,20 @@ pub struct Bot {
 
 impl Bot {
 	/// Interface for interacting with SC2 API through Request/Response.
+	///
+	/// Can be used to access protocol features which aren't wrapped by the crate yet.
+	/// Panics if called before the game is joined.
 	#[inline]
 	pub fn api(&self) -> &API {
 		self.api.as_ref().expect("API is not initialized")
 	}
+	/// Sends raw protobuf request to SC2 and returns the response.
+	///
+	/// Returns [`ResponseError`](crate::api::ResponseError) if SC2 reported errors in the response.
+	/// Useful for protocol features which aren't wrapped by the crate yet.
+	pub fn send_raw(&self, req: Request) -> SC2Result<Response> {
+		self.api().send_checked(req)
+	}
 	/// Sets step between every [`on_step`] iteration
 	/// (e.g. on `1` [`on_step`] will be called every frame, on `2` every second frame, ...).
 	/// Must be bigger than `0`.
@@ -486,14 +724,32 @@ impl Bot {
 	pub fn game_step(&self) -> u32 {
 		self.game_step.get_locked()
 	}
+	/// Returns summary of the last finished game, available from [`on_end`] call.
+	///
+	/// [`on_end`]: crate::Player::on_end
+	pub fn game_summary(&self) -> Option<&GameSummary> {
+		self.game_summary.as_ref()
+	}
 	/// Constructs new [`CountOptions`], used to count units fast and easy.
 	///
+	/// Ordered units include all units in production: queued orders of structures,
+	/// eggs and cocoons, morphing units and structures, warping in units and
+	/// structures under construction (including ones, whose workers are still moving to build).
+	/// Zergling eggs are counted as 2 zerglings.
+	///
 	/// # Examples
 	/// Count all ready marines:
 	/// ```
 	/// let count = self.counter().count(UnitTypeId::Marine);
 	/// ```
 	///
+	/// By default alternative forms of units are counted too
+	/// (e.g. lowered supply depots are counted as supply depots, lairs and hives as hatcheries).
+	/// Count only units of exactly given type:
+	/// ```
+	/// let count = self.counter().exact().count(UnitTypeId::Hatchery);
+	/// ```
+	///
 	/// Count all supplies in progress:
 	/// ```
 	/// let count = self.counter().ordered().count(UnitTypeId::SupplyDepot);
@@ -516,6 +772,26 @@ impl Bot {
 	pub fn counter(&self) -> CountOptions {
 		CountOptions::new(self, false)
 	}
+	/// Returns number of own units of given type in production, the same as
+	/// `self.counter().ordered().count(unit)`.
+	pub fn already_pending(&self, unit: UnitTypeId) -> usize {
+		self.ordered_count(unit)
+	}
+	// Number of ordered units of given type, see `counter` for details
+	fn ordered_count(&self, unit: UnitTypeId) -> usize {
+		let orders = self
+			.game_data
+			.units
+			.get(&unit)
+			.and_then(|data| data.ability)
+			.and_then(|ability| self.orders.get(&ability).copied())
+			.unwrap_or(0);
+		if unit == UnitTypeId::Zergling {
+			orders * 2
+		} else {
+			orders
+		}
+	}
 	/// The same as [`counter`](Self::counter), but counts enemy units instead.
 	///
 	/// All information about enemy units count is based on scouting.
@@ -523,6 +799,35 @@ impl Bot {
 	pub fn enemy_counter(&self) -> CountOptions {
 		CountOptions::new(self, true)
 	}
+	/// Enables or disables filter of repeated orders.
+	///
+	/// When enabled, commands which aren't queued are dropped if unit is already executing
+	/// the same ability with the same target. Positions of targets are considered the same
+	/// when they're closer than given tolerance. This saves APM and prevents order flickering,
+	/// which can cancel attack animation. [Default: `None`]
+	pub fn set_order_filter(&self, tolerance: Option<f32>) {
+		self.commander.write_lock().order_filter = tolerance;
+	}
+	/// Returns tolerance of the filter of repeated orders, or `None` if filter is disabled.
+	pub fn order_filter(&self) -> Option<f32> {
+		self.commander.read_lock().order_filter
+	}
+	/// Sets minimum number of frames between commands with the same ability to the same unit.
+	///
+	/// Commands issued earlier are dropped, queued commands are never throttled.
+	/// Useful when micro runs every step with small `game_step`, so units
+	/// don't get their orders reset too often. `0` disables throttling. [Default: `0`]
+	pub fn set_command_throttle(&self, frames: u32) {
+		let mut commander = self.commander.write_lock();
+		commander.throttle = frames;
+		if frames == 0 {
+			commander.last_commands.clear();
+		}
+	}
+	/// Returns minimum number of frames between commands with the same ability to the same unit.
+	pub fn command_throttle(&self) -> u32 {
+		self.commander.read_lock().throttle
+	}
 	pub(crate) fn get_actions(&mut self) -> &[Action] {
 		let actions = &mut self.actions;
 
@@ -546,8 +851,19 @@ impl Bot {
 					.map(|(ability, units)| Action::ToggleAutocast(ability, units)),
 			);
 		}
+		commander.damage.clear();
+		if commander.throttle > 0 {
+			let game_loop = self.state.observation.game_loop();
+			let throttle = commander.throttle;
+			commander
+				.last_commands
+				.retain(|_, issued| game_loop < *issued + throttle);
+		}
+		drop(commander);
 
-		actions
+		self.perf.actions += self.actions.len() as u64;
+		self.log_actions();
+		&self.actions
 	}
 	pub(crate) fn clear_actions(&mut self) {
 		self.actions.clear();
@@ -614,12 +930,14 @@ impl Bot {
 		cost
 	}
 	/// Checks if bot has enough resources and supply to build given unit type.
+	///
+	/// Resources and supply [reserved](Self::reserve) for other purposes are not counted.
 	pub fn can_afford(&self, unit: UnitTypeId, check_supply: bool) -> bool {
 		let cost = self.get_unit_cost(unit);
-		if self.minerals < cost.minerals || self.vespene < cost.vespene {
+		if self.available_minerals() < cost.minerals || self.available_vespene() < cost.vespene {
 			return false;
 		}
-		if check_supply && (self.supply_left as f32) < cost.supply {
+		if check_supply && self.available_supply() < cost.supply {
 			return false;
 		}
 		true
@@ -632,15 +950,44 @@ impl Bot {
 			.map_or_else(Default::default, |data| data.cost())
 	}
 	/// Checks if bot has enough resources to make given upgrade.
+	///
+	/// Resources [reserved](Self::reserve) for other purposes are not counted.
 	pub fn can_afford_upgrade(&self, upgrade: UpgradeId) -> bool {
 		let cost = self.get_upgrade_cost(upgrade);
-		self.minerals >= cost.minerals && self.vespene >= cost.vespene
+		self.available_minerals() >= cost.minerals && self.available_vespene() >= cost.vespene
+	}
+	/// Returns cost of using given ability in resources and supply.
+	///
+	/// Abilities which train, build or morph units cost the same as the unit
+	/// (see [`ABILITY_UNITS`]), research abilities cost the same as the upgrade
+	/// (see [`ABILITY_UPGRADES`]), other abilities are free
+	/// (their energy cost can be found in [`ENERGY_COSTS`]).
+	pub fn get_ability_cost(&self, ability: AbilityId) -> Cost {
+		if let Some(&unit) = ABILITY_UNITS.get(&ability) {
+			return self.get_unit_cost(unit);
+		}
+		ABILITY_UPGRADES
+			.get(&ability)
+			.and_then(|upgrade| self.game_data.upgrades.get(upgrade))
+			.map_or_else(Cost::default, |data| data.cost())
 	}
-	/*
-	fn can_afford_ability(&self, ability: AbilityId) -> bool {
-		unimplemented!()
+	/// Checks if bot has enough resources and supply for given unit to use given ability
+	/// (e.g. morphs, calldowns or spells).
+	///
+	/// For abilities which cost energy also checks if the caster has enough energy.
+	pub fn can_afford_ability(&self, ability: AbilityId, caster: &Unit) -> bool {
+		let cost = self.get_ability_cost(ability);
+		if self.available_minerals() < cost.minerals || self.available_vespene() < cost.vespene {
+			return false;
+		}
+		if self.available_supply() < cost.supply {
+			return false;
+		}
+		match ENERGY_COSTS.get(&ability) {
+			Some(&energy) => caster.energy().is_some_and(|e| e >= energy),
+			None => true,
+		}
 	}
-	*/
 	/// Subtracts cost of given unit type from [`minerals`],
 	/// [`vespene`], [`supply_left`] and adds to [`supply_used`].
 	///
@@ -781,6 +1128,24 @@ impl Bot {
 			.get(pos.into())
 			.map_or(false, |p| p.is_visible())
 	}
+	/// Checks if given position is covered by detection of known enemy detectors
+	/// or scanner sweeps, extended by given gap.
+	pub fn is_detected(&self, pos: Point2, gap: f32) -> bool {
+		self.units
+			.enemy
+			.all
+			.iter()
+			.filter(|u| u.is_detector())
+			.any(|d| d.is_closer(d.radius() + d.detect_range() + gap, pos))
+			|| self
+				.state
+				.observation
+				.raw
+				.effects
+				.iter()
+				.filter(|e| e.id == EffectId::ScannerSweep && e.alliance.is_enemy())
+				.any(|e| e.positions.iter().any(|p| p.is_closer(e.radius + gap, pos)))
+	}
 	/// Checks if given position is fully hidden
 	/// (terrain isn't visible, only darkness; only in campain and custom maps).
 	pub fn is_full_hidden<P: Into<(usize, usize)>>(&self, pos: P) -> bool {
@@ -791,6 +1156,95 @@ impl Bot {
 			.get(pos.into())
 			.map_or(true, |p| p.is_full_hidden())
 	}
+	/// Returns game loop, when given position was visible last time,
+	/// or `None` if it was never visible.
+	pub fn last_seen<P: Into<(usize, usize)>>(&self, pos: P) -> Option<u32> {
+		self.last_seen.get(pos.into()).copied().flatten()
+	}
+	/// Returns number of seconds passed since given position was visible last time,
+	/// or `None` if it was never visible.
+	pub fn seen_ago<P: Into<(usize, usize)>>(&self, pos: P) -> Option<f32> {
+		let game_loop = self.state.observation.game_loop();
+		self.last_seen(pos)
+			.map(|seen| game_loop.saturating_sub(seen) as f32 / FRAMES_PER_SECOND)
+	}
+	/// Map of game loops, when each position was visible last time (`None` if never).
+	pub fn last_seen_map(&self) -> &Array2<Option<u32>> {
+		&self.last_seen
+	}
+	pub(crate) fn update_last_seen(&mut self) {
+		let game_loop = self.state.observation.game_loop();
+		let visibility = &self.state.observation.raw.visibility;
+		if self.last_seen.dim() != visibility.dim() {
+			self.last_seen = Array2::from_elem(visibility.dim(), None);
+		}
+		for (seen, v) in self.last_seen.iter_mut().zip(visibility.iter()) {
+			if v.is_visible() {
+				*seen = Some(game_loop);
+			}
+		}
+	}
+	/// Checks if given position is covered by own psionic matrix (of pylon or warp prism).
+	pub fn is_powered<P: Into<(usize, usize)>>(&self, pos: P) -> bool {
+		self.power_map.get(pos.into()).copied().unwrap_or(false)
+	}
+	/// Map of positions covered by own psionic matrices, updated every step.
+	pub fn power_map(&self) -> &Array2<bool> {
+		&self.power_map
+	}
+	/// Returns positions near given point, where building of given size can be placed
+	/// and will be powered, sorted by distance to given point.
+	///
+	/// Positions are checked locally with [`placement_map`](Self::placement_map),
+	/// so they still should be validated with [`query_placement`](Self::query_placement) before use.
+	pub fn powered_placements_near(&self, pos: Point2, size: usize) -> Vec<Point2> {
+		let map = self.placement_map();
+		let offset = if size % 2 == 0 { 0.0 } else { 0.5 };
+		let mut placements = self
+			.state
+			.observation
+			.raw
+			.psionic_matrix
+			.iter()
+			.flat_map(|source| {
+				let (x0, y0) = <(usize, usize)>::from((source.pos - source.radius).floor());
+				let (x1, y1) = <(usize, usize)>::from((source.pos + source.radius).ceil());
+				iproduct!(x0..=x1, y0..=y1)
+			})
+			.collect::<FxHashSet<_>>()
+			.into_iter()
+			.map(|c| Point2::from(c).offset(offset, offset))
+			.filter(|p| self.is_powered(*p) && map.can_place(*p, size))
+			.collect::<Vec<_>>();
+		placements.sort_unstable_by(|a, b| {
+			a.distance_squared(pos)
+				.partial_cmp(&b.distance_squared(pos))
+				.unwrap()
+		});
+		placements
+	}
+	pub(crate) fn update_power_map(&mut self) {
+		let dim = self.game_info.placement_grid.dim();
+		if self.power_map.dim() != dim {
+			self.power_map = Array2::from_elem(dim, false);
+		} else {
+			self.power_map.fill(false);
+		}
+		for source in &self.state.observation.raw.psionic_matrix {
+			let (x0, y0) = <(usize, usize)>::from((source.pos - source.radius).floor());
+			let (x1, y1) = <(usize, usize)>::from((source.pos + source.radius).ceil());
+			for c in iproduct!(x0..=x1, y0..=y1) {
+				if Point2::from(c)
+					.offset(0.5, 0.5)
+					.is_closer(source.radius, source.pos)
+				{
+					if let Some(cell) = self.power_map.get_mut(c) {
+						*cell = true;
+					}
+				}
+			}
+		}
+	}
 	/// Checks if given position is not hidden (was explored before).
 	pub fn is_explored<P: Into<(usize, usize)>>(&self, pos: P) -> bool {
 		self.state
@@ -810,6 +1264,63 @@ impl Bot {
 			.get(pos.into())
 			.map_or(false, |p| p.is_empty())
 	}
+	/// Returns part of pathable map area covered with creep, from `0.0` to `1.0`.
+	pub fn creep_coverage(&self) -> f32 {
+		let creep = self.state.observation.raw.creep.read_lock();
+		let (pathable, covered) = self
+			.game_info
+			.pathing_grid
+			.indexed_iter()
+			.filter(|(_, p)| p.is_empty())
+			.fold((0, 0), |(pathable, covered), (pos, _)| {
+				let has_creep = creep.get(pos).is_some_and(|p| p.is_empty());
+				(pathable + 1, covered + has_creep as usize)
+			});
+		if pathable == 0 {
+			0.0
+		} else {
+			covered as f32 / pathable as f32
+		}
+	}
+	/// Checks if given position has creep and is next to pathable position without creep,
+	/// i.e. creep can be spread further from it.
+	pub fn is_creep_edge<P: Into<(usize, usize)>>(&self, pos: P) -> bool {
+		let creep = self.state.observation.raw.creep.read_lock();
+		self.is_creep_edge_in(&creep, pos.into())
+	}
+	/// Returns creep edge positions (see [`is_creep_edge`](Self::is_creep_edge))
+	/// in given distance from given point.
+	pub fn creep_edge_near(&self, pos: Point2, distance: f32) -> Vec<Point2> {
+		let creep = self.state.observation.raw.creep.read_lock();
+		let (width, height) = creep.dim();
+		let x0 = (pos.x - distance).floor().max(0.0) as usize;
+		let y0 = (pos.y - distance).floor().max(0.0) as usize;
+		let x1 = ((pos.x + distance).ceil().max(0.0) as usize).min(width);
+		let y1 = ((pos.y + distance).ceil().max(0.0) as usize).min(height);
+
+		let mut edge = Vec::new();
+		for x in x0..x1 {
+			for y in y0..y1 {
+				let cell = Point2::new(x as f32 + 0.5, y as f32 + 0.5);
+				if cell.is_closer(distance, pos) && self.is_creep_edge_in(&creep, (x, y)) {
+					edge.push(cell);
+				}
+			}
+		}
+		edge
+	}
+	fn is_creep_edge_in(&self, creep: &PixelMap, (x, y): (usize, usize)) -> bool {
+		if !creep.get((x, y)).is_some_and(|p| p.is_empty()) {
+			return false;
+		}
+		let pathing = &self.game_info.pathing_grid;
+		(-1..=1)
+			.flat_map(|dx| (-1..=1).map(move |dy| (dx, dy)))
+			.any(|(dx, dy)| {
+				let n = ((x as isize + dx) as usize, (y as isize + dy) as usize);
+				pathing.get(n).is_some_and(|p| p.is_empty()) && creep.get(n).is_some_and(|p| p.is_set())
+			})
+	}
 	pub(crate) fn init_data_for_unit(&mut self) {
 		self.race = self.game_info.players[&self.player_id].race_actual.unwrap();
 		if self.game_info.players.len() == 2 {
@@ -827,6 +1338,7 @@ impl Bot {
 			race_values: Rs::clone(&self.race_values),
 			max_cooldowns: Rs::clone(&self.max_cooldowns),
 			last_units_health: Rs::clone(&self.last_units_health),
+			buffs_appeared: Rs::clone(&self.buffs_appeared),
 			abilities_units: Rs::clone(&self.abilities_units),
 			enemy_upgrades: Rs::clone(&self.enemy_upgrades),
 			upgrades: Rs::clone(&self.state.observation.raw.upgrades),
@@ -958,18 +1470,18 @@ impl Bot {
 		// Sort expansions by distance to start location
 		let start = Target::Pos(self.start_location);
 		let paths = self
-			.query_pathing(expansions.iter().map(|exp| (start, exp.loc)).collect())
+			.query_pathing(&expansions.iter().map(|exp| (start, exp.loc)).collect::<Vec<_>>())
 			.unwrap();
 
 		let paths = expansions
 			.iter()
 			.zip(paths)
-			.map(|(exp, path)| (exp.loc, path.unwrap_or(f32::INFINITY)))
+			.map(|(exp, path)| (exp.loc, path.distance.unwrap_or(f32::INFINITY)))
 			.collect::<FxHashMap<Point2, f32>>();
 
 		expansions.sort_unstable_by(|a, b| paths[&a.loc].partial_cmp(&paths[&b.loc]).unwrap());
 
-		self.expansions = expansions;
+		self.expansions = expansions.into();
 
 		// Calclulating ramp locations
 		let mut ramp_points = FxHashSet::default();
@@ -1062,7 +1574,10 @@ impl Bot {
 		}
 
 		self.ramps.all = ramps;
+
+		self.blink_edges = find_blink_edges(&self.game_info.pathing_grid, &self.game_info.terrain_height);
 	}
+	#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 	pub(crate) fn prepare_step(&mut self) {
 		let observation = &self.state.observation;
 		self.time = (observation.game_loop() as f32) / FRAMES_PER_SECOND;
@@ -1074,6 +1589,8 @@ impl Bot {
 		self.supply_cap = common.food_cap;
 		self.supply_used = common.food_used;
 		self.supply_left = self.supply_cap.saturating_sub(self.supply_used);
+		self.expire_reservations();
+		self.placement_cache.write_lock().clear();
 
 		// Counting units and orders
 		let mut current_units = FxHashMap::default();
@@ -1110,16 +1627,26 @@ impl Bot {
 		}
 		self.current_units = current_units;
 		self.orders = orders;
+
+		self.update_enemy_intel();
 	}
 	pub(crate) fn update_units(&mut self, all_units: Units) {
-		*self.last_units_health.write_lock() = self
-			.units
-			.all
+		let mut last_health = self.last_units_health.write_lock();
+		last_health.clear();
+		last_health.extend(self.units.all.iter().filter_map(|u| Some((u.tag(), u.hits()?))));
+		drop(last_health);
+
+		let game_loop = self.state.observation.game_loop();
+		let mut buffs_appeared = self.buffs_appeared.write_lock();
+		let current_buffs = all_units
 			.iter()
-			.filter_map(|u| Some((u.tag(), u.hits()?)))
-			.collect();
-
-		self.units.clear();
+			.flat_map(|u| u.buffs().iter().map(move |b| (u.tag(), *b)))
+			.collect::<FxHashSet<_>>();
+		buffs_appeared.retain(|key, _| current_buffs.contains(key));
+		for key in current_buffs {
+			buffs_appeared.entry(key).or_insert(game_loop);
+		}
+		drop(buffs_appeared);
 
 		let mut techlab_tags = self.techlab_tags.write_lock();
 		let mut reactor_tags = self.reactor_tags.write_lock();
@@ -1145,30 +1672,11 @@ impl Bot {
 				Alliance::Neutral => match u.type_id() {
 					UnitTypeId::XelNagaTower => add_to!(units.watchtowers),
 
-					UnitTypeId::RichMineralField
-					| UnitTypeId::RichMineralField750
-					| UnitTypeId::MineralField
-					| UnitTypeId::MineralField450
-					| UnitTypeId::MineralField750
-					| UnitTypeId::LabMineralField
-					| UnitTypeId::LabMineralField750
-					| UnitTypeId::PurifierRichMineralField
-					| UnitTypeId::PurifierRichMineralField750
-					| UnitTypeId::PurifierMineralField
-					| UnitTypeId::PurifierMineralField750
-					| UnitTypeId::BattleStationMineralField
-					| UnitTypeId::BattleStationMineralField750
-					| UnitTypeId::MineralFieldOpaque
-					| UnitTypeId::MineralFieldOpaque900 => {
+					id if id.is_mineral_field() => {
 						add_to!(units.resources);
 						add_to!(units.mineral_fields);
 					}
-					UnitTypeId::VespeneGeyser
-					| UnitTypeId::SpacePlatformGeyser
-					| UnitTypeId::RichVespeneGeyser
-					| UnitTypeId::ProtossVespeneGeyser
-					| UnitTypeId::PurifierVespeneGeyser
-					| UnitTypeId::ShakurasVespeneGeyser => {
+					id if id.is_geyser() => {
 						add_to!(units.resources);
 						add_to!(units.vespene_geysers);
 					}
@@ -1211,12 +1719,7 @@ impl Bot {
 									add_to!(units.townhalls)
 								}
 
-								UnitTypeId::Refinery
-								| UnitTypeId::RefineryRich
-								| UnitTypeId::Assimilator
-								| UnitTypeId::AssimilatorRich
-								| UnitTypeId::Extractor
-								| UnitTypeId::ExtractorRich => add_to!(units.gas_buildings),
+								id if id.is_gas_structure() => add_to!(units.gas_buildings),
 
 								UnitTypeId::TechLab
 								| UnitTypeId::BarracksTechLab
@@ -1269,12 +1772,7 @@ impl Bot {
 								add_to!(units.townhalls)
 							}
 
-							UnitTypeId::Refinery
-							| UnitTypeId::RefineryRich
-							| UnitTypeId::Assimilator
-							| UnitTypeId::AssimilatorRich
-							| UnitTypeId::Extractor
-							| UnitTypeId::ExtractorRich => add_to!(units.gas_buildings),
+							id if id.is_gas_structure() => add_to!(units.gas_buildings),
 
 							_ => {}
 						}
@@ -1290,6 +1788,7 @@ impl Bot {
 				_ => {}
 			}
 		}
+		units.retain_current(&all_units);
 		units.all = all_units;
 
 		let enemies = &mut self.units.enemy;
@@ -1331,18 +1830,19 @@ impl Bot {
 			let cache = &mut self.units.cached;
 			let enemy_is_terran = self.enemy_race.is_terran();
 
-			cache.all.extend(enemies.all.clone());
-			cache.units.extend(enemies.units.clone());
-			cache.workers.extend(enemies.workers.clone());
+			// Extending and cloning in place reuses allocated memory of cache
+			cache.all.extend(enemies.all.iter().cloned());
+			cache.units.extend(enemies.units.iter().cloned());
+			cache.workers.extend(enemies.workers.iter().cloned());
 			if enemy_is_terran {
-				cache.structures.extend(enemies.structures.clone());
-				cache.townhalls.extend(enemies.townhalls.clone());
+				cache.structures.extend(enemies.structures.iter().cloned());
+				cache.townhalls.extend(enemies.townhalls.iter().cloned());
 			} else {
-				cache.structures = enemies.structures.clone();
-				cache.townhalls = enemies.townhalls.clone();
+				cache.structures.clone_from(&enemies.structures);
+				cache.townhalls.clone_from(&enemies.townhalls);
 			}
-			cache.gas_buildings = enemies.gas_buildings.clone();
-			cache.larvas = enemies.larvas.clone();
+			cache.gas_buildings.clone_from(&enemies.gas_buildings);
+			cache.larvas.clone_from(&enemies.larvas);
 
 			let mut to_remove = Vec::<u64>::new();
 			let mut burrowed = Vec::<u64>::new();
@@ -1528,97 +2028,167 @@ impl Bot {
 		.collect()
 	}
 
+	/// Returns [`PlacementMap`] with footprints of all known structures, resources
+	/// and mining area of each expansion marked as occupied.
+	///
+	/// Useful to check many placements locally and send [`query_placement`](Self::query_placement)
+	/// only for the best candidates.
+	pub fn placement_map(&self) -> PlacementMap {
+		let mut map = PlacementMap::new(&self.game_info.placement_grid);
+
+		let units = &self.units;
+		for u in units
+			.my
+			.structures
+			.iter()
+			.chain(&units.my.placeholders)
+			.chain(&units.enemy.structures)
+			.chain(&units.resources)
+			.chain(&units.destructables)
+		{
+			map.reserve_unit(u);
+		}
+
+		// Keeping space between townhalls and resources free
+		for exp in &self.expansions {
+			for tag in exp.minerals.iter().chain(&exp.geysers) {
+				if let Some(r) = units.resources.get(*tag) {
+					map.reserve_line(exp.loc, r.position(), 1.5);
+				}
+			}
+		}
+
+		map
+	}
+	/// Returns size of given building in tiles
+	/// (e.g. `2` for supply, `3` for barracks, `5` for command center),
+	/// or `None` if it's not a building.
+	pub fn get_building_size(&self, building: UnitTypeId) -> Option<usize> {
+		if building.is_addon() {
+			return Some(2);
+		}
+		let ability = self.game_data.units.get(&building)?.ability?;
+		let radius = self.game_data.abilities.get(&ability)?.footprint_radius?;
+		Some((radius * 2.0) as usize)
+	}
+
 	/// Nice wrapper around [`query_placement`](Self::query_placement).
 	/// Returns correct position where it is possible to build given `building`,
 	/// or `None` if position is not found or `building` can't be built by a worker.
+	/// Errors of placement query are logged and also result in `None`.
+	///
+	/// Positions are checked locally with [`placement_map`](Self::placement_map) first,
+	/// then all suitable ones are validated with a single placement query.
 	pub fn find_placement(
 		&self,
 		building: UnitTypeId,
 		near: Point2,
 		options: PlacementOptions,
 	) -> Option<Point2> {
-		if let Some(data) = self.game_data.units.get(&building) {
-			if let Some(ability) = data.ability {
-				let addon = options.addon;
-				if self
-					.query_placement(
-						if addon {
-							vec![
-								(ability, near, None),
-								(AbilityId::TerranBuildSupplyDepot, near.offset(2.5, -0.5), None),
-							]
-						} else {
-							vec![(ability, near, None)]
-						},
-						false,
-					)
-					.unwrap()
-					.iter()
-					.all(|r| matches!(r, ActionResult::Success))
-				{
-					return Some(near);
+		let ability = self.game_data.units.get(&building)?.ability?;
+		let size = self.get_building_size(building)?;
+		let mut map = self.placement_map();
+
+		if options.avoid_expansions && !building.is_townhall() {
+			for exp in self.expansions.free() {
+				map.reserve(exp.loc, 5);
+			}
+		}
+		let needs_addon = options.addon || (options.terran_addons && ADDON_STRUCTURES.contains(&building));
+		if options.terran_addons {
+			for s in self.units.my.structures.iter().of_types(&ADDON_STRUCTURES) {
+				map.reserve(s.position().offset(ADDON_OFFSET.0, ADDON_OFFSET.1), 2);
+			}
+		}
+		let gap = options.production_gap;
+		if gap > 0 && PRODUCTION_STRUCTURES.contains(&building) {
+			for s in self.units.my.structures.iter().of_types(&PRODUCTION_STRUCTURES) {
+				map.reserve(s.position(), s.building_size().unwrap_or(3) + gap * 2);
+				if s.has_addon() {
+					map.reserve(s.position().offset(ADDON_OFFSET.0, ADDON_OFFSET.1), 2 + gap * 2);
 				}
+			}
+		}
 
-				let placement_step = options.step;
-				for distance in (placement_step..options.max_distance).step_by(placement_step as usize) {
-					let positions = (-distance..=distance)
-						.step_by(placement_step as usize)
-						.flat_map(|offset| {
-							vec![
-								near.offset(offset as f32, (-distance) as f32),
-								near.offset(offset as f32, distance as f32),
-								near.offset((-distance) as f32, offset as f32),
-								near.offset(distance as f32, offset as f32),
-							]
-						})
-						.collect::<Vec<Point2>>();
-					let results = self
-						.query_placement(positions.iter().map(|pos| (ability, *pos, None)).collect(), false)
-						.unwrap();
+		let fits = |pos: Point2| {
+			if needs_addon {
+				map.can_place_with_addon(pos, size)
+			} else {
+				map.can_place(pos, size)
+			}
+		};
 
-					let mut valid_positions = positions
-						.iter()
-						.zip(results.iter())
-						.filter_map(|(pos, res)| {
-							if matches!(res, ActionResult::Success) {
-								Some(*pos)
-							} else {
-								None
-							}
-						})
-						.collect::<Vec<Point2>>();
-
-					if addon {
-						let results = self
-							.query_placement(
-								valid_positions
-									.iter()
-									.map(|pos| {
-										(AbilityId::TerranBuildSupplyDepot, pos.offset(2.5, -0.5), None)
-									})
-									.collect(),
-								false,
-							)
-							.unwrap();
-						valid_positions = valid_positions
-							.into_iter()
-							.zip(results.into_iter())
-							.filter(|(_, res)| *res == ActionResult::Success)
-							.map(|(pos, _)| pos)
-							.collect::<Vec<Point2>>();
-					}
+		// Candidates grouped by distance to `near`
+		let placement_step = options.step;
+		let mut rings = vec![vec![near]];
+		for distance in (placement_step..options.max_distance).step_by(placement_step as usize) {
+			rings.push(
+				(-distance..=distance)
+					.step_by(placement_step as usize)
+					.flat_map(|offset| {
+						[
+							near.offset(offset as f32, (-distance) as f32),
+							near.offset(offset as f32, distance as f32),
+							near.offset((-distance) as f32, offset as f32),
+							near.offset(distance as f32, offset as f32),
+						]
+					})
+					.collect(),
+			);
+		}
+		let candidates = rings
+			.into_iter()
+			.enumerate()
+			.flat_map(|(i, ring)| ring.into_iter().map(move |pos| (i, pos)))
+			.filter(|(_, pos)| fits(*pos))
+			.collect::<Vec<(usize, Point2)>>();
+		if candidates.is_empty() {
+			return None;
+		}
 
-					if !valid_positions.is_empty() {
-						return if options.random {
-							valid_positions.choose(&mut thread_rng()).copied()
-						} else {
-							valid_positions.iter().closest(near).copied()
-						};
+		// Place for addon is checked by API too, since local map doesn't know about all obstacles
+		let queries_per_pos = if needs_addon { 2 } else { 1 };
+		let results = match self.query_placement(
+			candidates
+				.iter()
+				.flat_map(|(_, pos)| {
+					let building = (ability, *pos, None);
+					if needs_addon {
+						let addon = pos.offset(ADDON_OFFSET.0, ADDON_OFFSET.1);
+						vec![building, (AbilityId::TerranBuildSupplyDepot, addon, None)]
+					} else {
+						vec![building]
 					}
-				}
+				})
+				.collect(),
+			false,
+		) {
+			Ok(results) => results,
+			Err(e) => {
+				error!("Can't query placement of {:?}: {}", building, e);
+				return None;
 			}
+		};
+		let mut valid_positions = candidates
+			.into_iter()
+			.zip(results.chunks(queries_per_pos))
+			.filter(|(_, res)| res.iter().all(|r| *r == ActionResult::Success))
+			.map(|(candidate, _)| candidate)
+			.collect::<Vec<(usize, Point2)>>();
+
+		// Only the closest ring with valid positions is used
+		let closest_ring = valid_positions.first()?.0;
+		valid_positions.retain(|(i, _)| *i == closest_ring);
+		let valid_positions = valid_positions
+			.into_iter()
+			.map(|(_, pos)| pos)
+			.collect::<Vec<_>>();
+
+		if options.random {
+			valid_positions.choose(&mut thread_rng()).copied()
+		} else {
+			valid_positions.iter().closest(near).copied()
 		}
-		None
 	}
 	/// Another wrapper around [`query_placement`](Self::query_placement),
 	/// used to find free geyser near given base.
@@ -1645,7 +2215,7 @@ impl Bot {
 	/// Returns next possible location from [`expansions`](Self::expansions) closest to bot's start location
 	/// or `None` if there aren't any free locations.
 	pub fn get_expansion(&self) -> Option<&Expansion> {
-		self.expansions.iter().find(|exp| exp.alliance.is_neutral())
+		self.expansions.free().next()
 	}
 	/// Returns next possible location from [`expansions`](Self::expansions) closest to
 	/// opponent's start location or `None` if there aren't any free locations.
@@ -1653,56 +2223,87 @@ impl Bot {
 		let expansions = self.free_expansions().collect::<Vec<_>>();
 		let start = Target::Pos(self.enemy_start);
 		let paths = self
-			.query_pathing(expansions.iter().map(|exp| (start, exp.loc)).collect())
+			.query_pathing(&expansions.iter().map(|exp| (start, exp.loc)).collect::<Vec<_>>())
 			.unwrap();
 
 		expansions
 			.into_iter()
 			.zip(paths)
-			.filter_map(|(exp, path)| Some((exp, path?)))
+			.filter_map(|(exp, path)| Some((exp, path.distance?)))
 			.min_by(|(_, path1), (_, path2)| path1.partial_cmp(path2).unwrap())
 			.map(|(exp, _)| exp)
 	}
 	/// Returns all [`expansions`](Self::expansions) taken by bot.
 	pub fn owned_expansions(&self) -> impl Iterator<Item = &Expansion> {
-		self.expansions.iter().filter(|exp| exp.alliance.is_mine())
+		self.expansions.mine()
 	}
 	/// Returns all [`expansions`](Self::expansions) taken by opponent.
 	pub fn enemy_expansions(&self) -> impl Iterator<Item = &Expansion> {
-		self.expansions.iter().filter(|exp| exp.alliance.is_enemy())
+		self.expansions.enemy()
 	}
 	/// Returns all available [`expansions`](Self::expansions).
 	pub fn free_expansions(&self) -> impl Iterator<Item = &Expansion> {
-		self.expansions.iter().filter(|exp| exp.alliance.is_neutral())
+		self.expansions.free()
+	}
+	/// Returns placements of protoss wall at natural expansion
+	/// (2nd location in [`expansions`](Self::expansions)),
+	/// or `None` if chokepoint near natural can't be walled.
+	///
+	/// See [`wall::natural_wall`] for details.
+	pub fn natural_wall(&self) -> Option<NaturalWall> {
+		let natural = self.expansions.get(1)?;
+		wall::natural_wall(
+			&self.game_info.pathing_grid,
+			&self.game_info.placement_grid,
+			natural.loc,
+			self.enemy_start,
+		)
 	}
 	/// Sends pathing requests to API.
 	///
-	/// Takes `Vec` of (start, goal), where `start` is position or unit tag and `goal` is position.
+	/// Takes slice of (start, goal), where `start` is position or unit tag and `goal` is position.
 	///
-	/// Returns `Vec` ordered by input values,
-	/// where element is distance of path from start to goal or `None` if there's no path.
-	pub fn query_pathing(&self, paths: Vec<(Target, Point2)>) -> SC2Result<Vec<Option<f32>>> {
+	/// Returns `Vec` of [`PathingResult`] ordered by input values.
+	pub fn query_pathing(&self, paths: &[(Target, Point2)]) -> SC2Result<Vec<PathingResult>> {
 		let mut req = Request::new();
 		let req_pathing = req.mut_query().mut_pathing();
+		for path in paths {
+			req_pathing.push(pathing_request(*path));
+		}
 
-		for (start, goal) in paths {
-			let mut pathing = RequestQueryPathing::new();
-			match start {
-				Target::Tag(tag) => pathing.set_unit_tag(tag),
-				Target::Pos(pos) => pathing.set_start_pos(pos.into_proto()),
-				Target::None => panic!("start pos is not specified in query pathing request"),
-			}
-			pathing.set_end_pos(goal.into_proto());
-			req_pathing.push(pathing);
+		let res = self.api().send(req)?;
+		Ok(res.get_query().get_pathing().iter().map(pathing_result).collect())
+	}
+	/// Returns ground distance from start to goal or `None` if there's no path,
+	/// where `start` is position or unit tag.
+	///
+	/// Wrapper around [`query_pathing`](Self::query_pathing) for a single path.
+	pub fn ground_distance(&self, start: Target, goal: Point2) -> SC2Result<Option<f32>> {
+		Ok(self
+			.query_pathing(&[(start, goal)])?
+			.first()
+			.and_then(|result| result.distance))
+	}
+	/// Queries abilities available for own units with given tags on current step.
+	///
+	/// Results are accessible with [`Unit::abilities`] and [`Unit::has_ability`] until the next step.
+	/// Useful when per-step query is limited or disabled with [`abilities_scope`](Self::abilities_scope).
+	pub fn query_abilities(&self, tags: &[u64]) -> SC2Result<()> {
+		if tags.is_empty() {
+			return Ok(());
+		}
+
+		let mut req = Request::new();
+		let req_query_abilities = req.mut_query().mut_abilities();
+		for &tag in tags {
+			req_query_abilities.push(abilities_request(tag));
 		}
 
 		let res = self.api().send(req)?;
-		Ok(res
-			.get_query()
-			.get_pathing()
-			.iter()
-			.map(|result| result.distance)
-			.collect())
+		self.abilities_units
+			.write_lock()
+			.extend(res.get_query().get_abilities().iter().map(abilities_result));
+		Ok(())
 	}
 	/// Sends placement requests to API.
 	/// Takes creep, psionic matrix, and other stuff into account.
@@ -1716,33 +2317,51 @@ impl Bot {
 	/// Takes `Vec` of (build ability, position, tag of worker or `None`).
 	///
 	/// Returns `Vec` of [`ActionResult`] ordered by input values.
+	///
+	/// Results are cached until the end of current step, so repeated queries
+	/// of the same ability and position (rounded to half of cell) don't make new requests.
 	pub fn query_placement(
 		&self,
 		places: Vec<(AbilityId, Point2, Option<u64>)>,
 		check_resources: bool,
 	) -> SC2Result<Vec<ActionResult>> {
+		let key = |place| placement_key(place, check_resources);
+
+		let mut results = Vec::with_capacity(places.len());
+		let mut missing = Vec::new();
+		{
+			let cache = self.placement_cache.read_lock();
+			for (i, place) in places.iter().enumerate() {
+				match cache.get(&key(*place)) {
+					Some(result) => results.push(*result),
+					None => {
+						results.push(ActionResult::Success);
+						missing.push(i);
+					}
+				}
+			}
+		}
+		if missing.is_empty() {
+			return Ok(results);
+		}
+
 		let mut req = Request::new();
 		let req_query = req.mut_query();
 		req_query.set_ignore_resource_requirements(!check_resources);
 		let req_placement = req_query.mut_placements();
 
-		for (ability, pos, builder) in places {
-			let mut placement = RequestQueryBuildingPlacement::new();
-			placement.set_ability_id(ability.to_i32().unwrap());
-			placement.set_target_pos(pos.into_proto());
-			if let Some(tag) = builder {
-				placement.set_placing_unit_tag(tag);
-			}
-			req_placement.push(placement);
+		for &i in &missing {
+			req_placement.push(placement_request(places[i]));
 		}
 
 		let res = self.api().send(req)?;
-		Ok(res
-			.get_query()
-			.get_placements()
-			.iter()
-			.map(|result| ActionResult::from_proto(result.get_result()))
-			.collect())
+		let mut cache = self.placement_cache.write_lock();
+		for (i, result) in missing.into_iter().zip(res.get_query().get_placements()) {
+			let result = ActionResult::from_proto(result.get_result());
+			cache.insert(key(places[i]), result);
+			results[i] = result;
+		}
+		Ok(results)
 	}
 
 	/// Leaves current game, which is counted as Defeat for bot.
@@ -1784,6 +2403,7 @@ impl Default for Bot {
 		Self {
 			game_step: Rs::new(LockU32::new(1)),
 			game_left: false,
+			game_summary: None,
 			disable_fog: false,
 			race: Race::Random,
 			enemy_race: Race::Random,
@@ -1802,6 +2422,7 @@ impl Default for Bot {
 			data_for_unit: Default::default(),
 			units: Default::default(),
 			abilities_units: Default::default(),
+			abilities_scope: Default::default(),
 			orders: Default::default(),
 			current_units: Default::default(),
 			time: Default::default(),
@@ -1821,8 +2442,28 @@ impl Default for Bot {
 			expansions: Default::default(),
 			max_cooldowns: Default::default(),
 			last_units_health: Default::default(),
+			buffs_appeared: Default::default(),
 			vision_blockers: Default::default(),
 			ramps: Default::default(),
+			supply_manager: Default::default(),
+			worker_manager: Default::default(),
+			speed_mining: Default::default(),
+			auto_repair: Default::default(),
+			auto_burrow: Default::default(),
+			auto_gg: Default::default(),
+			plugins: Default::default(),
+			timers: Vec::new(),
+			enemy_intel: Default::default(),
+			hidden_enemies: Default::default(),
+			enemy_army: Default::default(),
+			action_log: Default::default(),
+			perf: Default::default(),
+			reservations: Default::default(),
+			roles: Default::default(),
+			last_seen: Default::default(),
+			power_map: Default::default(),
+			blink_edges: Default::default(),
+			placement_cache: Default::default(),
 			enemy_upgrades: Default::default(),
 			owned_tags: Default::default(),
 			under_construction: Default::default(),
@@ -0,0 +1,330 @@
+//! Build orders and parser of their text exports.
+//!
+//! Supports [Spawning Tool](https://lotv.spawningtool.com) text exports
+//! and common short notation:
+//! ```text
+//! 14  0:18  Spawning Pool
+//! 16  0:49  Hatchery
+//! 17  1:00  Extractor
+//! 19  1:30  Queen x2, Zergling x2
+//! 20        Metabolic Boost
+//! ```
+
+use crate::{
+	bot::Bot,
+	consts::RACE_VALUES,
+	ids::{UnitTypeId, UpgradeId},
+	player::Race,
+};
+use num_traits::FromPrimitive;
+use rustc_hash::FxHashMap;
+use std::{error::Error, fmt};
+
+/// Thing which should be made in [`BuildStep`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuildItem {
+	/// Unit or structure to train, build or morph.
+	Unit(UnitTypeId),
+	/// Upgrade to research.
+	Upgrade(UpgradeId),
+}
+
+/// Single step of [`BuildOrder`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildStep {
+	/// Supply at which step should be started.
+	pub supply: Option<u32>,
+	/// In-game time in seconds at which step should be started.
+	pub time: Option<f32>,
+	/// What should be made.
+	pub item: BuildItem,
+	/// How many times item should be made.
+	pub count: usize,
+}
+impl BuildStep {
+	/// Checks if step should be started now:
+	/// bot reached required supply or time (if step has neither of them, it's always due).
+	pub fn is_due(&self, bot: &Bot) -> bool {
+		match (self.supply, self.time) {
+			(None, None) => true,
+			(supply, time) => {
+				supply.is_some_and(|supply| bot.supply_used >= supply)
+					|| time.is_some_and(|time| bot.time >= time)
+			}
+		}
+	}
+}
+
+/// Sequence of steps to execute one by one.
+#[derive(Debug, Clone, Default)]
+pub struct BuildOrder {
+	/// All steps of build order.
+	pub steps: Vec<BuildStep>,
+	current: usize,
+}
+impl BuildOrder {
+	/// Constructs build order from given steps.
+	pub fn new(steps: Vec<BuildStep>) -> Self {
+		Self { steps, current: 0 }
+	}
+	/// Parses build order from text export.
+	/// `race` is used to resolve race-specific names like "Gas".
+	///
+	/// Each non-empty line should contain optional supply, optional time (`m:ss`)
+	/// and comma-separated item names, each optionally followed by count (`x2`).
+	/// Notes in parentheses (e.g. "(Chrono Boost)") are ignored.
+	/// Lines starting with `#` or `//` are treated as comments.
+	pub fn parse(text: &str, race: Race) -> Result<Self, ParseBuildOrderError> {
+		let names = Names::new(race);
+		let mut steps = Vec::new();
+
+		for (i, line) in text.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+				continue;
+			}
+			let error = || ParseBuildOrderError {
+				line: i + 1,
+				text: line.to_string(),
+			};
+
+			let mut rest = line;
+			let mut supply = None;
+			let mut time = None;
+			if let Some((token, tail)) = split_token(rest) {
+				// Supply can be written as "14" or "14/15"
+				if let Ok(value) = token.split('/').next().unwrap_or(token).parse::<u32>() {
+					supply = Some(value);
+					rest = tail;
+				}
+			}
+			if let Some((token, tail)) = split_token(rest) {
+				if let Some(value) = parse_time(token) {
+					time = Some(value);
+					rest = tail;
+				}
+			}
+
+			for entry in rest.split(',') {
+				let (name, count) = parse_entry(entry);
+				if name.is_empty() {
+					continue;
+				}
+				let item = names.get(&name).ok_or_else(error)?;
+				steps.push(BuildStep {
+					supply,
+					time,
+					item,
+					count,
+				});
+			}
+		}
+
+		Ok(Self::new(steps))
+	}
+	/// Returns current step or `None` if build order is finished.
+	pub fn current(&self) -> Option<&BuildStep> {
+		self.steps.get(self.current)
+	}
+	/// Returns current step if it should be started now.
+	pub fn due(&self, bot: &Bot) -> Option<&BuildStep> {
+		self.current().filter(|step| step.is_due(bot))
+	}
+	/// Marks current step as complete and moves to the next one.
+	pub fn advance(&mut self) {
+		if self.current < self.steps.len() {
+			self.current += 1;
+		}
+	}
+	/// Returns index of current step.
+	pub fn position(&self) -> usize {
+		self.current
+	}
+	/// Checks if all steps are complete.
+	pub fn is_finished(&self) -> bool {
+		self.current >= self.steps.len()
+	}
+	/// Starts build order from the beginning.
+	pub fn reset(&mut self) {
+		self.current = 0;
+	}
+}
+
+/// Error returned when build order text can't be parsed.
+#[derive(Debug, Clone)]
+pub struct ParseBuildOrderError {
+	/// Number of line with error (starting from 1).
+	pub line: usize,
+	/// Text of line with error.
+	pub text: String,
+}
+impl fmt::Display for ParseBuildOrderError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"Unknown build order item at line {}: {:?}",
+			self.line, self.text
+		)
+	}
+}
+impl Error for ParseBuildOrderError {}
+
+fn split_token(s: &str) -> Option<(&str, &str)> {
+	let s = s.trim_start();
+	if s.is_empty() {
+		return None;
+	}
+	Some(match s.find(char::is_whitespace) {
+		Some(i) => (&s[..i], &s[i..]),
+		None => (s, ""),
+	})
+}
+
+fn parse_time(s: &str) -> Option<f32> {
+	let (minutes, seconds) = s.split_once(':')?;
+	Some(minutes.parse::<u32>().ok()? as f32 * 60.0 + seconds.parse::<u32>().ok()? as f32)
+}
+
+// Returns normalized name and count of entry like "Queen x2" or "Reactor (Barracks)".
+fn parse_entry(entry: &str) -> (String, usize) {
+	let mut name = String::new();
+	let mut count = 1;
+	let mut parts = entry.split('(');
+	let main = parts.next().unwrap_or("");
+
+	let mut words = main.split_whitespace().collect::<Vec<_>>();
+	if let Some(n) = words.last().and_then(|w| parse_count(w)) {
+		count = n;
+		words.pop();
+	}
+	for w in &words {
+		name.push_str(w);
+	}
+	let name = normalize(&name);
+
+	// Parenthesized notes are ignored, except counts and addon parents
+	for part in parts {
+		let note = part.split(')').next().unwrap_or("").trim();
+		if let Some(n) = parse_count(note) {
+			count = n;
+		} else if name == "reactor" || name == "techlab" {
+			return (format!("{}{}", normalize(note), name), count);
+		}
+	}
+	(name, count)
+}
+
+fn parse_count(s: &str) -> Option<usize> {
+	let s = s.trim();
+	s.strip_prefix(['x', 'X'])
+		.or_else(|| s.strip_suffix(['x', 'X']))
+		.and_then(|n| n.parse().ok())
+}
+
+fn normalize(name: &str) -> String {
+	name.chars()
+		.filter(|c| c.is_ascii_alphanumeric())
+		.map(|c| c.to_ascii_lowercase())
+		.collect()
+}
+
+struct Names(FxHashMap<String, BuildItem>);
+impl Names {
+	fn new(race: Race) -> Self {
+		let mut names = FxHashMap::default();
+		for id in (0..=UnitTypeId::MAX_ID).filter_map(UnitTypeId::from_u32) {
+			names
+				.entry(normalize(&format!("{:?}", id)))
+				.or_insert(BuildItem::Unit(id));
+		}
+		for id in (0..=UpgradeId::MAX_ID).filter_map(UpgradeId::from_u32) {
+			names
+				.entry(normalize(&format!("{:?}", id)))
+				.or_insert(BuildItem::Upgrade(id));
+		}
+
+		if let Some(values) = RACE_VALUES.get(&race) {
+			names.insert("gas".to_string(), BuildItem::Unit(values.gas));
+			names.insert("supply".to_string(), BuildItem::Unit(values.supply));
+			names.insert("worker".to_string(), BuildItem::Unit(values.worker));
+		}
+
+		let aliases = [
+			// Zerg
+			("pool", BuildItem::Unit(UnitTypeId::SpawningPool)),
+			("hatch", BuildItem::Unit(UnitTypeId::Hatchery)),
+			("ling", BuildItem::Unit(UnitTypeId::Zergling)),
+			("lings", BuildItem::Unit(UnitTypeId::Zergling)),
+			("warren", BuildItem::Unit(UnitTypeId::RoachWarren)),
+			("hydraden", BuildItem::Unit(UnitTypeId::HydraliskDen)),
+			("hydraliskden", BuildItem::Unit(UnitTypeId::HydraliskDen)),
+			("lurkerden", BuildItem::Unit(UnitTypeId::LurkerDenMP)),
+			("evo", BuildItem::Unit(UnitTypeId::EvolutionChamber)),
+			(
+				"metabolicboost",
+				BuildItem::Upgrade(UpgradeId::Zerglingmovementspeed),
+			),
+			("lingspeed", BuildItem::Upgrade(UpgradeId::Zerglingmovementspeed)),
+			(
+				"adrenalglands",
+				BuildItem::Upgrade(UpgradeId::Zerglingattackspeed),
+			),
+			(
+				"pneumatizedcarapace",
+				BuildItem::Upgrade(UpgradeId::Overlordspeed),
+			),
+			(
+				"groovedspines",
+				BuildItem::Upgrade(UpgradeId::EvolveGroovedSpines),
+			),
+			(
+				"muscularaugments",
+				BuildItem::Upgrade(UpgradeId::EvolveMuscularAugments),
+			),
+			// Terran
+			("depot", BuildItem::Unit(UnitTypeId::SupplyDepot)),
+			("rax", BuildItem::Unit(UnitTypeId::Barracks)),
+			("cc", BuildItem::Unit(UnitTypeId::CommandCenter)),
+			("orbital", BuildItem::Unit(UnitTypeId::OrbitalCommand)),
+			("ebay", BuildItem::Unit(UnitTypeId::EngineeringBay)),
+			("stim", BuildItem::Upgrade(UpgradeId::Stimpack)),
+			("combatshield", BuildItem::Upgrade(UpgradeId::ShieldWall)),
+			(
+				"concussiveshells",
+				BuildItem::Upgrade(UpgradeId::PunisherGrenades),
+			),
+			("cloakingfield", BuildItem::Upgrade(UpgradeId::BansheeCloak)),
+			// Protoss
+			("gate", BuildItem::Unit(UnitTypeId::Gateway)),
+			("core", BuildItem::Unit(UnitTypeId::CyberneticsCore)),
+			("cybercore", BuildItem::Unit(UnitTypeId::CyberneticsCore)),
+			("robo", BuildItem::Unit(UnitTypeId::RoboticsFacility)),
+			("twilight", BuildItem::Unit(UnitTypeId::TwilightCouncil)),
+			("warpgate", BuildItem::Upgrade(UpgradeId::WarpGateResearch)),
+			("blink", BuildItem::Upgrade(UpgradeId::BlinkTech)),
+			(
+				"resonatingglaives",
+				BuildItem::Upgrade(UpgradeId::AdeptPiercingAttack),
+			),
+		];
+		for (name, item) in aliases {
+			names.insert(name.to_string(), item);
+		}
+		// Plural names like "Zerglings" or "Marines"
+		let plurals = names
+			.iter()
+			.filter_map(|(name, item)| match item {
+				BuildItem::Unit(_) => Some((format!("{}s", name), *item)),
+				BuildItem::Upgrade(_) => None,
+			})
+			.collect::<Vec<_>>();
+		for (name, item) in plurals {
+			names.entry(name).or_insert(item);
+		}
+
+		Self(names)
+	}
+	fn get(&self, name: &str) -> Option<BuildItem> {
+		self.0.get(name).copied()
+	}
+}
@@ -0,0 +1,115 @@
+//! Capture of protobuf traffic between bot and SC2.
+//!
+//! Capture can be started with [`API::start_capture`](crate::api::API::start_capture)
+//! or by setting [`SC2_CAPTURE`](ENV_CAPTURE) environment variable to path of capture file,
+//! which records the whole game including `CreateGame` and `JoinGame` requests.
+//! Only traffic of the bot is captured, connection of human player in
+//! [`RunnerMulti`](crate::client::RunnerMulti) isn't.
+//!
+//! File consists of records: direction byte (`0` for request, `1` for response),
+//! length of message as `u32` in little endian and the protobuf message itself.
+//! Captured messages can be read back with [`CaptureReader`]:
+//! ```ignore
+//! for msg in CaptureReader::open("game.sc2capture")? {
+//!     match msg? {
+//!         Captured::Request(req) => println!("-> {:?}", req),
+//!         Captured::Response(res) => println!("<- {:?}", res),
+//!     }
+//! }
+//! ```
+
+use crate::client::SC2Result;
+use protobuf::Message;
+use sc2_proto::sc2api::{Request, Response};
+use std::{
+	fs::File,
+	io::{BufReader, BufWriter, ErrorKind, Read, Write},
+	path::Path,
+};
+
+/// Environment variable with path to capture file. If set, traffic is captured from the start.
+pub const ENV_CAPTURE: &str = "SC2_CAPTURE";
+
+const REQUEST: u8 = 0;
+const RESPONSE: u8 = 1;
+
+/// Message read from capture file.
+#[derive(Debug, Clone)]
+pub enum Captured {
+	/// Request sent by bot.
+	Request(Request),
+	/// Response received from SC2.
+	Response(Response),
+}
+
+pub(crate) struct CaptureWriter(BufWriter<File>);
+impl CaptureWriter {
+	pub(crate) fn create<P: AsRef<Path>>(path: P) -> SC2Result<Self> {
+		Ok(Self(BufWriter::new(File::create(path)?)))
+	}
+	pub(crate) fn request(&mut self, bytes: &[u8]) {
+		self.write(REQUEST, bytes);
+	}
+	pub(crate) fn response(&mut self, bytes: &[u8]) {
+		self.write(RESPONSE, bytes);
+	}
+	// Capture is a debugging aid, so failures are logged instead of breaking the game
+	fn write(&mut self, direction: u8, bytes: &[u8]) {
+		let result = self
+			.0
+			.write_all(&[direction])
+			.and_then(|_| self.0.write_all(&(bytes.len() as u32).to_le_bytes()))
+			.and_then(|_| self.0.write_all(bytes));
+		if let Err(e) = result {
+			error!("Can't write to capture file: {}", e);
+		}
+	}
+}
+impl Drop for CaptureWriter {
+	fn drop(&mut self) {
+		if let Err(e) = self.0.flush() {
+			error!("Can't flush capture file: {}", e);
+		}
+	}
+}
+
+/// Iterator over messages in capture file.
+pub struct CaptureReader<R: Read>(R);
+impl CaptureReader<BufReader<File>> {
+	/// Opens capture file at given path.
+	pub fn open<P: AsRef<Path>>(path: P) -> SC2Result<Self> {
+		Ok(Self(BufReader::new(File::open(path)?)))
+	}
+}
+impl<R: Read> CaptureReader<R> {
+	/// Reads capture from given reader.
+	pub fn new(reader: R) -> Self {
+		Self(reader)
+	}
+	fn read_message(&mut self) -> SC2Result<Option<Captured>> {
+		let mut direction = [0];
+		match self.0.read_exact(&mut direction) {
+			Ok(()) => {}
+			Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+			Err(e) => return Err(e.into()),
+		}
+		let mut len = [0; 4];
+		self.0.read_exact(&mut len)?;
+		let mut bytes = vec![0; u32::from_le_bytes(len) as usize];
+		self.0.read_exact(&mut bytes)?;
+
+		let msg = match direction[0] {
+			REQUEST => Captured::Request(Request::parse_from_bytes(&bytes)?),
+			RESPONSE => Captured::Response(Response::parse_from_bytes(&bytes)?),
+			d => return Err(format!("Invalid direction in capture file: {}", d).into()),
+		};
+		Ok(Some(msg))
+	}
+}
+impl<R: Read> Iterator for CaptureReader<R> {
+	type Item = SC2Result<Captured>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.read_message().transpose()
+	}
+}
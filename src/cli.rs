@@ -0,0 +1,88 @@
+//! Parsing of standard ladder arguments. Available with `"clap"` feature.
+//!
+//! Ladder managers start bots with `--LadderServer`, `--GamePort`, `--StartPort`
+//! and `--OpponentId` arguments. [`run`] parses them and joins ladder game if ports are given,
+//! otherwise calls local fallback, so bot's `main` can be as short as:
+//! ```ignore
+//! fn main() -> SC2Result<()> {
+//!     let mut bot = MyBot::default();
+//!     rust_sc2::cli::run(&mut bot, |bot| {
+//!         run_vs_computer(
+//!             bot,
+//!             Computer::new(Race::Random, Difficulty::VeryHard, None),
+//!             "EternalEmpireLE",
+//!             Default::default(),
+//!         )
+//!     })
+//! }
+//! ```
+
+use crate::{
+	bot::Bot,
+	client::{join_ladder_game, SC2Result},
+	Player,
+};
+use clap::Parser;
+use std::ops::{Deref, DerefMut};
+
+/// Address of ladder server used when `--LadderServer` isn't given.
+pub const DEFAULT_LADDER_SERVER: &str = "127.0.0.1";
+
+/// Standard arguments passed to bot by ladder manager.
+#[derive(Parser, Debug, Clone, Default)]
+pub struct LadderArgs {
+	/// Address of ladder server
+	#[clap(long = "LadderServer")]
+	pub ladder_server: Option<String>,
+	/// Port of game host
+	#[clap(long = "GamePort")]
+	pub game_port: Option<i32>,
+	/// Start of port range used by bot
+	#[clap(long = "StartPort")]
+	pub start_port: Option<i32>,
+	/// Id of opponent on the ladder
+	#[clap(long = "OpponentId")]
+	pub opponent_id: Option<String>,
+	/// Whether the game is played in realtime mode
+	#[clap(long = "RealTime")]
+	pub realtime: bool,
+}
+impl LadderArgs {
+	/// Parses arguments of current process. Exits with error message if they are invalid.
+	pub fn from_env() -> Self {
+		Self::parse()
+	}
+	/// Checks if both `--GamePort` and `--StartPort` are given, i.e. bot was started by ladder.
+	pub fn is_ladder(&self) -> bool {
+		self.game_port.is_some() && self.start_port.is_some()
+	}
+	/// Joins ladder game with parsed arguments if bot was started by ladder
+	/// (in realtime mode if `--RealTime` is given), otherwise calls `local` fallback.
+	pub fn run<B, F>(&self, bot: &mut B, local: F) -> SC2Result<()>
+	where
+		B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
+		F: FnOnce(&mut B) -> SC2Result<()>,
+	{
+		match (self.game_port, self.start_port) {
+			(Some(game_port), Some(start_port)) => join_ladder_game(
+				bot,
+				self.ladder_server.as_deref().unwrap_or(DEFAULT_LADDER_SERVER),
+				game_port,
+				start_port,
+				self.opponent_id.as_deref(),
+				self.realtime,
+			),
+			_ => local(bot),
+		}
+	}
+}
+
+/// Parses ladder arguments of current process and runs the game
+/// (see [`LadderArgs::run`]).
+pub fn run<B, F>(bot: &mut B, local: F) -> SC2Result<()>
+where
+	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
+	F: FnOnce(&mut B) -> SC2Result<()>,
+{
+	LadderArgs::from_env().run(bot, local)
+}
@@ -3,33 +3,55 @@
 //! Contains Runner structures for verbose configuration and multiple games,
 //! and simple runner functions for playing once.
 
+#[cfg(not(feature = "no-launch"))]
+use crate::paths::*;
 use crate::{
 	api::API,
 	bot::{Bot, LockOwned, Rs},
+	game_data::Attribute,
 	game_state::update_state,
-	paths::*,
-	player::Computer,
+	ids::UnitTypeId,
+	player::{Computer, GameEndReason, GameResult, GameSummary},
 	IntoProto, IntoSC2, Player, PlayerSettings,
 };
-use sc2_proto::sc2api::{PlayerSetup, PlayerType, PortSet, Request, RequestCreateGame, Status};
+use num_traits::FromPrimitive;
+use sc2_proto::{
+	raw::Alliance as ProtoAlliance,
+	sc2api::{PlayerSetup, PlayerType, PortSet, Request, RequestCreateGame, ResponseObservation, Status},
+};
+#[cfg(not(feature = "no-launch"))]
+use std::process::Command;
 use std::{
+	env,
 	error::Error,
 	fmt,
 	fs::File,
 	io::Write,
 	net::{TcpListener, TcpStream},
 	ops::{Deref, DerefMut},
-	process::{Child, Command},
+	panic::{self, AssertUnwindSafe},
+	process::Child,
+	time::{Instant, SystemTime, UNIX_EPOCH},
 };
 use tungstenite::{connect, stream::MaybeTlsStream, WebSocket};
 
 pub(crate) type WS = WebSocket<MaybeTlsStream<TcpStream>>;
 pub type SC2Result<T> = Result<T, Box<dyn Error>>;
 
-#[cfg(all(feature = "wine_sc2", not(target_os = "linux")))]
+#[cfg(all(feature = "wine_sc2", not(target_os = "linux"), not(feature = "no-launch")))]
 compile_error!("Wine is only supported on linux");
 
 const HOST: &str = "127.0.0.1";
+/// Environment variable with address of SC2 client. [Default: `127.0.0.1`]
+pub const ENV_HOST: &str = "SC2_HOST";
+/// Environment variable with port of SC2 client. Runners vs Human use next port for
+/// human's client. [Default: unused port is picked]
+pub const ENV_PORT: &str = "SC2_PORT";
+/// Environment variable, which tells runners not to launch SC2 when set to `1` or `true`.
+/// Clients should be already started and listening on [`SC2_HOST`](ENV_HOST) and
+/// [`SC2_PORT`](ENV_PORT).
+pub const ENV_EXTERNAL: &str = "SC2_EXTERNAL";
+#[cfg(not(feature = "no-launch"))]
 const SC2_BINARY: &str = {
 	#[cfg(any(target_os = "windows", feature = "wine_sc2"))]
 	{
@@ -76,20 +98,30 @@ const SC2_BINARY: &str = {
 	}
 };
 
+/// Binary of Windows installation, launched through Wine on Linux.
+#[cfg(not(feature = "no-launch"))]
+const WINE_SC2_BINARY: &str = if cfg!(target_arch = "x86") {
+	"SC2.exe"
+} else {
+	"SC2_x64.exe"
+};
+
 /// Runner for games vs built-in AI.
 pub struct RunnerSingle<'a, B>
 where
 	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
 {
 	bot: &'a mut B,
-	sc2_path: String,
+	sc2_path: Option<String>,
+	maps_path: Option<String>,
 	sc2_version: Option<&'a str>,
 	/// Computer opponent configuration.
 	pub computer: Computer,
-	map_path: String,
+	map: String,
 	/// Play games in real time mode or not.
 	pub realtime: bool,
 	/// Save replay after the game in given path.
+	/// If bot returns error or panics, replay is saved with `_crash` suffix.
 	pub save_replay_as: Option<&'a str>,
 }
 
@@ -100,32 +132,36 @@ where
 	/// Constructs new single player runner.
 	pub fn new(bot: &'a mut B, computer: Computer, map: &str, sc2_version: Option<&'a str>) -> Self {
 		debug!("Starting game vs computer");
-		let sc2_path = get_path_to_sc2();
-		let map_path = get_map_path(&sc2_path, map);
-
 		Self {
 			bot,
-			sc2_path,
+			sc2_path: None,
+			maps_path: None,
 			sc2_version,
 			computer,
-			map_path,
+			map: map.to_string(),
 			save_replay_as: None,
 			realtime: false,
 		}
 	}
 
 	/// Launches SC2 client and connects bot to the API.
+	///
+	/// Host, port and whether to launch SC2 can be configured with
+	/// [`SC2_HOST`](ENV_HOST), [`SC2_PORT`](ENV_PORT) and [`SC2_EXTERNAL`](ENV_EXTERNAL)
+	/// environment variables.
 	pub fn launch(&mut self) -> SC2Result<()> {
-		let port = get_unused_port();
+		let host = get_host();
+		let port = get_ports(1)?[0];
 		debug!("Launching SC2 process");
-		self.bot.process = Some(launch_client(&self.sc2_path, port, self.sc2_version));
+		self.bot.process = start_client(&mut self.sc2_path, port, self.sc2_version);
 		debug!("Connecting to websocket");
-		self.bot.api = Some(API::new(connect_to_websocket(HOST, port)?));
+		self.bot.api = Some(API::new_captured(connect_to_websocket(&host, port)?));
 		Ok(())
 	}
 
 	/// Runs requested game.
 	pub fn run_game(&mut self) -> SC2Result<()> {
+		let map_path = self.map_path();
 		let settings = self.bot.get_player_settings();
 		let api = self.bot.api();
 
@@ -133,9 +169,7 @@ where
 		let mut req = Request::new();
 		let req_create_game = req.mut_create_game();
 
-		req_create_game
-			.mut_local_map()
-			.set_map_path(self.map_path.clone());
+		req_create_game.mut_local_map().set_map_path(map_path);
 		create_player_setup(&settings, req_create_game);
 		create_computer_setup(&self.computer, req_create_game);
 
@@ -159,13 +193,7 @@ where
 
 		set_static_data(self.bot)?;
 
-		debug!("Entered main loop");
-		play_first_step(self.bot, self.realtime)?;
-		let mut iteration = 0;
-		while play_step(self.bot, iteration, self.realtime)? {
-			iteration += 1;
-		}
-		debug!("Game finished");
+		play_game(self.bot, self.realtime, self.save_replay_as)?;
 
 		if let Some(path) = &self.save_replay_as {
 			save_replay(self.bot.api(), path)?;
@@ -176,9 +204,24 @@ where
 	/// Changes map to play on.
 	///
 	/// # Panics
-	/// Panics if the map doesn't exist in maps directory.
+	/// [`run_game`](Self::run_game) panics if the map doesn't exist in maps directory.
 	pub fn set_map(&mut self, map: &str) {
-		self.map_path = get_map_path(&self.sc2_path, map);
+		self.map = map.to_string();
+	}
+
+	/// Sets path to SC2 installation, used instead of auto-discovered one.
+	/// Should be called before [`launch`](Self::launch).
+	pub fn set_sc2_path(&mut self, path: &str) {
+		self.sc2_path = Some(path.to_string());
+	}
+
+	/// Sets path to directory with maps. [Default: `Maps` directory of SC2 installation]
+	pub fn set_maps_path(&mut self, path: &str) {
+		self.maps_path = Some(path.to_string());
+	}
+
+	fn map_path(&mut self) -> String {
+		resolve_map_path(&mut self.sc2_path, self.maps_path.as_deref(), &self.map)
 	}
 
 	/// Manually closes SC2 client.
@@ -194,14 +237,16 @@ where
 {
 	bot: &'a mut B,
 	human: Human,
-	sc2_path: String,
+	sc2_path: Option<String>,
+	maps_path: Option<String>,
 	sc2_version: Option<&'a str>,
 	/// Configuration of human opponent.
 	pub human_settings: PlayerSettings<'a>,
-	map_path: String,
+	map: String,
 	/// Play games in real time mode or not.
 	pub realtime: bool,
 	/// Save replay after the game in given path.
+	/// If bot returns error or panics, replay is saved with `_crash` suffix.
 	pub save_replay_as: Option<&'a str>,
 }
 
@@ -217,42 +262,45 @@ where
 		sc2_version: Option<&'a str>,
 	) -> Self {
 		debug!("Starting human vs bot");
-		let sc2_path = get_path_to_sc2();
-		let map_path = get_map_path(&sc2_path, map);
-
 		Self {
 			bot,
 			human: Human::default(),
-			sc2_path,
+			sc2_path: None,
+			maps_path: None,
 			sc2_version,
 			human_settings,
-			map_path,
+			map: map.to_string(),
 			save_replay_as: None,
 			realtime: false,
 		}
 	}
 
 	/// Launches SC2 clients and connects bot to the API.
+	///
+	/// Host, ports and whether to launch SC2 can be configured with
+	/// [`SC2_HOST`](ENV_HOST), [`SC2_PORT`](ENV_PORT) and [`SC2_EXTERNAL`](ENV_EXTERNAL)
+	/// environment variables.
 	pub fn launch(&mut self) -> SC2Result<()> {
-		// let (port_bot, port_human) = (PORT, PORT + 1);
-		let ports = get_unused_ports(2);
+		let host = get_host();
+		let ports = get_ports(2)?;
 		let (port_bot, port_human) = (ports[0], ports[1]);
 
 		debug!("Launching host SC2 process");
-		self.human.process = Some(launch_client(&self.sc2_path, port_human, self.sc2_version));
+		self.human.process = start_client(&mut self.sc2_path, port_human, self.sc2_version);
 		debug!("Launching client SC2 process");
-		self.bot.process = Some(launch_client(&self.sc2_path, port_bot, self.sc2_version));
+		self.bot.process = start_client(&mut self.sc2_path, port_bot, self.sc2_version);
 
 		debug!("Connecting to host websocket");
-		self.human.api = Some(API::new(connect_to_websocket(HOST, port_human)?));
+		self.human.api = Some(API::new(connect_to_websocket(&host, port_human)?));
 		debug!("Connecting to client websocket");
-		self.bot.api = Some(API::new(connect_to_websocket(HOST, port_bot)?));
+		self.bot.api = Some(API::new_captured(connect_to_websocket(&host, port_bot)?));
 
 		Ok(())
 	}
 
 	/// Runs requested game.
 	pub fn run_game(&mut self) -> SC2Result<()> {
+		let map_path = self.map_path();
 		let bot_settings = self.bot.get_player_settings();
 		let human_api = self.human.api.as_ref().unwrap();
 
@@ -260,9 +308,7 @@ where
 		let mut req = Request::new();
 		let req_create_game = req.mut_create_game();
 
-		req_create_game
-			.mut_local_map()
-			.set_map_path(self.map_path.clone());
+		req_create_game.mut_local_map().set_map_path(map_path);
 		create_player_setup(&self.human_settings, req_create_game);
 		create_player_setup(&bot_settings, req_create_game);
 		req_create_game.set_realtime(self.realtime);
@@ -298,13 +344,7 @@ where
 
 		set_static_data(self.bot)?;
 
-		debug!("Entered main loop");
-		play_first_step(self.bot, self.realtime)?;
-		let mut iteration = 0;
-		while play_step(self.bot, iteration, self.realtime)? {
-			iteration += 1;
-		}
-		debug!("Game finished");
+		play_game(self.bot, self.realtime, self.save_replay_as)?;
 
 		if let Some(path) = &self.save_replay_as {
 			save_replay(self.bot.api(), path)?;
@@ -314,9 +354,24 @@ where
 
 	/// Changes map to play on.
 	/// # Panics
-	/// Panics if the map doesn't exist in maps directory.
+	/// [`run_game`](Self::run_game) panics if the map doesn't exist in maps directory.
 	pub fn set_map(&mut self, map: &str) {
-		self.map_path = get_map_path(&self.sc2_path, map);
+		self.map = map.to_string();
+	}
+
+	/// Sets path to SC2 installation, used instead of auto-discovered one.
+	/// Should be called before [`launch`](Self::launch).
+	pub fn set_sc2_path(&mut self, path: &str) {
+		self.sc2_path = Some(path.to_string());
+	}
+
+	/// Sets path to directory with maps. [Default: `Maps` directory of SC2 installation]
+	pub fn set_maps_path(&mut self, path: &str) {
+		self.maps_path = Some(path.to_string());
+	}
+
+	fn map_path(&mut self) -> String {
+		resolve_map_path(&mut self.sc2_path, self.maps_path.as_deref(), &self.map)
 	}
 
 	/// Manually closes SC2 clients.
@@ -381,14 +436,19 @@ struct Ports {
 }
 
 /// Additional launch options for [`run_vs_computer`] and [`run_vs_human`].
-#[derive(Default)]
+#[derive(Default, Clone, Copy)]
 pub struct LaunchOptions<'a> {
 	/// SC2 version to play on, otherwise latest available will be used.
 	pub sc2_version: Option<&'a str>,
 	/// Save replay after the game in given path.
+	/// If bot returns error or panics, replay is saved with `_crash` suffix.
 	pub save_replay_as: Option<&'a str>,
 	/// Play games in real time mode or not.
 	pub realtime: bool,
+	/// Path to SC2 installation, otherwise it will be discovered automatically.
+	pub sc2_path: Option<&'a str>,
+	/// Path to directory with maps, otherwise `Maps` directory of SC2 installation will be used.
+	pub maps_path: Option<&'a str>,
 }
 
 // Runners
@@ -404,6 +464,12 @@ where
 	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
 {
 	let mut runner = RunnerSingle::new(bot, computer, map_name, options.sc2_version);
+	if let Some(path) = options.sc2_path {
+		runner.set_sc2_path(path);
+	}
+	if let Some(path) = options.maps_path {
+		runner.set_maps_path(path);
+	}
 	runner.launch()?;
 	runner.realtime = options.realtime;
 	runner.save_replay_as = options.save_replay_as;
@@ -412,6 +478,9 @@ where
 }
 
 /// Simple function to join ladder game.
+///
+/// If bot returns error or panics, replay is saved as `crash_<timestamp>.SC2Replay`
+/// in the current directory.
 pub fn run_ladder_game<B>(
 	bot: &mut B,
 	host: &str,
@@ -419,13 +488,27 @@ pub fn run_ladder_game<B>(
 	player_port: i32,
 	opponent_id: Option<&str>,
 ) -> SC2Result<()>
+where
+	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
+{
+	join_ladder_game(bot, host, port, player_port, opponent_id, false)
+}
+// Ladder game in realtime mode is joined with `--RealTime` argument (see `cli` module)
+pub(crate) fn join_ladder_game<B>(
+	bot: &mut B,
+	host: &str,
+	port: i32,
+	player_port: i32,
+	opponent_id: Option<&str>,
+	realtime: bool,
+) -> SC2Result<()>
 where
 	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
 {
 	debug!("Starting ladder game");
 
 	debug!("Connecting to websocket");
-	bot.api = Some(API::new(connect_to_websocket(host, port)?));
+	bot.api = Some(API::new_captured(connect_to_websocket(host, port)?));
 
 	debug!("Sending JoinGame request");
 
@@ -446,14 +529,7 @@ where
 
 	set_static_data(bot)?;
 
-	debug!("Entered main loop");
-	// Main loop
-	let mut iteration = 0;
-	play_first_step(bot, false)?;
-	while play_step(bot, iteration, false)? {
-		iteration += 1;
-	}
-	debug!("Game finished");
+	play_game(bot, realtime, None)?;
 
 	Ok(())
 }
@@ -469,6 +545,12 @@ where
 	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
 {
 	let mut runner = RunnerMulti::new(bot, human_settings, map_name, options.sc2_version);
+	if let Some(path) = options.sc2_path {
+		runner.set_sc2_path(path);
+	}
+	if let Some(path) = options.maps_path {
+		runner.set_maps_path(path);
+	}
 	runner.launch()?;
 	runner.realtime = options.realtime;
 	runner.save_replay_as = options.save_replay_as;
@@ -476,13 +558,69 @@ where
 	Ok(())
 }
 
-// Portpicker
-fn get_unused_port() -> i32 {
-	(5000..65535)
-		.find(|port| TcpListener::bind((HOST, *port)).is_ok())
-		.unwrap() as i32
+// Environment
+fn get_host() -> String {
+	env::var(ENV_HOST).unwrap_or_else(|_| HOST.to_string())
+}
+
+#[cfg(not(feature = "no-launch"))]
+fn is_external() -> bool {
+	env::var(ENV_EXTERNAL).map_or(false, |val| val == "1" || val.eq_ignore_ascii_case("true"))
+}
+
+fn get_ports(n: usize) -> SC2Result<Vec<i32>> {
+	match env::var(ENV_PORT) {
+		Ok(port) => {
+			let port = port
+				.parse::<i32>()
+				.map_err(|_| format!("Invalid {}: {:?}", ENV_PORT, port))?;
+			Ok((port..port + n as i32).collect())
+		}
+		Err(_) => Ok(get_unused_ports(n)),
+	}
+}
+
+// Launches SC2 client unless it's external, path to SC2 is discovered if not set
+#[cfg(not(feature = "no-launch"))]
+fn start_client(sc2_path: &mut Option<String>, port: i32, sc2_version: Option<&str>) -> Option<Child> {
+	if is_external() {
+		debug!("Using external SC2 process");
+		return None;
+	}
+	let sc2_path = sc2_path.get_or_insert_with(get_path_to_sc2);
+	Some(launch_client(sc2_path, port, sc2_version))
+}
+#[cfg(feature = "no-launch")]
+fn start_client(_sc2_path: &mut Option<String>, _port: i32, _sc2_version: Option<&str>) -> Option<Child> {
+	debug!("Using external SC2 process");
+	None
 }
 
+// External SC2 may run on another machine, so map can't be checked locally
+#[cfg(not(feature = "no-launch"))]
+fn resolve_map_path(sc2_path: &mut Option<String>, maps_path: Option<&str>, map: &str) -> String {
+	if let (Some(maps), true) = (maps_path, is_external()) {
+		return format!("{}/{}.SC2Map", maps, map);
+	}
+	let sc2_path = sc2_path.get_or_insert_with(get_path_to_sc2);
+	match maps_path {
+		Some(maps) => get_map_path_in(maps, map, is_wine_installation(sc2_path)),
+		None if is_external() => format!("{}/Maps/{}.SC2Map", sc2_path, map),
+		None => get_map_path(sc2_path, map),
+	}
+}
+// Without maps path map is passed relative to maps directory of SC2
+#[cfg(feature = "no-launch")]
+fn resolve_map_path(sc2_path: &mut Option<String>, maps_path: Option<&str>, map: &str) -> String {
+	match (maps_path, sc2_path) {
+		(Some(maps), _) => format!("{}/{}.SC2Map", maps, map),
+		(None, Some(sc2)) => format!("{}/Maps/{}.SC2Map", sc2, map),
+		(None, None) => format!("{}.SC2Map", map),
+	}
+}
+
+// Portpicker
+
 fn get_unused_ports(n: usize) -> Vec<i32> {
 	let mut ports = Vec::with_capacity(n);
 	for port in 5000..65535 {
@@ -602,6 +740,59 @@ fn wait_join(api: &API) -> SC2Result<u32> {
 	}
 }
 
+// Runs main loop. If bot returns error or panics, replay is saved with "_crash" suffix
+// (or as "crash_<timestamp>" when replay path isn't set) before error is propagated.
+fn play_game<B>(bot: &mut B, realtime: bool, save_replay_as: Option<&str>) -> SC2Result<()>
+where
+	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
+{
+	debug!("Entered main loop");
+	let result = panic::catch_unwind(AssertUnwindSafe(|| -> SC2Result<()> {
+		play_first_step(bot, realtime)?;
+		let mut iteration = 0;
+		while play_step(bot, iteration, realtime)? {
+			iteration += 1;
+		}
+		Ok(())
+	}));
+	match result {
+		Ok(Ok(())) => {
+			debug!("Game finished");
+			Ok(())
+		}
+		Ok(Err(e)) => {
+			error!("Game crashed: {}", e);
+			save_crash_replay(bot, save_replay_as);
+			Err(e)
+		}
+		Err(payload) => {
+			error!("Game crashed: bot panicked");
+			save_crash_replay(bot, save_replay_as);
+			panic::resume_unwind(payload)
+		}
+	}
+}
+
+fn save_crash_replay(bot: &Bot, save_replay_as: Option<&str>) {
+	let path = match save_replay_as {
+		Some(path) => format!("{}_crash", path.trim_end_matches(".SC2Replay")),
+		None => {
+			let timestamp = SystemTime::now()
+				.duration_since(UNIX_EPOCH)
+				.map_or(0, |time| time.as_secs());
+			format!("crash_{}", timestamp)
+		}
+	};
+	let api = match &bot.api {
+		Some(api) => api,
+		None => return,
+	};
+	match save_replay(api, &path) {
+		Ok(()) => debug!("Crash replay saved as {}.SC2Replay", path),
+		Err(e) => error!("Can't save crash replay: {}", e),
+	}
+}
+
 fn play_first_step<B>(bot: &mut B, realtime: bool) -> SC2Result<()>
 where
 	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
@@ -615,21 +806,16 @@ where
 	bot.prepare_start();
 	bot.prepare_step();
 
+	if bot.get_player_settings().auto_gg {
+		crate::modules::auto_gg::enable(bot);
+	}
+	bot.plugins_start()?;
 	for e in events {
 		bot.on_event(e)?;
 	}
 	bot.on_start()?;
 
-	let bot_actions = bot.get_actions();
-	if !bot_actions.is_empty() {
-		let mut req = Request::new();
-		let actions = req.mut_action().mut_actions();
-		for a in bot_actions {
-			actions.push(a.into_proto());
-		}
-		bot.clear_actions();
-		bot.api().send_request(req)?;
-	}
+	send_actions(bot)?;
 	if !realtime {
 		let mut req = Request::new();
 		req.mut_step().set_count(bot.game_step.get_locked());
@@ -638,6 +824,10 @@ where
 	Ok(())
 }
 
+#[cfg_attr(
+	feature = "tracing",
+	tracing::instrument(name = "step", skip_all, fields(iteration = iteration))
+)]
 fn play_step<B>(bot: &mut B, iteration: usize, realtime: bool) -> SC2Result<bool>
 where
 	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
@@ -647,21 +837,69 @@ where
 	let res = bot.api().send(req)?;
 
 	if matches!(res.get_status(), Status::ended) {
-		let result = res.get_observation().get_player_result()[bot.player_id as usize - 1]
-			.get_result()
-			.into_sc2();
-		debug!("Result for bot: {:?}", result);
-		bot.on_end(result)?;
+		let res_obs = res.get_observation();
+		let result = res_obs
+			.get_player_result()
+			.iter()
+			.find(|r| r.get_player_id() == bot.player_id)
+			.map_or(GameResult::Undecided, |r| r.get_result().into_sc2());
+		let reason = end_reason(bot, res_obs, result);
+		debug!("Result for bot: {:?} ({:?})", result, reason);
+		bot.perf.requests = bot.api().request_timing();
+		if bot.perf.print_on_end {
+			info!("{}", bot.perf);
+		}
+		let obs = res_obs.get_observation();
+		let summary = GameSummary {
+			result,
+			reason,
+			game_loop: obs.get_game_loop(),
+			score: obs.get_score().into_sc2(),
+		};
+		bot.game_summary = Some(summary.clone());
+		bot.on_end_full(summary)?;
 		return Ok(false);
 	}
 
-	let events = update_state(bot, res.get_observation())?;
+	let step_start = Instant::now();
+	let mut section = Instant::now();
+	let mut record = |bot: &mut B, name| {
+		bot.perf.record(name, section.elapsed());
+		section = Instant::now();
+	};
+
+	let mut events = update_state(bot, res.get_observation())?;
 	bot.prepare_step();
+	bot.update_enemy_army(&events);
+	bot.update_roles(&events);
+	let hidden_events = bot.track_hidden_enemies(&events);
+	events.extend(hidden_events);
+	events.extend(bot.fire_timers());
+	record(bot, "update_state");
+
+	bot.manage_workers(&events);
+	bot.manage_supply();
+	bot.speed_mining_step(&events);
+	bot.auto_repair_step(&events);
+	bot.auto_burrow_step(&events);
+	bot.auto_gg_step();
+	record(bot, "managers");
+
+	bot.plugins_before_step(&events)?;
+	record(bot, "plugins_before_step");
+	bot.on_before_step(iteration)?;
+	record(bot, "on_before_step");
 
 	for e in events {
 		bot.on_event(e)?;
 	}
+	record(bot, "on_event");
 	bot.on_step(iteration)?;
+	record(bot, "on_step");
+	bot.plugins_after_step()?;
+	record(bot, "plugins_after_step");
+	bot.on_after_step(iteration)?;
+	record(bot, "on_after_step");
 	if bot.game_left {
 		let mut req = Request::new();
 		req.mut_leave_game();
@@ -669,24 +907,8 @@ where
 		return Ok(false);
 	}
 
-	let bot_actions = bot.get_actions();
-	if !bot_actions.is_empty() {
-		// println!("{:?}: {:?}", iteration, bot_actions);
-		let mut req = Request::new();
-		let actions = req.mut_action().mut_actions();
-		for a in bot_actions {
-			actions.push(a.into_proto());
-		}
-		bot.clear_actions();
-		bot.api().send_request(req)?;
-		/*
-		let res = api.send(req);
-		let results = res.get_action().get_result();
-		if !results.is_empty() {
-			println!("action_results: {:?}", results);
-		}
-		*/
-	}
+	send_actions(bot)?;
+	record(bot, "send_actions");
 
 	let bot_debug_commands = bot.get_debug_commands();
 	if !bot_debug_commands.is_empty() {
@@ -698,6 +920,10 @@ where
 		bot.clear_debug_commands();
 		bot.api().send_request(req)?;
 	}
+	bot.perf.step.add(step_start.elapsed());
+	bot.perf.requests = bot.api().request_timing();
+	bot.watchdog_step();
+
 	if !realtime {
 		let mut req = Request::new();
 		req.mut_step().set_count(bot.game_step.get_locked());
@@ -706,6 +932,52 @@ where
 	Ok(true)
 }
 
+// Game is won by elimination if loser has no structures in the last observation,
+// enemy structures include snapshots, so they are seen even out of vision.
+fn end_reason(bot: &Bot, res_obs: &ResponseObservation, result: GameResult) -> GameEndReason {
+	let has_structures = |alliance: ProtoAlliance| {
+		res_obs
+			.get_observation()
+			.get_raw_data()
+			.get_units()
+			.iter()
+			.filter(|u| u.get_alliance() == alliance)
+			.filter_map(|u| UnitTypeId::from_u32(u.get_unit_type()))
+			.any(|id| {
+				bot.game_data
+					.units
+					.get(&id)
+					.is_some_and(|data| data.attributes.contains(&Attribute::Structure))
+			})
+	};
+	let loser = match result {
+		GameResult::Victory => ProtoAlliance::Enemy,
+		GameResult::Defeat => ProtoAlliance::value_Self,
+		GameResult::Tie => return GameEndReason::Timeout,
+		GameResult::Undecided => return GameEndReason::Disconnect,
+	};
+	if has_structures(loser) {
+		GameEndReason::Surrender
+	} else {
+		GameEndReason::Elimination
+	}
+}
+
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+fn send_actions(bot: &mut Bot) -> SC2Result<()> {
+	let bot_actions = bot.get_actions();
+	if !bot_actions.is_empty() {
+		let mut req = Request::new();
+		let actions = req.mut_action().mut_actions();
+		for a in bot_actions {
+			actions.push(a.into_proto());
+		}
+		bot.clear_actions();
+		bot.api().send_request(req)?;
+	}
+	Ok(())
+}
+
 fn save_replay(api: &API, path: &str) -> SC2Result<()> {
 	let mut req = Request::new();
 	req.mut_save_replay();
@@ -721,37 +993,33 @@ fn save_replay(api: &API, path: &str) -> SC2Result<()> {
 	Ok(())
 }
 
+#[cfg(not(feature = "no-launch"))]
 fn launch_client(sc2_path: &str, port: i32, sc2_version: Option<&str>) -> Child {
 	let (base_version, data_hash) = match sc2_version {
 		Some(ver) => get_version_info(ver),
 		None => (get_latest_base_version(sc2_path), ""),
 	};
-	let sc2_full_path = format!("{}/Versions/Base{}/{}", sc2_path, base_version, SC2_BINARY);
+	let wine = is_wine_installation(sc2_path);
+	let binary = if wine { WINE_SC2_BINARY } else { SC2_BINARY };
+	let sc2_full_path = format!("{}/Versions/Base{}/{}", sc2_path, base_version, binary);
 
-	let mut process = if cfg!(feature = "wine_sc2") {
-		let wine = std::env::var("WINE").unwrap_or_else(|_| "wine".to_string());
+	let mut process = if wine {
+		let wine = env::var("WINE").unwrap_or_else(|_| "wine".to_string());
 		let mut command = Command::new(wine);
 		command.arg(sc2_full_path);
 		command
 	} else {
 		Command::new(sc2_full_path)
 	};
-	let cwd = {
-		#[cfg(any(target_os = "windows", feature = "wine_sc2"))]
-		{
-			#[cfg(target_arch = "x86_64")]
-			{
-				format!("{}/Support64", sc2_path)
-			}
-			#[cfg(target_arch = "x86")]
-			{
-				format!("{}/Support", sc2_path)
-			}
-		}
-		#[cfg(any(target_os = "macos", all(target_os = "linux", not(feature = "wine_sc2"))))]
-		{
-			sc2_path
-		}
+	let cwd = if cfg!(target_os = "windows") || wine {
+		let support = if cfg!(target_arch = "x86") {
+			"Support"
+		} else {
+			"Support64"
+		};
+		format!("{}/{}", sc2_path, support)
+	} else {
+		sc2_path.to_string()
 	};
 	process
 		.current_dir(cwd)
@@ -294,6 +294,53 @@ lazy_static! {
 		UnitTypeId::Zergling => UnitTypeId::ZerglingBurrowed,
 		UnitTypeId::ZerglingBurrowed => UnitTypeId::Zergling,
 	];
+	/// Upgraded and alternative forms of unit or structure, which count as it.
+	/// Unlike aliases it goes only one way: lairs count as hatcheries, but hatcheries don't count as lairs.
+	pub(crate) static ref UNIT_FORMS: HashMap<UnitTypeId, Vec<UnitTypeId>> = hashmap![
+		// Terran
+		UnitTypeId::CommandCenter => vec![
+			UnitTypeId::CommandCenterFlying,
+			UnitTypeId::OrbitalCommand,
+			UnitTypeId::OrbitalCommandFlying,
+			UnitTypeId::PlanetaryFortress,
+		],
+		UnitTypeId::OrbitalCommand => vec![UnitTypeId::OrbitalCommandFlying],
+		UnitTypeId::SupplyDepot => vec![UnitTypeId::SupplyDepotLowered],
+		UnitTypeId::Refinery => vec![UnitTypeId::RefineryRich],
+		UnitTypeId::Barracks => vec![UnitTypeId::BarracksFlying],
+		UnitTypeId::Factory => vec![UnitTypeId::FactoryFlying],
+		UnitTypeId::Starport => vec![UnitTypeId::StarportFlying],
+		UnitTypeId::SiegeTank => vec![UnitTypeId::SiegeTankSieged],
+		UnitTypeId::WidowMine => vec![UnitTypeId::WidowMineBurrowed],
+		UnitTypeId::Thor => vec![UnitTypeId::ThorAP],
+		UnitTypeId::VikingFighter => vec![UnitTypeId::VikingAssault],
+		UnitTypeId::Liberator => vec![UnitTypeId::LiberatorAG],
+		// Zerg
+		UnitTypeId::Hatchery => vec![UnitTypeId::Lair, UnitTypeId::Hive],
+		UnitTypeId::Lair => vec![UnitTypeId::Hive],
+		UnitTypeId::Spire => vec![UnitTypeId::GreaterSpire],
+		UnitTypeId::Extractor => vec![UnitTypeId::ExtractorRich],
+		UnitTypeId::SpineCrawler => vec![UnitTypeId::SpineCrawlerUprooted],
+		UnitTypeId::SporeCrawler => vec![UnitTypeId::SporeCrawlerUprooted],
+		UnitTypeId::Overlord => vec![UnitTypeId::OverlordTransport],
+		UnitTypeId::Overseer => vec![UnitTypeId::OverseerSiegeMode],
+		UnitTypeId::Drone => vec![UnitTypeId::DroneBurrowed],
+		UnitTypeId::Queen => vec![UnitTypeId::QueenBurrowed],
+		UnitTypeId::Zergling => vec![UnitTypeId::ZerglingBurrowed],
+		UnitTypeId::Baneling => vec![UnitTypeId::BanelingBurrowed],
+		UnitTypeId::Roach => vec![UnitTypeId::RoachBurrowed],
+		UnitTypeId::Ravager => vec![UnitTypeId::RavagerBurrowed],
+		UnitTypeId::Hydralisk => vec![UnitTypeId::HydraliskBurrowed],
+		UnitTypeId::LurkerMP => vec![UnitTypeId::LurkerMPBurrowed],
+		UnitTypeId::Infestor => vec![UnitTypeId::InfestorBurrowed],
+		UnitTypeId::SwarmHostMP => vec![UnitTypeId::SwarmHostBurrowedMP],
+		UnitTypeId::Ultralisk => vec![UnitTypeId::UltraliskBurrowed],
+		// Protoss
+		UnitTypeId::Gateway => vec![UnitTypeId::WarpGate],
+		UnitTypeId::Assimilator => vec![UnitTypeId::AssimilatorRich],
+		UnitTypeId::Observer => vec![UnitTypeId::ObserverSiegeMode],
+		UnitTypeId::WarpPrism => vec![UnitTypeId::WarpPrismPhasing],
+	];
 	/// Tech requirements mapped to different units.
 	///
 	/// Basic usage:
@@ -684,6 +731,216 @@ lazy_static! {
 		UpgradeId::EnhancedShockwaves => UnitTypeId::GhostAcademy,
 	];
 
+	/// Abilities used to train, build or morph units mapped to unit types.
+	///
+	/// Abilities are specific (e.g. `BarracksTrainMarine`), the same as in orders of producers.
+	pub static ref TRAIN_ABILITIES: HashMap<UnitTypeId, AbilityId> = hashmap![
+		UnitTypeId::Adept => AbilityId::TrainAdept,
+		UnitTypeId::Archon => AbilityId::MorphArchon,
+		UnitTypeId::Armory => AbilityId::TerranBuildArmory,
+		UnitTypeId::Assimilator => AbilityId::ProtossBuildAssimilator,
+		UnitTypeId::AutoTurret => AbilityId::BuildAutoTurretAutoTurret,
+		UnitTypeId::Baneling => AbilityId::MorphZerglingToBanelingBaneling,
+		UnitTypeId::BanelingNest => AbilityId::ZergBuildBanelingNest,
+		UnitTypeId::Banshee => AbilityId::StarportTrainBanshee,
+		UnitTypeId::Barracks => AbilityId::TerranBuildBarracks,
+		UnitTypeId::Battlecruiser => AbilityId::StarportTrainBattlecruiser,
+		UnitTypeId::BroodLord => AbilityId::MorphToBroodLordBroodLord,
+		UnitTypeId::Bunker => AbilityId::TerranBuildBunker,
+		UnitTypeId::Carrier => AbilityId::StargateTrainCarrier,
+		UnitTypeId::Changeling => AbilityId::SpawnChangelingSpawnChangeling,
+		UnitTypeId::Colossus => AbilityId::RoboticsFacilityTrainColossus,
+		UnitTypeId::CommandCenter => AbilityId::TerranBuildCommandCenter,
+		UnitTypeId::Corruptor => AbilityId::LarvaTrainCorruptor,
+		UnitTypeId::CreepTumor => AbilityId::BuildCreepTumorTumor,
+		UnitTypeId::CreepTumorQueen => AbilityId::BuildCreepTumorQueen,
+		UnitTypeId::CyberneticsCore => AbilityId::ProtossBuildCyberneticsCore,
+		UnitTypeId::Cyclone => AbilityId::TrainCyclone,
+		UnitTypeId::DarkShrine => AbilityId::ProtossBuildDarkShrine,
+		UnitTypeId::DarkTemplar => AbilityId::GatewayTrainDarkTemplar,
+		UnitTypeId::Disruptor => AbilityId::TrainDisruptor,
+		UnitTypeId::Drone => AbilityId::LarvaTrainDrone,
+		UnitTypeId::EngineeringBay => AbilityId::TerranBuildEngineeringBay,
+		UnitTypeId::EvolutionChamber => AbilityId::ZergBuildEvolutionChamber,
+		UnitTypeId::Extractor => AbilityId::ZergBuildExtractor,
+		UnitTypeId::Factory => AbilityId::TerranBuildFactory,
+		UnitTypeId::FleetBeacon => AbilityId::ProtossBuildFleetBeacon,
+		UnitTypeId::Forge => AbilityId::ProtossBuildForge,
+		UnitTypeId::FusionCore => AbilityId::TerranBuildFusionCore,
+		UnitTypeId::Gateway => AbilityId::ProtossBuildGateway,
+		UnitTypeId::Ghost => AbilityId::BarracksTrainGhost,
+		UnitTypeId::GhostAcademy => AbilityId::TerranBuildGhostAcademy,
+		UnitTypeId::GreaterSpire => AbilityId::UpgradeToGreaterSpireGreaterSpire,
+		UnitTypeId::Hatchery => AbilityId::ZergBuildHatchery,
+		UnitTypeId::Hellion => AbilityId::FactoryTrainHellion,
+		UnitTypeId::HellionTank => AbilityId::TrainHellbat,
+		UnitTypeId::HighTemplar => AbilityId::GatewayTrainHighTemplar,
+		UnitTypeId::Hive => AbilityId::UpgradeToHiveHive,
+		UnitTypeId::Hydralisk => AbilityId::LarvaTrainHydralisk,
+		UnitTypeId::HydraliskDen => AbilityId::ZergBuildHydraliskDen,
+		UnitTypeId::Immortal => AbilityId::RoboticsFacilityTrainImmortal,
+		UnitTypeId::InfestationPit => AbilityId::ZergBuildInfestationPit,
+		UnitTypeId::Infestor => AbilityId::LarvaTrainInfestor,
+		UnitTypeId::Lair => AbilityId::UpgradeToLairLair,
+		UnitTypeId::Liberator => AbilityId::StarportTrainLiberator,
+		UnitTypeId::LurkerDenMP => AbilityId::BuildLurkerDen,
+		UnitTypeId::LocustMPFlying => AbilityId::EffectSpawnLocusts,
+		UnitTypeId::LurkerMP => AbilityId::MorphLurker,
+		UnitTypeId::Marauder => AbilityId::BarracksTrainMarauder,
+		UnitTypeId::Marine => AbilityId::BarracksTrainMarine,
+		UnitTypeId::Medivac => AbilityId::StarportTrainMedivac,
+		UnitTypeId::MissileTurret => AbilityId::TerranBuildMissileTurret,
+		UnitTypeId::Mothership => AbilityId::NexusTrainMothershipMothership,
+		UnitTypeId::Mutalisk => AbilityId::LarvaTrainMutalisk,
+		UnitTypeId::Nexus => AbilityId::ProtossBuildNexus,
+		UnitTypeId::NydusCanal => AbilityId::BuildNydusWorm,
+		UnitTypeId::NydusNetwork => AbilityId::ZergBuildNydusNetwork,
+		UnitTypeId::Observer => AbilityId::RoboticsFacilityTrainObserver,
+		UnitTypeId::Oracle => AbilityId::StargateTrainOracle,
+		UnitTypeId::OracleStasisTrap => AbilityId::BuildStasisTrap,
+		UnitTypeId::OrbitalCommand => AbilityId::UpgradeToOrbitalOrbitalCommand,
+		UnitTypeId::Overlord => AbilityId::LarvaTrainOverlord,
+		UnitTypeId::OverlordTransport => AbilityId::MorphOverlordTransport,
+		UnitTypeId::Overseer => AbilityId::MorphOverseer,
+		UnitTypeId::Phoenix => AbilityId::StargateTrainPhoenix,
+		UnitTypeId::PhotonCannon => AbilityId::ProtossBuildPhotonCannon,
+		UnitTypeId::PlanetaryFortress => AbilityId::UpgradeToPlanetaryFortressPlanetaryFortress,
+		UnitTypeId::Probe => AbilityId::NexusTrainProbe,
+		UnitTypeId::Pylon => AbilityId::ProtossBuildPylon,
+		UnitTypeId::Queen => AbilityId::TrainQueenQueen,
+		UnitTypeId::Ravager => AbilityId::MorphToRavagerRavager,
+		UnitTypeId::Raven => AbilityId::StarportTrainRaven,
+		UnitTypeId::Reaper => AbilityId::BarracksTrainReaper,
+		UnitTypeId::Refinery => AbilityId::TerranBuildRefinery,
+		UnitTypeId::Roach => AbilityId::LarvaTrainRoach,
+		UnitTypeId::RoachWarren => AbilityId::ZergBuildRoachWarren,
+		UnitTypeId::RoboticsBay => AbilityId::ProtossBuildRoboticsBay,
+		UnitTypeId::RoboticsFacility => AbilityId::ProtossBuildRoboticsFacility,
+		UnitTypeId::SCV => AbilityId::CommandCenterTrainSCV,
+		UnitTypeId::SensorTower => AbilityId::TerranBuildSensorTower,
+		UnitTypeId::Sentry => AbilityId::GatewayTrainSentry,
+		UnitTypeId::ShieldBattery => AbilityId::BuildShieldBattery,
+		UnitTypeId::SiegeTank => AbilityId::FactoryTrainSiegeTank,
+		UnitTypeId::SpawningPool => AbilityId::ZergBuildSpawningPool,
+		UnitTypeId::SpineCrawler => AbilityId::ZergBuildSpineCrawler,
+		UnitTypeId::Spire => AbilityId::ZergBuildSpire,
+		UnitTypeId::SporeCrawler => AbilityId::ZergBuildSporeCrawler,
+		UnitTypeId::Stalker => AbilityId::GatewayTrainStalker,
+		UnitTypeId::Stargate => AbilityId::ProtossBuildStargate,
+		UnitTypeId::Starport => AbilityId::TerranBuildStarport,
+		UnitTypeId::SupplyDepot => AbilityId::TerranBuildSupplyDepot,
+		UnitTypeId::SwarmHostMP => AbilityId::TrainSwarmHost,
+		UnitTypeId::Tempest => AbilityId::StargateTrainTempest,
+		UnitTypeId::TemplarArchive => AbilityId::ProtossBuildTemplarArchive,
+		UnitTypeId::Thor => AbilityId::FactoryTrainThor,
+		UnitTypeId::TwilightCouncil => AbilityId::ProtossBuildTwilightCouncil,
+		UnitTypeId::Ultralisk => AbilityId::LarvaTrainUltralisk,
+		UnitTypeId::UltraliskCavern => AbilityId::ZergBuildUltraliskCavern,
+		UnitTypeId::VikingFighter => AbilityId::StarportTrainVikingFighter,
+		UnitTypeId::Viper => AbilityId::LarvaTrainViper,
+		UnitTypeId::VoidRay => AbilityId::StargateTrainVoidRay,
+		UnitTypeId::WarpPrism => AbilityId::RoboticsFacilityTrainWarpPrism,
+		UnitTypeId::WidowMine => AbilityId::FactoryTrainWidowMine,
+		UnitTypeId::Zealot => AbilityId::GatewayTrainZealot,
+		UnitTypeId::Zergling => AbilityId::LarvaTrainZergling,
+	];
+	/// Abilities used to produce units mapped to unit types, inverse of [`TRAIN_ABILITIES`].
+	///
+	/// Also contains abilities used to warp in units with warpgates.
+	pub static ref ABILITY_UNITS: HashMap<AbilityId, UnitTypeId> = TRAIN_ABILITIES
+		.iter()
+		.chain(WARPGATE_ABILITIES.iter())
+		.map(|(unit, ability)| (*ability, *unit))
+		.collect();
+	/// Abilities used to research upgrades mapped to upgrades.
+	pub static ref RESEARCH_ABILITIES: HashMap<UpgradeId, AbilityId> = hashmap![
+		UpgradeId::AdeptPiercingAttack => AbilityId::ResearchAdeptResonatingGlaives,
+		UpgradeId::AnabolicSynthesis => AbilityId::ResearchAnabolicSynthesis,
+		UpgradeId::BansheeCloak => AbilityId::ResearchBansheeCloakingField,
+		UpgradeId::BansheeSpeed => AbilityId::ResearchBansheeHyperflightRotors,
+		UpgradeId::BattlecruiserEnableSpecializations => AbilityId::ResearchBattlecruiserWeaponRefit,
+		UpgradeId::BlinkTech => AbilityId::ResearchBlink,
+		UpgradeId::Burrow => AbilityId::ResearchBurrow,
+		UpgradeId::Charge => AbilityId::ResearchCharge,
+		UpgradeId::ChitinousPlating => AbilityId::ResearchChitinousPlating,
+		UpgradeId::CycloneLockOnDamageUpgrade => AbilityId::ResearchCycloneLockOnDamage,
+		UpgradeId::DarkTemplarBlinkUpgrade => AbilityId::ResearchShadowStrike,
+		UpgradeId::DiggingClaws => AbilityId::ResearchAdaptiveTalons,
+		UpgradeId::DrillClaws => AbilityId::ResearchDrillingClaws,
+		UpgradeId::EvolveGroovedSpines => AbilityId::ResearchGroovedSpines,
+		UpgradeId::EvolveMuscularAugments => AbilityId::ResearchMuscularAugments,
+		UpgradeId::ExtendedThermalLance => AbilityId::ResearchExtendedThermalLance,
+		UpgradeId::GraviticDrive => AbilityId::ResearchGraviticDrive,
+		UpgradeId::HighCapacityBarrels => AbilityId::ResearchInfernalPreigniter,
+		UpgradeId::HiSecAutoTracking => AbilityId::ResearchHiSecAutoTracking,
+		UpgradeId::LiberatorMorph => AbilityId::StarportTechLabResearchLiberatorAGMode,
+		UpgradeId::NeuralParasite => AbilityId::ResearchNeuralParasite,
+		UpgradeId::ObserverGraviticBooster => AbilityId::ResearchGraviticBooster,
+		UpgradeId::Overlordspeed => AbilityId::ResearchPneumatizedCarapace,
+		UpgradeId::PersonalCloaking => AbilityId::ResearchPersonalCloaking,
+		UpgradeId::PhoenixRangeUpgrade => AbilityId::ResearchPhoenixAnionPulseCrystals,
+		UpgradeId::ProtossAirArmorsLevel1 => AbilityId::CyberneticsCoreResearchProtossAirArmorLevel1,
+		UpgradeId::ProtossAirArmorsLevel2 => AbilityId::CyberneticsCoreResearchProtossAirArmorLevel2,
+		UpgradeId::ProtossAirArmorsLevel3 => AbilityId::CyberneticsCoreResearchProtossAirArmorLevel3,
+		UpgradeId::ProtossAirWeaponsLevel1 => AbilityId::CyberneticsCoreResearchProtossAirWeaponsLevel1,
+		UpgradeId::ProtossAirWeaponsLevel2 => AbilityId::CyberneticsCoreResearchProtossAirWeaponsLevel2,
+		UpgradeId::ProtossAirWeaponsLevel3 => AbilityId::CyberneticsCoreResearchProtossAirWeaponsLevel3,
+		UpgradeId::ProtossGroundArmorsLevel1 => AbilityId::ForgeResearchProtossGroundArmorLevel1,
+		UpgradeId::ProtossGroundArmorsLevel2 => AbilityId::ForgeResearchProtossGroundArmorLevel2,
+		UpgradeId::ProtossGroundArmorsLevel3 => AbilityId::ForgeResearchProtossGroundArmorLevel3,
+		UpgradeId::ProtossGroundWeaponsLevel1 => AbilityId::ForgeResearchProtossGroundWeaponsLevel1,
+		UpgradeId::ProtossGroundWeaponsLevel2 => AbilityId::ForgeResearchProtossGroundWeaponsLevel2,
+		UpgradeId::ProtossGroundWeaponsLevel3 => AbilityId::ForgeResearchProtossGroundWeaponsLevel3,
+		UpgradeId::ProtossShieldsLevel1 => AbilityId::ForgeResearchProtossShieldsLevel1,
+		UpgradeId::ProtossShieldsLevel2 => AbilityId::ForgeResearchProtossShieldsLevel2,
+		UpgradeId::ProtossShieldsLevel3 => AbilityId::ForgeResearchProtossShieldsLevel3,
+		UpgradeId::PsiStormTech => AbilityId::ResearchPsiStorm,
+		UpgradeId::PunisherGrenades => AbilityId::ResearchConcussiveShells,
+		UpgradeId::RavenCorvidReactor => AbilityId::ResearchRavenCorvidReactor,
+		UpgradeId::ShieldWall => AbilityId::ResearchCombatShield,
+		UpgradeId::SmartServos => AbilityId::ResearchSmartServos,
+		UpgradeId::Stimpack => AbilityId::BarracksTechLabResearchStimpack,
+		UpgradeId::TerranBuildingArmor => AbilityId::ResearchTerranStructureArmorUpgrade,
+		UpgradeId::TerranInfantryArmorsLevel1 => AbilityId::EngineeringBayResearchTerranInfantryArmorLevel1,
+		UpgradeId::TerranInfantryArmorsLevel2 => AbilityId::EngineeringBayResearchTerranInfantryArmorLevel2,
+		UpgradeId::TerranInfantryArmorsLevel3 => AbilityId::EngineeringBayResearchTerranInfantryArmorLevel3,
+		UpgradeId::TerranInfantryWeaponsLevel1 => AbilityId::EngineeringBayResearchTerranInfantryWeaponsLevel1,
+		UpgradeId::TerranInfantryWeaponsLevel2 => AbilityId::EngineeringBayResearchTerranInfantryWeaponsLevel2,
+		UpgradeId::TerranInfantryWeaponsLevel3 => AbilityId::EngineeringBayResearchTerranInfantryWeaponsLevel3,
+		UpgradeId::TerranShipWeaponsLevel1 => AbilityId::ArmoryResearchTerranShipWeaponsLevel1,
+		UpgradeId::TerranShipWeaponsLevel2 => AbilityId::ArmoryResearchTerranShipWeaponsLevel2,
+		UpgradeId::TerranShipWeaponsLevel3 => AbilityId::ArmoryResearchTerranShipWeaponsLevel3,
+		UpgradeId::TerranVehicleWeaponsLevel1 => AbilityId::ArmoryResearchTerranVehicleWeaponsLevel1,
+		UpgradeId::TerranVehicleWeaponsLevel2 => AbilityId::ArmoryResearchTerranVehicleWeaponsLevel2,
+		UpgradeId::TerranVehicleWeaponsLevel3 => AbilityId::ArmoryResearchTerranVehicleWeaponsLevel3,
+		UpgradeId::TerranVehicleAndShipArmorsLevel1 => AbilityId::ArmoryResearchTerranVehicleAndShipPlatingLevel1,
+		UpgradeId::TerranVehicleAndShipArmorsLevel2 => AbilityId::ArmoryResearchTerranVehicleAndShipPlatingLevel2,
+		UpgradeId::TerranVehicleAndShipArmorsLevel3 => AbilityId::ArmoryResearchTerranVehicleAndShipPlatingLevel3,
+		UpgradeId::WarpGateResearch => AbilityId::ResearchWarpGate,
+		UpgradeId::ZergFlyerArmorsLevel1 => AbilityId::ResearchZergFlyerArmorLevel1,
+		UpgradeId::ZergFlyerArmorsLevel2 => AbilityId::ResearchZergFlyerArmorLevel2,
+		UpgradeId::ZergFlyerArmorsLevel3 => AbilityId::ResearchZergFlyerArmorLevel3,
+		UpgradeId::ZergFlyerWeaponsLevel1 => AbilityId::ResearchZergFlyerAttackLevel1,
+		UpgradeId::ZergFlyerWeaponsLevel2 => AbilityId::ResearchZergFlyerAttackLevel2,
+		UpgradeId::ZergFlyerWeaponsLevel3 => AbilityId::ResearchZergFlyerAttackLevel3,
+		UpgradeId::ZergGroundArmorsLevel1 => AbilityId::ResearchZergGroundArmorLevel1,
+		UpgradeId::ZergGroundArmorsLevel2 => AbilityId::ResearchZergGroundArmorLevel2,
+		UpgradeId::ZergGroundArmorsLevel3 => AbilityId::ResearchZergGroundArmorLevel3,
+		UpgradeId::Zerglingattackspeed => AbilityId::ResearchZerglingAdrenalGlands,
+		UpgradeId::Zerglingmovementspeed => AbilityId::ResearchZerglingMetabolicBoost,
+		UpgradeId::ZergMeleeWeaponsLevel1 => AbilityId::ResearchZergMeleeWeaponsLevel1,
+		UpgradeId::ZergMeleeWeaponsLevel2 => AbilityId::ResearchZergMeleeWeaponsLevel2,
+		UpgradeId::ZergMeleeWeaponsLevel3 => AbilityId::ResearchZergMeleeWeaponsLevel3,
+		UpgradeId::ZergMissileWeaponsLevel1 => AbilityId::ResearchZergMissileWeaponsLevel1,
+		UpgradeId::ZergMissileWeaponsLevel2 => AbilityId::ResearchZergMissileWeaponsLevel2,
+		UpgradeId::ZergMissileWeaponsLevel3 => AbilityId::ResearchZergMissileWeaponsLevel3,
+		UpgradeId::EnhancedShockwaves => AbilityId::GhostAcademyResearchEnhancedShockwaves,
+	];
+	/// Upgrades mapped to abilities used to research them, inverse of [`RESEARCH_ABILITIES`].
+	pub static ref ABILITY_UPGRADES: HashMap<AbilityId, UpgradeId> = RESEARCH_ABILITIES
+		.iter()
+		.map(|(upgrade, ability)| (*ability, *upgrade))
+		.collect();
 	pub(crate) static ref DAMAGE_BONUS_PER_UPGRADE: HashMap<UnitTypeId, BonusesForTarget> = hashmap![
 		// Protoss
 		UnitTypeId::Probe => hashmap![TargetType::Ground => (Some(0), hashmap![])],
@@ -758,6 +1015,23 @@ lazy_static! {
 		UnitTypeId::Hydralisk => (UpgradeId::EvolveMuscularAugments, 1.25),
 		UnitTypeId::Ultralisk => (UpgradeId::AnabolicSynthesis, 1.2),
 	];
+	pub(crate) static ref SIGHT_UPGRADES: HashMap<UnitTypeId, (UpgradeId, f32)> = hashmap![
+		UnitTypeId::MissileTurret => (UpgradeId::HiSecAutoTracking, 1.0),
+		UnitTypeId::AutoTurret => (UpgradeId::HiSecAutoTracking, 1.0),
+		UnitTypeId::PointDefenseDrone => (UpgradeId::HiSecAutoTracking, 1.0),
+		UnitTypeId::PlanetaryFortress => (UpgradeId::HiSecAutoTracking, 1.0),
+	];
+	/// Detection ranges of detectors, used when SC2 doesn't report it (e.g. for enemy units).
+	pub static ref DETECTION_RANGES: HashMap<UnitTypeId, f32> = hashmap![
+		UnitTypeId::Observer => 11.0,
+		UnitTypeId::ObserverSiegeMode => 13.75,
+		UnitTypeId::Raven => 11.0,
+		UnitTypeId::Overseer => 11.0,
+		UnitTypeId::OverseerSiegeMode => 13.75,
+		UnitTypeId::MissileTurret => 11.0,
+		UnitTypeId::SporeCrawler => 11.0,
+		UnitTypeId::PhotonCannon => 11.0,
+	];
 	pub(crate) static ref SPEED_ON_CREEP: HashMap<UnitTypeId, f32> = hashmap![
 		UnitTypeId::Queen => 2.67,
 		UnitTypeId::Zergling => 1.3,
@@ -869,6 +1143,62 @@ lazy_static! {
 		UnitTypeId::InhibitorZoneFlyingMedium => 5.0,
 		UnitTypeId::InhibitorZoneFlyingLarge => 6.0,
 	];
+	/// Durations (in seconds) of temporary buffs, used to estimate when they expire.
+	pub static ref BUFF_DURATIONS: HashMap<BuffId, f32> = hashmap![
+		BuffId::Stimpack => 11.0,
+		BuffId::StimpackMarauder => 11.0,
+		BuffId::ChronoBoostEnergyCost => 20.0,
+		BuffId::QueenSpawnLarvaTimer => 29.0,
+		BuffId::FungalGrowth => 2.85,
+	];
+	/// Durations (in seconds) of effects, used to estimate when they end.
+	/// For Corrosive Bile and Nuke it's time until they land.
+	pub static ref EFFECT_DURATIONS: HashMap<EffectId, f32> = hashmap![
+		EffectId::PsiStormPersistent => 2.85,
+		EffectId::RavagerCorrosiveBileCP => 2.5,
+		EffectId::NukePersistent => 14.0,
+		EffectId::ScannerSweep => 12.3,
+	];
+	/// Energy costs of abilities, which require energy.
+	pub static ref ENERGY_COSTS: HashMap<AbilityId, u32> = hashmap![
+		// Terran
+		AbilityId::CalldownMULECalldownMULE => 50,
+		AbilityId::SupplyDropSupplyDrop => 50,
+		AbilityId::ScannerSweepScan => 50,
+		AbilityId::EMPEMP => 75,
+		AbilityId::EffectGhostSnipe => 50,
+		AbilityId::BehaviorCloakOnGhost => 25,
+		AbilityId::BehaviorCloakOnBanshee => 25,
+		AbilityId::EffectInterferenceMatrix => 75,
+		AbilityId::EffectAntiArmorMissile => 75,
+		AbilityId::BuildAutoTurretAutoTurret => 50,
+		// Zerg
+		AbilityId::EffectInjectLarva => 25,
+		AbilityId::BuildCreepTumorQueen => 25,
+		AbilityId::TransfusionTransfusion => 50,
+		AbilityId::FungalGrowthFungalGrowth => 75,
+		AbilityId::NeuralParasiteNeuralParasite => 100,
+		AbilityId::ContaminateContaminate => 125,
+		AbilityId::BlindingCloudBlindingCloud => 100,
+		AbilityId::EffectAbduct => 75,
+		AbilityId::ParasiticBombParasiticBomb => 125,
+		AbilityId::SpawnChangelingSpawnChangeling => 50,
+		// Protoss
+		AbilityId::PsiStormPsiStorm => 75,
+		AbilityId::FeedbackFeedback => 50,
+		AbilityId::GuardianShieldGuardianShield => 75,
+		AbilityId::HallucinationArchon => 75,
+		AbilityId::HallucinationPhoenix => 75,
+		AbilityId::HallucinationStalker => 75,
+		AbilityId::HallucinationZealot => 75,
+		AbilityId::GravitonBeamGravitonBeam => 50,
+		AbilityId::OracleRevelationOracleRevelation => 25,
+		AbilityId::BuildStasisTrap => 50,
+		AbilityId::BehaviorPulsarBeamOn => 25,
+		AbilityId::EffectChronoBoostEnergyCost => 50,
+		AbilityId::EffectMassRecallNexus => 50,
+		AbilityId::EffectTimeWarp => 75,
+	];
 	pub(crate) static ref SPEED_BUFFS: HashMap<BuffId, f32> = hashmap![
 		BuffId::Stimpack => 1.5,
 		BuffId::StimpackMarauder => 1.5,
@@ -882,3 +1212,35 @@ lazy_static! {
 		BuffId::AccelerationZoneFlyingTemporalField => 1.35,
 	];
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn ability_units_is_inverse_of_train_abilities() {
+		for (unit, ability) in TRAIN_ABILITIES.iter() {
+			assert_eq!(ABILITY_UNITS.get(ability), Some(unit), "{:?}", ability);
+		}
+		assert_eq!(
+			ABILITY_UNITS.len(),
+			TRAIN_ABILITIES.len() + WARPGATE_ABILITIES.len()
+		);
+	}
+
+	#[test]
+	fn warpgate_units_can_be_trained() {
+		for (unit, ability) in WARPGATE_ABILITIES.iter() {
+			assert!(TRAIN_ABILITIES.contains_key(unit), "{:?}", unit);
+			assert_eq!(ABILITY_UNITS.get(ability), Some(unit), "{:?}", ability);
+		}
+	}
+
+	#[test]
+	fn produced_units_have_train_abilities() {
+		let produced = PRODUCERS.keys().chain(ALL_PRODUCERS.keys());
+		for unit in produced {
+			assert!(TRAIN_ABILITIES.contains_key(unit), "{:?}", unit);
+		}
+	}
+}
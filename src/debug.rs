@@ -24,6 +24,7 @@ pub struct Debugger {
 	debug_commands: Vec<DebugCommand>,
 	debug_drawings: Vec<DebugDraw>,
 	kill_tags: FxHashSet<u64>,
+	toggles: FxHashSet<DebugToggle>,
 }
 impl Debugger {
 	pub(crate) fn get_commands(&mut self) -> &[DebugCommand] {
@@ -78,6 +79,80 @@ impl Debugger {
 	pub fn draw_sphere(&mut self, pos: Point3, radius: f32, color: Option<Color>) {
 		self.debug_drawings.push(DebugDraw::Sphere(pos, radius, color));
 	}
+	/// Draws lines in game world connecting given points one by one.
+	pub fn draw_polyline(&mut self, points: &[Point3], color: Option<Color>) {
+		for pair in points.windows(2) {
+			self.draw_line(pair[0], pair[1], color);
+		}
+	}
+	/// Draws closed polygon in game world with given vertices.
+	pub fn draw_polygon(&mut self, points: &[Point3], color: Option<Color>) {
+		self.draw_polyline(points, color);
+		if points.len() > 2 {
+			self.draw_line(points[points.len() - 1], points[0], color);
+		}
+	}
+	/// Draws arrow in game world pointing from `p0` to `p1`.
+	pub fn draw_arrow(&mut self, p0: Point3, p1: Point3, color: Option<Color>) {
+		self.draw_line(p0, p1, color);
+
+		let dir = Point2::new(p1.x - p0.x, p1.y - p0.y);
+		let len = dir.len();
+		if len == 0.0 {
+			return;
+		}
+		let head = (len * 0.25).min(1.0);
+		let back = dir / len * head;
+		let side = back.rotate90(true) * 0.5;
+		for side in [side, -side] {
+			let end = Point3::new(p1.x - back.x + side.x, p1.y - back.y + side.y, p1.z);
+			self.draw_line(p1, end, color);
+		}
+	}
+	/// Draws outline of rectangle on the terrain at height `z` with corners `p0` and `p1`.
+	pub fn draw_rect(&mut self, p0: Point2, p1: Point2, z: f32, color: Option<Color>) {
+		self.draw_polygon(
+			&[
+				p0.to3(z),
+				Point3::new(p1.x, p0.y, z),
+				p1.to3(z),
+				Point3::new(p0.x, p1.y, z),
+			],
+			color,
+		);
+	}
+	/// Draws filled rectangle on the terrain at height `z` with corners `p0` and `p1`.
+	///
+	/// Rectangle is filled with dense lines, so drawing many big rectangles is expensive.
+	/// For grids (e.g. influence maps) draw one rectangle per tile.
+	pub fn fill_rect(&mut self, p0: Point2, p1: Point2, z: f32, color: Option<Color>) {
+		const STEP: f32 = 0.2;
+
+		let (x0, x1) = (p0.x.min(p1.x), p0.x.max(p1.x));
+		let (y0, y1) = (p0.y.min(p1.y), p0.y.max(p1.y));
+		let mut y = y0;
+		while y < y1 {
+			self.draw_line(Point3::new(x0, y, z), Point3::new(x1, y, z), color);
+			y += STEP;
+		}
+		self.draw_rect(Point2::new(x0, y0), Point2::new(x1, y1), z, color);
+	}
+	/// Draws panel of text lines in game window with 2d coordinates, where (0, 0) is left upper corner
+	/// and (1, 1) is right bottom corner.
+	pub fn draw_text_panel<S: AsRef<str>>(
+		&mut self,
+		lines: &[S],
+		pos: ScreenPos,
+		color: Option<Color>,
+		size: Option<u32>,
+	) {
+		let text = lines
+			.iter()
+			.map(|line| line.as_ref())
+			.collect::<Vec<_>>()
+			.join("\n");
+		self.draw_text(&text, DebugPos::Screen(pos), color, size);
+	}
 	/// Spawns units using given commands in format: (unit type, owner's player id, position, count).
 	pub fn create_units<'a, T>(&mut self, cmds: T)
 	where
@@ -104,6 +179,48 @@ impl Debugger {
 				.map(|(tag, unit_value, value)| DebugCommand::SetUnitValue(tag, unit_value, value)),
 		);
 	}
+	/// Spawns `count` units of given type for player with given id (bot itself if `None`).
+	pub fn spawn(&mut self, unit: UnitTypeId, owner: Option<u32>, pos: Point2, count: u32) {
+		self.debug_commands
+			.push(DebugCommand::CreateUnit(unit, owner, pos, count));
+	}
+	/// Kills unit with given tag.
+	pub fn kill(&mut self, tag: u64) {
+		self.kill_tags.insert(tag);
+	}
+	/// Sets health of unit with given tag.
+	pub fn set_health(&mut self, tag: u64, value: u32) {
+		self.debug_commands
+			.push(DebugCommand::SetUnitValue(tag, UnitValue::Health, value));
+	}
+	/// Sets shield of unit with given tag.
+	pub fn set_shield(&mut self, tag: u64, value: u32) {
+		self.debug_commands
+			.push(DebugCommand::SetUnitValue(tag, UnitValue::Shield, value));
+	}
+	/// Sets energy of unit with given tag.
+	pub fn set_energy(&mut self, tag: u64, value: u32) {
+		self.debug_commands
+			.push(DebugCommand::SetUnitValue(tag, UnitValue::Energy, value));
+	}
+	/// Gives at least given amount of minerals to the bot.
+	///
+	/// Debug API can only give 5000 minerals at once,
+	/// so amount is rounded up to multiple of 5000.
+	pub fn give_minerals(&mut self, amount: u32) {
+		for _ in 0..amount.div_ceil(5000) {
+			self.cheat_minerals();
+		}
+	}
+	/// Gives at least given amount of vespene to the bot.
+	///
+	/// Debug API can only give 5000 vespene at once,
+	/// so amount is rounded up to multiple of 5000.
+	pub fn give_vespene(&mut self, amount: u32) {
+		for _ in 0..amount.div_ceil(5000) {
+			self.cheat_gas();
+		}
+	}
 	/// Ends game with Victory for bot
 	pub fn win_game(&mut self) {
 		self.debug_commands.push(DebugCommand::EndGame(true));
@@ -112,25 +229,71 @@ impl Debugger {
 	pub fn end_game(&mut self) {
 		self.debug_commands.push(DebugCommand::EndGame(false));
 	}
-	/// Disables fog of war, makes all map visible
+	/// Disables fog of war, makes all map visible (using it again reverts the effect)
 	pub fn show_map(&mut self) {
-		self.debug_commands
-			.push(DebugCommand::GameState(DebugGameState::ShowMap));
+		self.toggle(DebugToggle::ShowMap);
 	}
-	/// Gives ability to control enemy units
+	/// Gives ability to control enemy units (using it again reverts the effect)
 	pub fn control_enemy(&mut self) {
-		self.debug_commands
-			.push(DebugCommand::GameState(DebugGameState::ControlEnemy));
+		self.toggle(DebugToggle::ControlEnemy);
 	}
-	/// Disables supply usage
+	/// Disables supply usage (using it again reverts the effect)
 	pub fn cheat_supply(&mut self) {
-		self.debug_commands
-			.push(DebugCommand::GameState(DebugGameState::Food));
+		self.toggle(DebugToggle::NoSupply);
 	}
-	/// Makes free all units, structures and upgrades
+	/// Makes free all units, structures and upgrades (using it again reverts the effect)
 	pub fn cheat_free_build(&mut self) {
+		self.toggle(DebugToggle::FreeBuild);
+	}
+	fn toggle(&mut self, toggle: DebugToggle) {
+		if !self.toggles.remove(&toggle) {
+			self.toggles.insert(toggle);
+		}
 		self.debug_commands
-			.push(DebugCommand::GameState(DebugGameState::Free));
+			.push(DebugCommand::GameState(toggle.game_state()));
+	}
+	/// Enables or disables given game state cheat.
+	/// Does nothing if cheat is already in requested state.
+	pub fn set_toggle(&mut self, toggle: DebugToggle, enabled: bool) {
+		if self.is_toggled(toggle) != enabled {
+			self.toggle(toggle);
+		}
+	}
+	/// Checks if given game state cheat is enabled.
+	pub fn is_toggled(&self, toggle: DebugToggle) -> bool {
+		self.toggles.contains(&toggle)
+	}
+	/// Enables or disables vision of the whole map.
+	pub fn set_show_map(&mut self, enabled: bool) {
+		self.set_toggle(DebugToggle::ShowMap, enabled);
+	}
+	/// Enables or disables control of enemy units.
+	pub fn set_control_enemy(&mut self, enabled: bool) {
+		self.set_toggle(DebugToggle::ControlEnemy, enabled);
+	}
+	/// Enables or disables invincibility and increased damage of bot's units.
+	pub fn set_god(&mut self, enabled: bool) {
+		self.set_toggle(DebugToggle::God, enabled);
+	}
+	/// Enables or disables free units, structures and upgrades.
+	pub fn set_free_build(&mut self, enabled: bool) {
+		self.set_toggle(DebugToggle::FreeBuild, enabled);
+	}
+	/// Enables or disables fast making of units, structures and upgrades.
+	pub fn set_fast_build(&mut self, enabled: bool) {
+		self.set_toggle(DebugToggle::FastBuild, enabled);
+	}
+	/// Enables or disables supply usage.
+	pub fn set_no_supply(&mut self, enabled: bool) {
+		self.set_toggle(DebugToggle::NoSupply, enabled);
+	}
+	/// Enables or disables cooldown of abilities.
+	pub fn set_no_cooldown(&mut self, enabled: bool) {
+		self.set_toggle(DebugToggle::NoCooldown, enabled);
+	}
+	/// Enables or disables tech requirements.
+	pub fn set_ignore_tech(&mut self, enabled: bool) {
+		self.set_toggle(DebugToggle::IgnoreTech, enabled);
 	}
 	/// Gives 5000 minerals and gas to the bot
 	pub fn cheat_resources(&mut self) {
@@ -147,20 +310,17 @@ impl Debugger {
 		self.debug_commands
 			.push(DebugCommand::GameState(DebugGameState::Gas));
 	}
-	/// Makes all bot's units invincible and significantly increases their damage
+	/// Makes all bot's units invincible and significantly increases their damage (using it again reverts the effect)
 	pub fn cheat_god(&mut self) {
-		self.debug_commands
-			.push(DebugCommand::GameState(DebugGameState::God));
+		self.toggle(DebugToggle::God);
 	}
-	/// Removes cooldown of abilities of bot's units
+	/// Removes cooldown of abilities of bot's units (using it again reverts the effect)
 	pub fn cheat_cooldown(&mut self) {
-		self.debug_commands
-			.push(DebugCommand::GameState(DebugGameState::Cooldown));
+		self.toggle(DebugToggle::NoCooldown);
 	}
-	/// Removes all tech requirements for bot
+	/// Removes all tech requirements for bot (using it again reverts the effect)
 	pub fn cheat_tech_tree(&mut self) {
-		self.debug_commands
-			.push(DebugCommand::GameState(DebugGameState::TechTree));
+		self.toggle(DebugToggle::IgnoreTech);
 	}
 	/// First use: researches all upgrades for units and sets level 1 of damage and armor upgrades
 	///
@@ -173,10 +333,9 @@ impl Debugger {
 		self.debug_commands
 			.push(DebugCommand::GameState(DebugGameState::Upgrade));
 	}
-	/// Significantly increases speed of making units, structures and upgrades
+	/// Significantly increases speed of making units, structures and upgrades (using it again reverts the effect)
 	pub fn cheat_fast_build(&mut self) {
-		self.debug_commands
-			.push(DebugCommand::GameState(DebugGameState::FastBuild));
+		self.toggle(DebugToggle::FastBuild);
 	}
 }
 
@@ -326,6 +485,41 @@ impl IntoProto<DebugSetUnitValue_UnitValue> for UnitValue {
 	}
 }
 
+/// Game state cheats, which can be switched on and off with [`set_toggle`](Debugger::set_toggle).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DebugToggle {
+	/// Vision of the whole map.
+	ShowMap,
+	/// Control of enemy units.
+	ControlEnemy,
+	/// Invincibility and increased damage of bot's units.
+	God,
+	/// Free units, structures and upgrades.
+	FreeBuild,
+	/// Fast making of units, structures and upgrades.
+	FastBuild,
+	/// No supply usage.
+	NoSupply,
+	/// No cooldown of abilities.
+	NoCooldown,
+	/// No tech requirements.
+	IgnoreTech,
+}
+impl DebugToggle {
+	fn game_state(self) -> DebugGameState {
+		match self {
+			DebugToggle::ShowMap => DebugGameState::ShowMap,
+			DebugToggle::ControlEnemy => DebugGameState::ControlEnemy,
+			DebugToggle::God => DebugGameState::God,
+			DebugToggle::FreeBuild => DebugGameState::Free,
+			DebugToggle::FastBuild => DebugGameState::FastBuild,
+			DebugToggle::NoSupply => DebugGameState::Food,
+			DebugToggle::NoCooldown => DebugGameState::Cooldown,
+			DebugToggle::IgnoreTech => DebugGameState::TechTree,
+		}
+	}
+}
+
 #[derive(Debug, Clone, Copy)]
 pub(crate) enum DebugGameState {
 	ShowMap,
@@ -0,0 +1,93 @@
+//! Dodging of dangerous effects: Psionic Storms, Corrosive Biles, Nukes and Liberator zones.
+//!
+//! ```ignore
+//! for u in &self.units.my.units {
+//!     if !self.dodge_effects(u) {
+//!         // Usual micro
+//!     }
+//! }
+//! ```
+
+use crate::{
+	action::Target,
+	bot::Bot,
+	distance::*,
+	game_state::{EffectKind, TimedEffect},
+	geometry::Point2,
+	unit::Unit,
+};
+use std::f32::consts::TAU;
+
+/// Radius of Tactical Nuke explosion.
+pub const NUKE_RADIUS: f32 = 8.0;
+/// Extra distance kept from the border of dangerous area.
+const DODGE_GAP: f32 = 0.5;
+/// Distance between rings of candidate positions.
+const RING_STEP: f32 = 0.5;
+/// Maximum distance from unit to checked positions.
+const MAX_DODGE_DISTANCE: f32 = 10.0;
+/// Number of candidate positions on each ring.
+const DIRECTIONS: usize = 16;
+
+fn danger_radius(e: &TimedEffect, u: &Unit) -> Option<f32> {
+	match e.kind {
+		EffectKind::PsiStorm | EffectKind::CorrosiveBile => Some(e.radius),
+		EffectKind::Nuke => Some(NUKE_RADIUS),
+		EffectKind::LiberatorZone if e.alliance.is_enemy() && !u.is_flying() => Some(e.radius),
+		_ => None,
+	}
+}
+
+impl Bot {
+	/// Returns areas of effects, which can damage given unit, as (center, radius) pairs.
+	///
+	/// Psionic Storms, Corrosive Biles and Nukes are dangerous regardless of owner,
+	/// enemy Liberator zones are dangerous only for ground units.
+	pub fn dangerous_areas(&self, u: &Unit) -> Vec<(Point2, f32)> {
+		self.state
+			.effects()
+			.iter()
+			.filter_map(|e| Some((e, danger_radius(e, u)?)))
+			.flat_map(|(e, radius)| e.positions.iter().map(move |p| (*p, radius)))
+			.collect()
+	}
+	/// Returns the closest position to given unit outside of all
+	/// [dangerous areas](Self::dangerous_areas), or `None` if unit is already safe
+	/// or there's no safe position within 10 distance.
+	///
+	/// Positions for ground units are checked to be pathable.
+	pub fn dodge_position(&self, u: &Unit) -> Option<Point2> {
+		let areas = self.dangerous_areas(u);
+		let gap = u.radius() + DODGE_GAP;
+		let is_safe = |pos: Point2| {
+			areas
+				.iter()
+				.all(|(center, radius)| center.is_further(radius + gap, pos))
+		};
+
+		let pos = u.position();
+		if is_safe(pos) {
+			return None;
+		}
+		let rings = (MAX_DODGE_DISTANCE / RING_STEP) as usize;
+		(1..=rings)
+			.flat_map(|ring| {
+				(0..DIRECTIONS).map(move |i| {
+					pos.towards_angle(i as f32 * TAU / DIRECTIONS as f32, ring as f32 * RING_STEP)
+				})
+			})
+			.find(|p| is_safe(*p) && (u.is_flying() || self.is_pathable(*p)))
+	}
+	/// Moves given unit out of dangerous areas (see [`dodge_position`](Self::dodge_position)).
+	///
+	/// Returns `true` if unit was ordered to move.
+	pub fn dodge_effects(&self, u: &Unit) -> bool {
+		match self.dodge_position(u) {
+			Some(pos) => {
+				u.move_to(Target::Pos(pos), false);
+				true
+			}
+			None => false,
+		}
+	}
+}
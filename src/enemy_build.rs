@@ -0,0 +1,241 @@
+//! Recognition of opponent's opening by scouted information.
+//!
+//! Bot remembers every enemy structure it has seen with estimated construction start time,
+//! first appearance of enemy unit types and enemy workers in own base.
+//! Based on that [`enemy_build`](Bot::enemy_build) guesses opponent's build with heuristics.
+
+use crate::{
+	bot::Bot, consts::FRAMES_PER_SECOND, distance::*, geometry::Point2, ids::UnitTypeId,
+	placement::PRODUCTION_STRUCTURES,
+};
+use rustc_hash::FxHashMap;
+
+// Second townhall started before that time (in seconds) is a fast expand.
+const FAST_EXPAND_TIME: f32 = 65.0;
+// Second townhall started before that time is a standard expand.
+const STANDARD_EXPAND_TIME: f32 = 150.0;
+// Spawning pool started before that time is an early pool.
+const EARLY_POOL_TIME: f32 = 35.0;
+// Gas started before that time is an early gas.
+const EARLY_GAS_TIME: f32 = 45.0;
+// After that time opponent without expansion is considered to be on one base.
+const ONE_BASE_TIME: f32 = 150.0;
+// Openings are recognized only before that time.
+const OPENING_END_TIME: f32 = 300.0;
+
+/// Opening of opponent, recognized by [`enemy_build`](Bot::enemy_build).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum EnemyBuild {
+	/// Not enough information.
+	#[default]
+	Unknown,
+	/// Production structures built outside of opponent's bases.
+	Proxy,
+	/// Opponent attacks with workers.
+	WorkerRush,
+	/// Early aggression from one base (e.g. early pool or mass gateways).
+	OneBaseAllIn,
+	/// Expansion before any production (hatch first, CC first, nexus first).
+	FastExpand,
+	/// Expansion at usual time after first production structure.
+	Standard,
+}
+
+/// Guess of opponent's opening with confidence from `0.0` to `1.0`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BuildGuess {
+	/// Recognized opening.
+	pub build: EnemyBuild,
+	/// How sure the guess is, from `0.0` to `1.0`.
+	pub confidence: f32,
+}
+
+/// Information about opponent collected while scouting.
+#[derive(Debug, Clone, Default)]
+pub struct EnemyIntel {
+	/// Time in seconds, when each enemy unit type was first seen.
+	pub first_seen: FxHashMap<UnitTypeId, f32>,
+	/// Enemy structures ever seen: (tag, (type, position, estimated start time)).
+	pub structures: FxHashMap<u64, (UnitTypeId, Point2, f32)>,
+	/// Largest number of enemy workers seen in own main base at the same time.
+	pub workers_in_base: usize,
+	/// Last time, when opponent's natural was visible without townhall on it.
+	pub natural_empty: Option<f32>,
+}
+impl EnemyIntel {
+	/// Returns the earliest estimated start time of structure with one of given types.
+	pub fn started(&self, types: &[UnitTypeId]) -> Option<f32> {
+		self.structures
+			.values()
+			.filter(|(t, _, _)| types.contains(t))
+			.map(|(_, _, start)| *start)
+			.min_by(|a, b| a.partial_cmp(b).unwrap())
+	}
+	/// Returns start times of all enemy townhalls in ascending order.
+	pub fn townhalls(&self) -> Vec<f32> {
+		let mut times = self
+			.structures
+			.values()
+			.filter(|(t, _, _)| t.is_townhall())
+			.map(|(_, _, start)| *start)
+			.collect::<Vec<_>>();
+		times.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+		times
+	}
+}
+
+impl Bot {
+	/// Returns information about opponent collected while scouting.
+	pub fn enemy_intel(&self) -> &EnemyIntel {
+		&self.enemy_intel
+	}
+	/// Guesses opponent's opening by scouted information.
+	///
+	/// Recognition uses estimated start times of seen structures,
+	/// so the earlier and more often opponent's bases are scouted, the better is the guess.
+	/// Returns [`Unknown`](EnemyBuild::Unknown) with zero confidence if nothing is known yet.
+	pub fn enemy_build(&self) -> BuildGuess {
+		let intel = &self.enemy_intel;
+		let mut guesses = Vec::new();
+
+		if intel.workers_in_base >= 4 {
+			guesses.push((
+				EnemyBuild::WorkerRush,
+				if intel.workers_in_base >= 6 { 0.9 } else { 0.6 },
+			));
+		}
+
+		let enemy_bases = self.enemy_bases();
+		let proxies = intel
+			.structures
+			.values()
+			.filter(|(t, pos, start)| {
+				*start < OPENING_END_TIME
+					&& PRODUCTION_STRUCTURES.contains(t)
+					&& !enemy_bases.iter().any(|base| pos.is_closer(30.0, *base))
+			})
+			.count();
+		if proxies > 0 {
+			guesses.push((EnemyBuild::Proxy, if proxies > 1 { 0.95 } else { 0.85 }));
+		}
+
+		let townhalls = intel.townhalls();
+		let expand = townhalls.get(1).copied();
+		let first_production = intel
+			.started(&PRODUCTION_STRUCTURES)
+			.into_iter()
+			.chain(intel.started(&[UnitTypeId::SpawningPool]))
+			.min_by(|a, b| a.partial_cmp(b).unwrap());
+		match expand {
+			Some(time) if time <= FAST_EXPAND_TIME || first_production.is_some_and(|p| time < p) => {
+				guesses.push((EnemyBuild::FastExpand, 0.8));
+			}
+			Some(time) if time <= STANDARD_EXPAND_TIME => guesses.push((EnemyBuild::Standard, 0.7)),
+			Some(_) => guesses.push((EnemyBuild::Standard, 0.4)),
+			None => {}
+		}
+
+		let early_pool = intel
+			.started(&[UnitTypeId::SpawningPool])
+			.is_some_and(|t| t <= EARLY_POOL_TIME);
+		if early_pool && !expand.is_some_and(|t| t <= STANDARD_EXPAND_TIME) {
+			guesses.push((EnemyBuild::OneBaseAllIn, 0.85));
+		} else if expand.is_none() && intel.natural_empty.is_some_and(|t| t >= ONE_BASE_TIME) {
+			let production = intel
+				.structures
+				.values()
+				.filter(|(t, _, start)| *start < OPENING_END_TIME && PRODUCTION_STRUCTURES.contains(t))
+				.count();
+			let early_gas = intel
+				.started(&[
+					UnitTypeId::Refinery,
+					UnitTypeId::RefineryRich,
+					UnitTypeId::Assimilator,
+					UnitTypeId::AssimilatorRich,
+					UnitTypeId::Extractor,
+					UnitTypeId::ExtractorRich,
+				])
+				.is_some_and(|t| t <= EARLY_GAS_TIME);
+
+			let mut confidence = 0.5 + 0.15 * production.saturating_sub(1).min(3) as f32;
+			if early_gas {
+				confidence += 0.1;
+			}
+			guesses.push((EnemyBuild::OneBaseAllIn, confidence.min(0.95)));
+		}
+
+		guesses
+			.into_iter()
+			.max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+			.map_or_else(Default::default, |(build, confidence)| BuildGuess {
+				build,
+				confidence,
+			})
+	}
+
+	// Opponent's main and natural.
+	fn enemy_bases(&self) -> Vec<Point2> {
+		let start = self.enemy_start;
+		let natural = self
+			.expansions
+			.iter()
+			.filter(|exp| exp.loc.is_further(1.0, start))
+			.min_by(|a, b| {
+				a.loc
+					.distance_squared(start)
+					.partial_cmp(&b.loc.distance_squared(start))
+					.unwrap()
+			})
+			.map(|exp| exp.loc);
+		let mut bases = vec![start];
+		bases.extend(natural);
+		bases
+	}
+
+	pub(crate) fn update_enemy_intel(&mut self) {
+		let time = self.time;
+		if time > OPENING_END_TIME {
+			return;
+		}
+
+		let mut intel = std::mem::take(&mut self.enemy_intel);
+		for u in self.units.enemy.all.iter() {
+			intel.first_seen.entry(u.type_id()).or_insert(time);
+			if u.is_structure() && !intel.structures.contains_key(&u.tag()) {
+				let build_time = self
+					.game_data
+					.units
+					.get(&u.type_id())
+					.map_or(0.0, |data| data.build_time / FRAMES_PER_SECOND);
+				let start = (time - u.build_progress() * build_time).max(0.0);
+				intel
+					.structures
+					.insert(u.tag(), (u.type_id(), u.position(), start));
+			}
+		}
+
+		let in_base = self
+			.units
+			.enemy
+			.workers
+			.iter()
+			.filter(|u| u.is_closer(25.0, self.start_location))
+			.count();
+		intel.workers_in_base = intel.workers_in_base.max(in_base);
+
+		if let Some(&natural) = self.enemy_bases().get(1) {
+			if self.is_visible(natural)
+				&& !self
+					.units
+					.enemy
+					.townhalls
+					.iter()
+					.any(|th| th.is_closer(3.0, natural))
+			{
+				intel.natural_empty = Some(time);
+			}
+		}
+
+		self.enemy_intel = intel;
+	}
+}
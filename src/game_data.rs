@@ -2,6 +2,8 @@
 #![allow(missing_docs)]
 
 use crate::{
+	action::Target,
+	consts::ENERGY_COSTS,
 	ids::{AbilityId, BuffId, EffectId, UnitTypeId, UpgradeId},
 	player::Race,
 	FromProto, TryFromProto,
@@ -35,6 +37,45 @@ pub struct GameData {
 	/// Information about effects mapped to `EffectId`s.
 	pub effects: FxHashMap<EffectId, EffectData>,
 }
+impl GameData {
+	/// Returns chain of unit aliases starting from given type, not including it
+	/// (e.g. burrowed and unburrowed versions of units).
+	pub fn unit_alias_chain(&self, unit: UnitTypeId) -> Vec<UnitTypeId> {
+		let mut chain = Vec::new();
+		let mut current = unit;
+		while let Some(alias) = self.units.get(&current).and_then(|data| data.unit_alias) {
+			if alias == unit || chain.contains(&alias) {
+				break;
+			}
+			chain.push(alias);
+			current = alias;
+		}
+		chain
+	}
+	/// Returns all types, which given type counts as for tech requirements
+	/// (e.g. Hive counts as Lair and Hatchery), following tech aliases recursively.
+	/// Given type isn't included.
+	pub fn tech_alias_chain(&self, unit: UnitTypeId) -> Vec<UnitTypeId> {
+		let mut chain: Vec<UnitTypeId> = Vec::new();
+		let mut i = 0;
+		let mut current = unit;
+		loop {
+			if let Some(data) = self.units.get(&current) {
+				for alias in &data.tech_alias {
+					if *alias != unit && !chain.contains(alias) {
+						chain.push(*alias);
+					}
+				}
+			}
+			match chain.get(i) {
+				Some(next) => current = *next,
+				None => break,
+			}
+			i += 1;
+		}
+		chain
+	}
+}
 impl FromProto<ResponseData> for GameData {
 	fn from_proto(data: ResponseData) -> Self {
 		Self {
@@ -68,7 +109,7 @@ impl FromProto<ResponseData> for GameData {
 }
 
 /// Cost of an item (`UnitTypeId` or `UpgradeId`) in resources, supply and time.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Cost {
 	pub minerals: u32,
@@ -87,6 +128,19 @@ pub enum AbilityTarget {
 	PointOrUnit,
 	PointOrNone,
 }
+impl AbilityTarget {
+	/// Checks if ability with this kind of target can be used with given target.
+	pub fn accepts(self, target: Target) -> bool {
+		matches!(
+			(self, target),
+			(Self::None, Target::None)
+				| (Self::Point, Target::Pos(_))
+				| (Self::Unit, Target::Tag(_))
+				| (Self::PointOrUnit, Target::Pos(_) | Target::Tag(_))
+				| (Self::PointOrNone, Target::Pos(_) | Target::None)
+		)
+	}
+}
 impl FromProto<AbilityData_Target> for AbilityTarget {
 	fn from_proto(target: AbilityData_Target) -> Self {
 		match target {
@@ -154,7 +208,7 @@ impl FromProto<Weapon_TargetType> for TargetType {
 }
 
 /// Weapon's characteristic.
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Weapon {
 	/// Possible targets.
@@ -170,6 +224,29 @@ pub struct Weapon {
 	/// Cooldown (in seconds * game speed).
 	pub speed: f32,
 }
+impl Weapon {
+	/// Additional damage of one attack vs target with given attributes.
+	pub fn bonus_vs(&self, attributes: &[Attribute]) -> u32 {
+		self.damage_bonus
+			.iter()
+			.filter(|(attribute, _)| attributes.contains(attribute))
+			.map(|(_, bonus)| bonus)
+			.sum()
+	}
+	/// Total damage of one weapon use (all attacks, including bonuses)
+	/// vs target with given attributes, without upgrades and armor.
+	pub fn damage_vs(&self, attributes: &[Attribute]) -> u32 {
+		(self.damage + self.bonus_vs(attributes)) * self.attacks
+	}
+	/// Checks if weapon can hit air or ground target.
+	pub fn can_hit(&self, is_flying: bool) -> bool {
+		match self.target {
+			TargetType::Any => true,
+			TargetType::Air => is_flying,
+			TargetType::Ground => !is_flying,
+		}
+	}
+}
 impl FromProto<&ProtoWeapon> for Weapon {
 	fn from_proto(weapon: &ProtoWeapon) -> Self {
 		Self {
@@ -214,6 +291,12 @@ pub struct AbilityData {
 	/// Maximum range to target of the ability.
 	pub cast_range: Option<f32>,
 }
+impl AbilityData {
+	/// Energy needed to use ability, `0` for abilities which don't cost energy.
+	pub fn energy_cost(&self) -> u32 {
+		ENERGY_COSTS.get(&self.id).copied().unwrap_or(0)
+	}
+}
 impl TryFromProto<&ProtoAbilityData> for AbilityData {
 	fn try_from_proto(a: &ProtoAbilityData) -> Option<Self> {
 		Some(Self {
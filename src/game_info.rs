@@ -39,7 +39,99 @@ pub struct GameInfo {
 	pub start_locations: Vec<Point2>,
 	/// Center of the map.
 	pub map_center: Point2,
+	/// Symmetry of the map, detected by terrain height and pathing grid.
+	pub symmetry: Symmetry,
 }
+impl GameInfo {
+	/// Checks if given position is inside of [`playable_area`](Self::playable_area).
+	pub fn is_playable(&self, pos: Point2) -> bool {
+		self.playable_area.contains(pos)
+	}
+	/// Returns position symmetric to given one by map [`symmetry`](Self::symmetry),
+	/// e.g. translates own base location to the same base of opponent.
+	///
+	/// If symmetry wasn't detected, point reflection around center of playable area is used,
+	/// since most of ladder maps are rotationally symmetric.
+	pub fn mirror(&self, pos: Point2) -> Point2 {
+		self.symmetry.apply(pos, self.playable_area.center())
+	}
+}
+
+/// Kind of map symmetry, stored in [`GameInfo::symmetry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Symmetry {
+	/// Symmetry wasn't detected.
+	#[default]
+	Unknown,
+	/// Map is rotated by 180 degrees around its center.
+	Rotational,
+	/// Map is mirrored across vertical line through its center (left and right sides).
+	Vertical,
+	/// Map is mirrored across horizontal line through its center (top and bottom sides).
+	Horizontal,
+	/// Map is mirrored across diagonal going from bottom-left to top-right corner.
+	Diagonal,
+	/// Map is mirrored across diagonal going from top-left to bottom-right corner.
+	AntiDiagonal,
+}
+impl Symmetry {
+	const KINDS: [Symmetry; 5] = [
+		Symmetry::Rotational,
+		Symmetry::Vertical,
+		Symmetry::Horizontal,
+		Symmetry::Diagonal,
+		Symmetry::AntiDiagonal,
+	];
+
+	/// Returns position symmetric to given one relative to given center.
+	pub fn apply(self, pos: Point2, center: Point2) -> Point2 {
+		let d = pos - center;
+		match self {
+			Self::Unknown | Self::Rotational => center - d,
+			Self::Vertical => Point2::new(center.x - d.x, pos.y),
+			Self::Horizontal => Point2::new(pos.x, center.y - d.y),
+			Self::Diagonal => Point2::new(center.x + d.y, center.y + d.x),
+			Self::AntiDiagonal => Point2::new(center.x - d.y, center.y - d.x),
+		}
+	}
+
+	// Picks symmetry under which the most of playable cells have the same height and pathability.
+	fn detect(area: Rect, terrain_height: &ByteMap, pathing_grid: &PixelMap) -> Self {
+		let center = area.center();
+		let mut best = (Self::Unknown, 0.0);
+		for kind in Self::KINDS {
+			let mut total = 0;
+			let mut same = 0;
+			for x in area.x0..area.x1 {
+				for y in area.y0..area.y1 {
+					let pos = Point2::new(x as f32 + 0.5, y as f32 + 0.5);
+					let other = kind.apply(pos, center);
+					total += 1;
+					if area.contains(other)
+						&& terrain_height.get(<(usize, usize)>::from(other)) == terrain_height.get((x, y))
+						&& pathing_grid.get(<(usize, usize)>::from(other)) == pathing_grid.get((x, y))
+					{
+						same += 1;
+					}
+				}
+			}
+			if total > 0 {
+				let ratio = same as f32 / total as f32;
+				if ratio > best.1 {
+					best = (kind, ratio);
+				}
+			}
+		}
+		if best.1 >= SYMMETRY_THRESHOLD {
+			best.0
+		} else {
+			Self::Unknown
+		}
+	}
+}
+
+// Part of playable cells, which must match for symmetry to be detected.
+const SYMMETRY_THRESHOLD: f32 = 0.9;
 impl FromProto<ResponseGameInfo> for GameInfo {
 	fn from_proto(game_info: ResponseGameInfo) -> Self {
 		let start_raw = game_info.get_start_raw();
@@ -52,6 +144,15 @@ impl FromProto<ResponseGameInfo> for GameInfo {
 		let area_p1_x = area_p1.get_x();
 		let area_p1_y = area_p1.get_y();
 		let local_map_path = game_info.get_local_map_path().to_string();
+		let pathing_grid = PixelMap::from_proto(start_raw.get_pathing_grid());
+		let terrain_height = ByteMap::from_proto(start_raw.get_terrain_height());
+		let playable_area = Rect::new(
+			area_p0_x as usize,
+			area_p0_y as usize,
+			area_p1_x as usize,
+			area_p1_y as usize,
+		);
+		let symmetry = Symmetry::detect(playable_area, &terrain_height, &pathing_grid);
 		Self {
 			map_name: game_info.get_map_name().to_string(),
 			mod_names: game_info.get_mod_names().to_vec(),
@@ -82,15 +183,10 @@ impl FromProto<ResponseGameInfo> for GameInfo {
 				})
 				.collect(),
 			map_size: Size::new(map_size.get_x() as usize, map_size.get_y() as usize),
-			pathing_grid: PixelMap::from_proto(start_raw.get_pathing_grid()),
-			terrain_height: Rs::new(ByteMap::from_proto(start_raw.get_terrain_height())),
+			pathing_grid,
+			terrain_height: Rs::new(terrain_height),
 			placement_grid: PixelMap::from_proto(start_raw.get_placement_grid()),
-			playable_area: Rect::new(
-				area_p0_x as usize,
-				area_p0_y as usize,
-				area_p1_x as usize,
-				area_p1_y as usize,
-			),
+			playable_area,
 			start_locations: start_raw
 				.get_start_locations()
 				.iter()
@@ -100,6 +196,7 @@ impl FromProto<ResponseGameInfo> for GameInfo {
 				(area_p0_x + (area_p1_x - area_p0_x) / 2) as f32,
 				(area_p0_y + (area_p1_y - area_p0_y) / 2) as f32,
 			),
+			symmetry,
 		}
 	}
 }
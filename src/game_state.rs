@@ -3,20 +3,21 @@
 use crate::{
 	action::{Action, ActionError},
 	bot::{Bot, LockOwned, LockU32, Locked, Rs, Rw},
+	consts::{EFFECT_DURATIONS, FRAMES_PER_SECOND},
+	distance::*,
 	geometry::Point2,
 	ids::*,
-	pixel_map::{PixelMap, VisibilityMap},
+	pixel_map::{update_pixel_map, PixelMap, VisibilityMap},
 	score::Score,
 	unit::Unit,
 	units::Units,
 	Event, FromProto, Player, SC2Result,
 };
 use num_traits::FromPrimitive;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use sc2_proto::{
-	query::RequestQueryAvailableAbilities,
 	raw::{Alliance as ProtoAlliance, PowerSource as ProtoPowerSource},
-	sc2api::{Alert as ProtoAlert, Request, ResponseObservation},
+	sc2api::{Alert as ProtoAlert, ResponseObservation},
 };
 use std::ops::{Deref, DerefMut};
 
@@ -34,8 +35,57 @@ pub struct GameState {
 	// player_result,
 	/// Messeges in game chat.
 	pub chat: Vec<ChatMessage>,
+	effects: Vec<TimedEffect>,
+	effects_appeared: FxHashMap<(EffectId, Point2), u32>,
+}
+impl GameState {
+	/// Current effects on the map with their kinds and estimated time remaining.
+	///
+	/// Raw effects can be found in [`observation.raw.effects`](RawData::effects).
+	pub fn effects(&self) -> &[TimedEffect] {
+		&self.effects
+	}
+	fn update_effects(&mut self) {
+		let game_loop = self.observation.game_loop();
+		let raw_effects = &self.observation.raw.effects;
+
+		let current = raw_effects
+			.iter()
+			.filter_map(|e| Some((e.id, *e.positions.first()?)))
+			.collect::<FxHashSet<_>>();
+		self.effects_appeared.retain(|key, _| current.contains(key));
+		for key in current {
+			self.effects_appeared.entry(key).or_insert(game_loop);
+		}
+
+		let appeared = &self.effects_appeared;
+		self.effects = raw_effects
+			.iter()
+			.map(|e| {
+				let appeared = e
+					.positions
+					.first()
+					.and_then(|pos| appeared.get(&(e.id, *pos)).copied())
+					.unwrap_or(game_loop);
+				let elapsed = game_loop.saturating_sub(appeared) as f32 / FRAMES_PER_SECOND;
+				TimedEffect {
+					kind: EffectKind::from(e.id),
+					id: e.id,
+					positions: e.positions.clone(),
+					radius: e.radius,
+					alliance: e.alliance,
+					owner: e.owner,
+					appeared,
+					remaining: EFFECT_DURATIONS
+						.get(&e.id)
+						.map(|duration| (duration - elapsed).max(0.0)),
+				}
+			})
+			.collect();
+	}
 }
 
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 pub(crate) fn update_state<B>(
 	bot: &mut B,
 	response_observation: &ResponseObservation,
@@ -138,6 +188,7 @@ where
 			radius: r.get_radius(),
 		})
 		.collect();
+	state.update_effects();
 
 	let mut events = vec![];
 	// Dead units
@@ -184,43 +235,17 @@ where
 	raw.dead_units = dead_units;
 
 	// Upgrades
-	*raw.upgrades.write_lock() = raw_player
-		.get_upgrade_ids()
-		.iter()
-		.map(|u| UpgradeId::from_u32(*u).unwrap_or_else(|| panic!("There's no `UpgradeId` with value {}", u)))
-		.collect::<FxHashSet<_>>();
+	let mut upgrades = raw.upgrades.write_lock();
+	upgrades.clear();
+	upgrades.extend(raw_player.get_upgrade_ids().iter().map(|u| {
+		UpgradeId::from_u32(*u).unwrap_or_else(|| panic!("There's no `UpgradeId` with value {}", u))
+	}));
+	drop(upgrades);
 
 	// Map
 	let map_state = res_raw.get_map_state();
 	// Creep
-	*raw.creep.write_lock() = PixelMap::from_proto(map_state.get_creep());
-
-	// Available abilities
-	let mut req = Request::new();
-	let req_query_abilities = req.mut_query().mut_abilities();
-	for u in res_raw.get_units() {
-		if matches!(u.get_alliance(), ProtoAlliance::value_Self) {
-			let mut req_unit = RequestQueryAvailableAbilities::new();
-			req_unit.set_unit_tag(u.get_tag());
-			req_query_abilities.push(req_unit);
-		}
-	}
-
-	let res = bot.api().send(req)?;
-	*bot.abilities_units.write_lock() = res
-		.get_query()
-		.get_abilities()
-		.iter()
-		.map(|a| {
-			(
-				a.get_unit_tag(),
-				a.get_abilities()
-					.iter()
-					.filter_map(|ab| AbilityId::from_i32(ab.get_ability_id()))
-					.collect(),
-			)
-		})
-		.collect();
+	update_pixel_map(&mut raw.creep.write_lock(), map_state.get_creep());
 
 	// Get visiblity
 	let visibility = VisibilityMap::from_proto(map_state.get_visibility());
@@ -233,10 +258,24 @@ where
 
 	// Set visiblity
 	bot.state.observation.raw.visibility = visibility;
+	bot.update_last_seen();
+	bot.update_power_map();
 
 	// Updating units
 	bot.update_units(units);
 
+	// Available abilities
+	let tags = bot
+		.units
+		.my
+		.all
+		.iter()
+		.filter(|u| bot.abilities_scope.contains(u))
+		.map(|u| u.tag())
+		.collect::<Vec<_>>();
+	bot.abilities_units.write_lock().clear();
+	bot.query_abilities(&tags)?;
+
 	// Events
 	let mut owned_tags = vec![];
 	let mut under_construction = vec![];
@@ -285,6 +324,15 @@ where
 		}
 	}
 
+	for m in &bot.state.chat {
+		if m.player_id != bot.player_id {
+			events.push(Event::ChatMessage {
+				player_id: m.player_id,
+				message: m.message.clone(),
+			});
+		}
+	}
+
 	Ok(events)
 }
 
@@ -379,6 +427,67 @@ pub struct Effect {
 	pub radius: f32,
 }
 
+/// Kind of effect, which groups related effect ids.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EffectKind {
+	/// High Templar's Psionic Storm.
+	PsiStorm,
+	/// Area where Ravager's Corrosive Bile is going to land.
+	CorrosiveBile,
+	/// Liberator's Defender Mode zone (including the zone of sieging Liberator).
+	LiberatorZone,
+	/// Ghost's Tactical Nuke dot.
+	Nuke,
+	/// Orbital Command's Scanner Sweep.
+	Scan,
+	/// Any other effect.
+	Other,
+}
+impl From<EffectId> for EffectKind {
+	fn from(id: EffectId) -> Self {
+		match id {
+			EffectId::PsiStormPersistent => Self::PsiStorm,
+			EffectId::RavagerCorrosiveBileCP => Self::CorrosiveBile,
+			EffectId::LiberatorTargetMorphDelayPersistent | EffectId::LiberatorTargetMorphPersistent => {
+				Self::LiberatorZone
+			}
+			EffectId::NukePersistent => Self::Nuke,
+			EffectId::ScannerSweep => Self::Scan,
+			_ => Self::Other,
+		}
+	}
+}
+
+/// Effect on the map with its kind and estimated time remaining.
+///
+/// All of them stored in [`state.effects()`](GameState::effects).
+#[derive(Clone)]
+pub struct TimedEffect {
+	/// Kind of the effect.
+	pub kind: EffectKind,
+	/// Type of the effect.
+	pub id: EffectId,
+	/// Positions covered by this effect.
+	pub positions: Vec<Point2>,
+	/// Additional radius covered by effect around every it's position.
+	pub radius: f32,
+	/// Is this effect yours or opponent's.
+	pub alliance: Alliance,
+	/// Player id of effect's owner.
+	pub owner: u32,
+	/// Game loop when effect was first seen.
+	pub appeared: u32,
+	/// Estimated time (in seconds) until effect ends (or lands for Corrosive Bile and Nuke).
+	/// `None` for effects without fixed duration, like Liberator zones.
+	pub remaining: Option<f32>,
+}
+impl TimedEffect {
+	/// Checks if given position is covered by effect, with additional gap.
+	pub fn covers(&self, pos: Point2, gap: f32) -> bool {
+		self.positions.iter().any(|p| p.is_closer(self.radius + gap, pos))
+	}
+}
+
 /// The alliance of unit or effect to your bot.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Alliance {
@@ -38,6 +38,214 @@ impl Rect {
 	pub fn new(x0: usize, y0: usize, x1: usize, y1: usize) -> Self {
 		Self { x0, y0, x1, y1 }
 	}
+	/// Width of rectangle.
+	pub fn width(&self) -> usize {
+		self.x1 - self.x0
+	}
+	/// Height of rectangle.
+	pub fn height(&self) -> usize {
+		self.y1 - self.y0
+	}
+	/// Exact center of rectangle.
+	pub fn center(&self) -> Point2 {
+		Point2::new((self.x0 + self.x1) as f32 / 2.0, (self.y0 + self.y1) as f32 / 2.0)
+	}
+	/// Checks if given point is inside of rectangle.
+	pub fn contains(&self, pos: Point2) -> bool {
+		pos.x >= self.x0 as f32 && pos.x < self.x1 as f32 && pos.y >= self.y0 as f32 && pos.y < self.y1 as f32
+	}
+	/// Checks if rectangles have common area.
+	pub fn intersects(&self, other: &Rect) -> bool {
+		self.x0 < other.x1 && other.x0 < self.x1 && self.y0 < other.y1 && other.y0 < self.y1
+	}
+	/// Checks if rectangle has common area with given circle.
+	pub fn intersects_circle(&self, circle: &Circle) -> bool {
+		circle.intersects_rect(self)
+	}
+	/// Returns common area of rectangles, or `None` if they aren't intersect.
+	pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+		if self.intersects(other) {
+			Some(Rect::new(
+				self.x0.max(other.x0),
+				self.y0.max(other.y0),
+				self.x1.min(other.x1),
+				self.y1.min(other.y1),
+			))
+		} else {
+			None
+		}
+	}
+	/// Returns an iterator over all tiles inside of rectangle.
+	pub fn tiles(&self) -> impl Iterator<Item = (usize, usize)> {
+		iproduct!(self.x0..self.x1, self.y0..self.y1)
+	}
+
+	// Smallest rectangle containing all tiles, which intersect area from `min` to `max`
+	fn covering(min: Point2, max: Point2) -> Self {
+		Self::new(
+			min.x.floor().max(0.0) as usize,
+			min.y.floor().max(0.0) as usize,
+			max.x.ceil().max(0.0) as usize,
+			max.y.ceil().max(0.0) as usize,
+		)
+	}
+}
+
+/// Circle with given center and radius.
+#[allow(missing_docs)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Circle {
+	pub center: Point2,
+	pub radius: f32,
+}
+impl Circle {
+	/// Constructs new circle with given center and radius.
+	pub fn new(center: Point2, radius: f32) -> Self {
+		Self { center, radius }
+	}
+	/// Checks if given point is inside of circle.
+	pub fn contains(&self, pos: Point2) -> bool {
+		(pos - self.center).len_squared() <= self.radius * self.radius
+	}
+	/// Checks if circles have common area.
+	pub fn intersects(&self, other: &Circle) -> bool {
+		let radius = self.radius + other.radius;
+		(self.center - other.center).len_squared() <= radius * radius
+	}
+	/// Checks if circle has common area with given rectangle.
+	pub fn intersects_rect(&self, rect: &Rect) -> bool {
+		let closest = Point2::new(
+			self.center.x.max(rect.x0 as f32).min(rect.x1 as f32),
+			self.center.y.max(rect.y0 as f32).min(rect.y1 as f32),
+		);
+		self.contains(closest)
+	}
+	/// Returns the smallest rectangle of tiles, which contains circle.
+	pub fn bounding_rect(&self) -> Rect {
+		Rect::covering(self.center - self.radius, self.center + self.radius)
+	}
+	/// Returns an iterator over tiles, which centers are inside of circle.
+	pub fn tiles(&self) -> impl Iterator<Item = (usize, usize)> {
+		let circle = *self;
+		self.bounding_rect()
+			.tiles()
+			.filter(move |&(x, y)| circle.contains(Point2::new(x as f32 + 0.5, y as f32 + 0.5)))
+	}
+}
+
+/// Polygon with given vertices.
+///
+/// Edges connect consecutive vertices and the last vertex with the first one.
+/// Vertices can go either clockwise or counterclockwise.
+#[allow(missing_docs)]
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Polygon {
+	pub points: Vec<Point2>,
+}
+impl Polygon {
+	/// Constructs new polygon with given vertices.
+	pub fn new(points: Vec<Point2>) -> Self {
+		Self { points }
+	}
+	/// Returns an iterator over edges of polygon.
+	pub fn edges(&self) -> impl Iterator<Item = (Point2, Point2)> + '_ {
+		self.points
+			.iter()
+			.copied()
+			.zip(self.points.iter().copied().cycle().skip(1))
+	}
+	/// Area of polygon (polygon shouldn't have self-intersections).
+	pub fn area(&self) -> f32 {
+		(self.edges().map(|(a, b)| a.x * b.y - b.x * a.y).sum::<f32>() / 2.0).abs()
+	}
+	/// Checks if given point is inside of polygon.
+	pub fn contains(&self, pos: Point2) -> bool {
+		// Counting intersections of edges with ray going from point to the right
+		let crossings = self
+			.edges()
+			.filter(|(a, b)| {
+				(a.y > pos.y) != (b.y > pos.y) && pos.x < a.x + (pos.y - a.y) * (b.x - a.x) / (b.y - a.y)
+			})
+			.count();
+		crossings % 2 == 1
+	}
+	/// Checks if polygons have common area.
+	pub fn intersects(&self, other: &Polygon) -> bool {
+		match (self.points.first(), other.points.first()) {
+			(Some(p1), Some(p2)) => {
+				self.contains(*p2)
+					|| other.contains(*p1)
+					|| iproduct!(self.edges(), other.edges())
+						.any(|((a1, a2), (b1, b2))| segments_intersect(a1, a2, b1, b2))
+			}
+			_ => false,
+		}
+	}
+	/// Checks if polygon has common area with given circle.
+	pub fn intersects_circle(&self, circle: &Circle) -> bool {
+		let radius_squared = circle.radius * circle.radius;
+		!self.points.is_empty()
+			&& (self.contains(circle.center)
+				|| self
+					.edges()
+					.any(|(a, b)| segment_distance_squared(circle.center, a, b) <= radius_squared))
+	}
+	/// Returns the smallest rectangle of tiles, which contains polygon.
+	pub fn bounding_rect(&self) -> Rect {
+		let first = match self.points.first() {
+			Some(p) => *p,
+			None => return Rect::default(),
+		};
+		let (min, max) = self.points.iter().fold((first, first), |(min, max), p| {
+			(
+				Point2::new(min.x.min(p.x), min.y.min(p.y)),
+				Point2::new(max.x.max(p.x), max.y.max(p.y)),
+			)
+		});
+		Rect::covering(min, max)
+	}
+	/// Returns an iterator over tiles, which centers are inside of polygon.
+	pub fn tiles(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+		self.bounding_rect()
+			.tiles()
+			.filter(move |&(x, y)| self.contains(Point2::new(x as f32 + 0.5, y as f32 + 0.5)))
+	}
+}
+impl From<Vec<Point2>> for Polygon {
+	fn from(points: Vec<Point2>) -> Self {
+		Self::new(points)
+	}
+}
+
+fn cross(o: Point2, a: Point2, b: Point2) -> f32 {
+	(a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+}
+fn segments_intersect(a1: Point2, a2: Point2, b1: Point2, b2: Point2) -> bool {
+	let d1 = cross(b1, b2, a1);
+	let d2 = cross(b1, b2, a2);
+	let d3 = cross(a1, a2, b1);
+	let d4 = cross(a1, a2, b2);
+	if d1 * d2 < 0.0 && d3 * d4 < 0.0 {
+		return true;
+	}
+	// Touching and collinear segments
+	let on_segment = |p: Point2, a: Point2, b: Point2| {
+		p.x >= a.x.min(b.x) && p.x <= a.x.max(b.x) && p.y >= a.y.min(b.y) && p.y <= a.y.max(b.y)
+	};
+	(d1 == 0.0 && on_segment(a1, b1, b2))
+		|| (d2 == 0.0 && on_segment(a2, b1, b2))
+		|| (d3 == 0.0 && on_segment(b1, a1, a2))
+		|| (d4 == 0.0 && on_segment(b2, a1, a2))
+}
+fn segment_distance_squared(p: Point2, a: Point2, b: Point2) -> f32 {
+	let ab = b - a;
+	let len_squared = ab.len_squared();
+	let t = if len_squared > 0.0 {
+		((p - a).dot(ab) / len_squared).clamp(0.0, 1.0)
+	} else {
+		0.0
+	};
+	(p - (a + ab * t)).len_squared()
 }
 
 /// Point on 2D grid, the most frequently used geometric primitive.
@@ -608,3 +816,65 @@ impl Radius for Point2 {}
 impl Radius for &Point2 {}
 impl Radius for Point3 {}
 impl Radius for &Point3 {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn polygon(points: &[(f32, f32)]) -> Polygon {
+		Polygon::new(points.iter().map(|&(x, y)| Point2::new(x, y)).collect())
+	}
+	// Segments are given as [x1, y1, x2, y2]
+	fn intersect(a: [f32; 4], b: [f32; 4]) -> bool {
+		segments_intersect(
+			Point2::new(a[0], a[1]),
+			Point2::new(a[2], a[3]),
+			Point2::new(b[0], b[1]),
+			Point2::new(b[2], b[3]),
+		)
+	}
+
+	#[test]
+	fn polygon_contains() {
+		let square = polygon(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]);
+		assert!(square.contains(Point2::new(2.0, 2.0)));
+		assert!(!square.contains(Point2::new(5.0, 2.0)));
+		assert!(!square.contains(Point2::new(-1.0, 2.0)));
+		assert!(!square.contains(Point2::new(2.0, 5.0)));
+
+		// L-shaped, vertices going clockwise
+		let concave = polygon(&[
+			(0.0, 0.0),
+			(0.0, 4.0),
+			(2.0, 4.0),
+			(2.0, 2.0),
+			(4.0, 2.0),
+			(4.0, 0.0),
+		]);
+		assert!(concave.contains(Point2::new(1.0, 3.0)));
+		assert!(concave.contains(Point2::new(3.0, 1.0)));
+		assert!(!concave.contains(Point2::new(3.0, 3.0)));
+
+		assert!(!Polygon::default().contains(Point2::new(0.0, 0.0)));
+	}
+
+	#[test]
+	fn crossing_segments() {
+		assert!(intersect([0.0, 0.0, 2.0, 2.0], [0.0, 2.0, 2.0, 0.0]));
+		// Parallel
+		assert!(!intersect([0.0, 0.0, 2.0, 0.0], [0.0, 1.0, 2.0, 1.0]));
+		// Extension of one segment goes through endpoint of the other
+		assert!(!intersect([0.0, 0.0, 1.0, 1.0], [2.0, 0.0, 3.0, -1.0]));
+	}
+
+	#[test]
+	fn touching_and_collinear_segments() {
+		// Common endpoint
+		assert!(intersect([0.0, 0.0, 1.0, 1.0], [1.0, 1.0, 2.0, 0.0]));
+		// Endpoint on the other segment
+		assert!(intersect([0.0, 0.0, 2.0, 0.0], [1.0, 0.0, 1.0, 3.0]));
+		// Overlapping and disjoint collinear segments
+		assert!(intersect([0.0, 0.0, 2.0, 0.0], [1.0, 0.0, 3.0, 0.0]));
+		assert!(!intersect([0.0, 0.0, 1.0, 0.0], [2.0, 0.0, 3.0, 0.0]));
+	}
+}
@@ -0,0 +1,157 @@
+//! Tracking of cloaked and burrowed enemy units.
+//!
+//! Cloaked and burrowed enemies are visible as shadows even when they're not detected,
+//! so bot remembers their last known positions in [`hidden_enemies`](Bot::hidden_enemies)
+//! and fires [`Event::HiddenEnemyNearBase`] when one appears near own townhall:
+//! ```ignore
+//! fn on_event(&mut self, event: Event) -> SC2Result<()> {
+//!     if let Event::HiddenEnemyNearBase(tag) = event {
+//!         let pos = self.hidden_enemies().get(tag).unwrap().position;
+//!         // Bring detection to `pos`
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{bot::Bot, distance::*, geometry::Point2, ids::UnitTypeId, Event};
+use indexmap::IndexMap;
+use rustc_hash::{FxHashSet, FxHasher};
+use std::hash::BuildHasherDefault;
+
+type FxIndexMap<K, V> = IndexMap<K, V, BuildHasherDefault<FxHasher>>;
+
+/// Default value of [`alert_distance`](HiddenEnemies::alert_distance).
+const ALERT_DISTANCE: f32 = 20.0;
+
+/// Last known state of cloaked or burrowed enemy unit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HiddenEnemy {
+	/// Tag of the unit.
+	pub tag: u64,
+	/// Type of the unit.
+	pub type_id: UnitTypeId,
+	/// Position, where unit was seen last time.
+	pub position: Point2,
+	/// In-game time in seconds, when unit was seen last time.
+	pub last_seen: f32,
+	/// Unit was burrowed (otherwise it was cloaked).
+	pub burrowed: bool,
+	/// Unit was detected when it was seen last time.
+	pub detected: bool,
+}
+
+/// Cloaked and burrowed enemy units seen during the game.
+///
+/// Unit is forgotten when it dies or is seen not cloaked and not burrowed.
+#[derive(Debug, Clone)]
+pub struct HiddenEnemies {
+	units: FxIndexMap<u64, HiddenEnemy>,
+	// Units near bases, for which event was already fired
+	alerted: FxHashSet<u64>,
+	/// Distance to own townhalls, at which [`Event::HiddenEnemyNearBase`] is fired. [Default: `20.0`]
+	pub alert_distance: f32,
+}
+impl Default for HiddenEnemies {
+	fn default() -> Self {
+		Self {
+			units: Default::default(),
+			alerted: Default::default(),
+			alert_distance: ALERT_DISTANCE,
+		}
+	}
+}
+impl HiddenEnemies {
+	/// Returns an iterator over all remembered units in order they were first seen.
+	pub fn iter(&self) -> impl Iterator<Item = &HiddenEnemy> {
+		self.units.values()
+	}
+	/// Returns remembered unit with given tag.
+	pub fn get(&self, tag: u64) -> Option<&HiddenEnemy> {
+		self.units.get(&tag)
+	}
+	/// Number of remembered units.
+	pub fn len(&self) -> usize {
+		self.units.len()
+	}
+	/// Checks if there're no remembered units.
+	pub fn is_empty(&self) -> bool {
+		self.units.is_empty()
+	}
+	/// Returns units, which were seen last time closer than given distance to target.
+	pub fn closer<P: Into<Point2> + Copy>(
+		&self,
+		distance: f32,
+		target: P,
+	) -> impl Iterator<Item = &HiddenEnemy> {
+		self.iter()
+			.filter(move |u| u.position.is_closer(distance, target))
+	}
+	/// Returns units, which were seen not earlier than given in-game time (in seconds).
+	pub fn seen_since(&self, time: f32) -> impl Iterator<Item = &HiddenEnemy> {
+		self.iter().filter(move |u| u.last_seen >= time)
+	}
+	/// Forgets all remembered units.
+	pub fn clear(&mut self) {
+		self.units.clear();
+		self.alerted.clear();
+	}
+}
+
+impl Bot {
+	/// Returns cloaked and burrowed enemy units seen during the game.
+	pub fn hidden_enemies(&self) -> &HiddenEnemies {
+		&self.hidden_enemies
+	}
+	/// Returns mutable reference to tracked hidden enemies, e.g. to change alert distance.
+	pub fn hidden_enemies_mut(&mut self) -> &mut HiddenEnemies {
+		&mut self.hidden_enemies
+	}
+
+	pub(crate) fn track_hidden_enemies(&mut self, events: &[Event]) -> Vec<Event> {
+		let time = self.time;
+		let hidden = &mut self.hidden_enemies;
+		for e in events {
+			if let Event::UnitDestroyed(tag, _) = e {
+				hidden.units.shift_remove(tag);
+				hidden.alerted.remove(tag);
+			}
+		}
+
+		let townhalls = &self.units.my.townhalls;
+		let mut fired = Vec::new();
+		for u in &self.units.enemy.units {
+			let tag = u.tag();
+			if !u.is_cloaked() {
+				if hidden.units.shift_remove(&tag).is_some() {
+					hidden.alerted.remove(&tag);
+				}
+				continue;
+			}
+
+			let position = u.position();
+			hidden.units.insert(
+				tag,
+				HiddenEnemy {
+					tag,
+					type_id: u.type_id(),
+					position,
+					last_seen: time,
+					burrowed: u.is_burrowed(),
+					detected: u.is_revealed(),
+				},
+			);
+			// Event is fired again only after unit left the base
+			if townhalls
+				.iter()
+				.any(|t| t.is_closer(hidden.alert_distance, position))
+			{
+				if hidden.alerted.insert(tag) {
+					fired.push(Event::HiddenEnemyNearBase(tag));
+				}
+			} else {
+				hidden.alerted.remove(&tag);
+			}
+		}
+		fired
+	}
+}
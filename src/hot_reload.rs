@@ -0,0 +1,223 @@
+//! Hot reloading of bot logic from dynamic library. Available with `"hot_reload"` feature.
+//!
+//! Decision logic is moved to separate crate with `crate-type = ["cdylib"]`,
+//! which implements [`Logic`] and exports constructor of it:
+//! ```ignore
+//! #[derive(Default)]
+//! struct MyLogic;
+//! impl Logic for MyLogic {
+//!     fn on_step(&mut self, bot: &mut Bot, _iteration: usize) -> SC2Result<()> {
+//!         // Logic here
+//!         Ok(())
+//!     }
+//! }
+//!
+//! #[no_mangle]
+//! pub fn create_logic() -> Box<dyn Logic> {
+//!     Box::new(MyLogic::default())
+//! }
+//! ```
+//! Runner binary loads it with [`HotReload`], which is passed to runners as usual bot:
+//! ```ignore
+//! let mut bot = HotReload::new("target/debug/libmy_logic.so", Race::Terran);
+//! run_vs_computer(&mut bot, computer, map, Default::default())?;
+//! ```
+//! Library is reloaded on game start and during the game when its file is changed,
+//! so recompiling the library is enough to apply changes without restarting SC2.
+//! State of logic is lost on reload.
+//!
+//! Library must be compiled with the same compiler and version of this crate as the runner.
+
+use crate::{
+	bot::Bot,
+	client::SC2Result,
+	player::{GameSummary, Race},
+	Event, Player, PlayerSettings,
+};
+use libloading::Library;
+use std::{
+	env, fs,
+	ops::{Deref, DerefMut},
+	path::{Path, PathBuf},
+	time::SystemTime,
+};
+
+/// Name of function, exported by library, which constructs [`Logic`].
+pub const CONSTRUCTOR: &[u8] = b"create_logic";
+/// Library file is checked for changes once in this number of steps.
+const CHECK_INTERVAL: usize = 16;
+
+/// Bot logic, which can be loaded from dynamic library.
+/// Mirrors [`Player`] callbacks, but gets bot as argument.
+pub trait Logic {
+	/// Called once on game start or after logic was reloaded during the game.
+	fn on_start(&mut self, _bot: &mut Bot) -> SC2Result<()> {
+		Ok(())
+	}
+	/// Called on every game step.
+	fn on_step(&mut self, _bot: &mut Bot, _iteration: usize) -> SC2Result<()> {
+		Ok(())
+	}
+	/// Called once on last step with summary of the game.
+	fn on_end(&self, _bot: &Bot, _summary: GameSummary) -> SC2Result<()> {
+		Ok(())
+	}
+	/// Called when different events happen.
+	fn on_event(&mut self, _bot: &mut Bot, _event: Event) -> SC2Result<()> {
+		Ok(())
+	}
+}
+
+type Constructor = fn() -> Box<dyn Logic>;
+
+/// Bot, which loads its [`Logic`] from dynamic library and reloads it when library changes.
+pub struct HotReload {
+	bot: Bot,
+	race: Race,
+	name: Option<String>,
+	path: PathBuf,
+	modified: Option<SystemTime>,
+	loads: usize,
+	// Logic must be dropped before library it was loaded from
+	logic: Option<Box<dyn Logic>>,
+	library: Option<(Library, PathBuf)>,
+}
+impl HotReload {
+	/// Constructs bot, which loads logic from library at given path.
+	/// Library is loaded on game start.
+	pub fn new<P: AsRef<Path>>(path: P, race: Race) -> Self {
+		Self {
+			bot: Default::default(),
+			race,
+			name: None,
+			path: path.as_ref().to_path_buf(),
+			modified: None,
+			loads: 0,
+			logic: None,
+			library: None,
+		}
+	}
+	/// Sets name of the player.
+	pub fn with_name(mut self, name: &str) -> Self {
+		self.name = Some(name.to_string());
+		self
+	}
+	/// Checks if library file was changed since it was loaded.
+	pub fn is_changed(&self) -> bool {
+		self.modified.is_none() || self.modified != modified_time(&self.path)
+	}
+	/// Loads logic from library, replacing the current one.
+	///
+	/// Library is copied to temporary directory before loading,
+	/// so the original file can be overwritten by compiler.
+	/// If loading fails, the current logic is kept.
+	pub fn reload(&mut self) -> SC2Result<()> {
+		// Remembered before loading, so broken file isn't loaded again until it's changed
+		self.modified = modified_time(&self.path);
+		self.loads += 1;
+		let stem = self.path.file_stem().and_then(|s| s.to_str()).unwrap_or("logic");
+		let ext = self.path.extension().and_then(|s| s.to_str()).unwrap_or("");
+		let copy = env::temp_dir().join(format!("{}-{}-{}.{}", stem, std::process::id(), self.loads, ext));
+		fs::copy(&self.path, &copy)?;
+
+		let (logic, library) = match unsafe { load(&copy) } {
+			Ok(loaded) => loaded,
+			Err(e) => {
+				let _ = fs::remove_file(copy);
+				return Err(e);
+			}
+		};
+		self.unload();
+		self.logic = Some(logic);
+		self.library = Some((library, copy));
+		debug!("Logic loaded from {}", self.path.display());
+		Ok(())
+	}
+	fn unload(&mut self) {
+		self.logic = None;
+		if let Some((library, copy)) = self.library.take() {
+			drop(library);
+			let _ = fs::remove_file(copy);
+		}
+	}
+	fn reload_if_changed(&mut self) -> SC2Result<bool> {
+		if self.logic.is_some() && !self.is_changed() {
+			return Ok(false);
+		}
+		match self.reload() {
+			Ok(()) => Ok(true),
+			// Library may be half-written by compiler, so the game goes on with the old logic
+			Err(e) if self.logic.is_some() => {
+				warn!("Can't reload logic from {}: {}", self.path.display(), e);
+				Ok(false)
+			}
+			Err(e) => Err(e),
+		}
+	}
+}
+impl Drop for HotReload {
+	fn drop(&mut self) {
+		self.unload();
+	}
+}
+impl Deref for HotReload {
+	type Target = Bot;
+
+	fn deref(&self) -> &Self::Target {
+		&self.bot
+	}
+}
+impl DerefMut for HotReload {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.bot
+	}
+}
+impl Player for HotReload {
+	fn get_player_settings(&self) -> PlayerSettings {
+		let settings = PlayerSettings::new(self.race);
+		match &self.name {
+			Some(name) => settings.with_name(name),
+			None => settings,
+		}
+	}
+	fn on_start(&mut self) -> SC2Result<()> {
+		self.reload_if_changed()?;
+		match &mut self.logic {
+			Some(logic) => logic.on_start(&mut self.bot),
+			None => Ok(()),
+		}
+	}
+	fn on_step(&mut self, iteration: usize) -> SC2Result<()> {
+		if iteration % CHECK_INTERVAL == 0 && self.reload_if_changed()? {
+			if let Some(logic) = &mut self.logic {
+				logic.on_start(&mut self.bot)?;
+			}
+		}
+		match &mut self.logic {
+			Some(logic) => logic.on_step(&mut self.bot, iteration),
+			None => Ok(()),
+		}
+	}
+	fn on_end_full(&self, summary: GameSummary) -> SC2Result<()> {
+		match &self.logic {
+			Some(logic) => logic.on_end(&self.bot, summary),
+			None => Ok(()),
+		}
+	}
+	fn on_event(&mut self, event: Event) -> SC2Result<()> {
+		match &mut self.logic {
+			Some(logic) => logic.on_event(&mut self.bot, event),
+			None => Ok(()),
+		}
+	}
+}
+
+unsafe fn load(path: &Path) -> SC2Result<(Box<dyn Logic>, Library)> {
+	let library = Library::new(path)?;
+	let logic = library.get::<Constructor>(CONSTRUCTOR)?();
+	Ok((logic, library))
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+	fs::metadata(path).and_then(|m| m.modified()).ok()
+}
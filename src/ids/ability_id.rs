@@ -1605,3 +1605,7 @@ pub enum AbilityId {
 	MorphToBanelingCancel = 4432,
 	MothershipCloakOracleCloakField = 4434,
 }
+impl AbilityId {
+	/// The largest id value, all variants are in range `0..=MAX_ID`.
+	pub const MAX_ID: u32 = 4434;
+}
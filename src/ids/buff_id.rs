@@ -316,3 +316,7 @@ pub enum BuffId {
 	TakenDamage = 307,
 	RavenScramblerMissileCarrier = 308,
 }
+impl BuffId {
+	/// The largest id value, all variants are in range `0..=MAX_ID`.
+	pub const MAX_ID: u32 = 308;
+}
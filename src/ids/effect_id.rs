@@ -20,3 +20,7 @@ pub enum EffectId {
 	RavagerCorrosiveBileCP = 11,
 	LurkerMP = 12,
 }
+impl EffectId {
+	/// The largest id value, all variants are in range `0..=MAX_ID`.
+	pub const MAX_ID: u32 = 12;
+}
@@ -36,6 +36,57 @@ impl UnitTypeId {
 			| UnitTypeId::StarportReactor
 		)
 	}
+	/// Checks if it's refinery, assimilator or extractor (including rich ones).
+	#[rustfmt::skip::macros(matches)]
+	#[inline]
+	pub fn is_gas_structure(self) -> bool {
+		matches!(
+			self,
+			UnitTypeId::Refinery
+			| UnitTypeId::RefineryRich
+			| UnitTypeId::Assimilator
+			| UnitTypeId::AssimilatorRich
+			| UnitTypeId::Extractor
+			| UnitTypeId::ExtractorRich
+		)
+	}
+	/// Checks if it's any kind of mineral field.
+	#[rustfmt::skip::macros(matches)]
+	#[inline]
+	pub fn is_mineral_field(self) -> bool {
+		matches!(
+			self,
+			UnitTypeId::RichMineralField
+			| UnitTypeId::RichMineralField750
+			| UnitTypeId::MineralField
+			| UnitTypeId::MineralField450
+			| UnitTypeId::MineralField750
+			| UnitTypeId::LabMineralField
+			| UnitTypeId::LabMineralField750
+			| UnitTypeId::PurifierRichMineralField
+			| UnitTypeId::PurifierRichMineralField750
+			| UnitTypeId::PurifierMineralField
+			| UnitTypeId::PurifierMineralField750
+			| UnitTypeId::BattleStationMineralField
+			| UnitTypeId::BattleStationMineralField750
+			| UnitTypeId::MineralFieldOpaque
+			| UnitTypeId::MineralFieldOpaque900
+		)
+	}
+	/// Checks if it's any kind of vespene geyser.
+	#[rustfmt::skip::macros(matches)]
+	#[inline]
+	pub fn is_geyser(self) -> bool {
+		matches!(
+			self,
+			UnitTypeId::VespeneGeyser
+			| UnitTypeId::SpacePlatformGeyser
+			| UnitTypeId::RichVespeneGeyser
+			| UnitTypeId::ProtossVespeneGeyser
+			| UnitTypeId::PurifierVespeneGeyser
+			| UnitTypeId::ShakurasVespeneGeyser
+		)
+	}
 	#[rustfmt::skip::macros(matches)]
 	#[inline]
 	pub fn is_melee(self) -> bool {
@@ -234,6 +285,60 @@ impl UnitTypeId {
 			| UnitTypeId::Mothership
 		)
 	}
+	/// Returns base type of unit in alternative mode: flying, lowered, sieged, burrowed, etc.
+	/// (e.g. `SupplyDepotLowered` → `SupplyDepot`, `SiegeTankSieged` → `SiegeTank`).
+	/// Other types are returned unchanged.
+	pub fn normalized(self) -> UnitTypeId {
+		match self {
+			UnitTypeId::SupplyDepotLowered => UnitTypeId::SupplyDepot,
+			UnitTypeId::BarracksFlying => UnitTypeId::Barracks,
+			UnitTypeId::FactoryFlying => UnitTypeId::Factory,
+			UnitTypeId::StarportFlying => UnitTypeId::Starport,
+			UnitTypeId::CommandCenterFlying => UnitTypeId::CommandCenter,
+			UnitTypeId::OrbitalCommandFlying => UnitTypeId::OrbitalCommand,
+			UnitTypeId::SiegeTankSieged => UnitTypeId::SiegeTank,
+			UnitTypeId::WidowMineBurrowed => UnitTypeId::WidowMine,
+			UnitTypeId::ThorAP => UnitTypeId::Thor,
+			UnitTypeId::VikingAssault => UnitTypeId::VikingFighter,
+			UnitTypeId::LiberatorAG => UnitTypeId::Liberator,
+			UnitTypeId::DroneBurrowed => UnitTypeId::Drone,
+			UnitTypeId::QueenBurrowed => UnitTypeId::Queen,
+			UnitTypeId::ZerglingBurrowed => UnitTypeId::Zergling,
+			UnitTypeId::BanelingBurrowed => UnitTypeId::Baneling,
+			UnitTypeId::RoachBurrowed => UnitTypeId::Roach,
+			UnitTypeId::RavagerBurrowed => UnitTypeId::Ravager,
+			UnitTypeId::HydraliskBurrowed => UnitTypeId::Hydralisk,
+			UnitTypeId::LurkerMPBurrowed => UnitTypeId::LurkerMP,
+			UnitTypeId::InfestorBurrowed => UnitTypeId::Infestor,
+			UnitTypeId::SwarmHostBurrowedMP => UnitTypeId::SwarmHostMP,
+			UnitTypeId::UltraliskBurrowed => UnitTypeId::Ultralisk,
+			UnitTypeId::SpineCrawlerUprooted => UnitTypeId::SpineCrawler,
+			UnitTypeId::SporeCrawlerUprooted => UnitTypeId::SporeCrawler,
+			UnitTypeId::OverseerSiegeMode => UnitTypeId::Overseer,
+			UnitTypeId::ObserverSiegeMode => UnitTypeId::Observer,
+			UnitTypeId::WarpPrismPhasing => UnitTypeId::WarpPrism,
+			UnitTypeId::AssimilatorRich => UnitTypeId::Assimilator,
+			UnitTypeId::ExtractorRich => UnitTypeId::Extractor,
+			UnitTypeId::RefineryRich => UnitTypeId::Refinery,
+			id => id,
+		}
+	}
+	/// Returns type, which given type counts as for tech requirements.
+	///
+	/// Same as [`normalized`](Self::normalized), but also maps morphed structures
+	/// to the structures they were morphed from (e.g. `OrbitalCommand` → `CommandCenter`,
+	/// `WarpGate` → `Gateway`). Note that higher tech still satisfies requirements
+	/// of lower one, but not vice versa (Hive counts as Lair, but Lair doesn't count as Hive).
+	pub fn tech_normalized(self) -> UnitTypeId {
+		match self.normalized() {
+			UnitTypeId::OrbitalCommand | UnitTypeId::PlanetaryFortress => UnitTypeId::CommandCenter,
+			UnitTypeId::Lair | UnitTypeId::Hive => UnitTypeId::Hatchery,
+			UnitTypeId::GreaterSpire => UnitTypeId::Spire,
+			UnitTypeId::WarpGate => UnitTypeId::Gateway,
+			UnitTypeId::OverlordTransport => UnitTypeId::Overlord,
+			id => id,
+		}
+	}
 }
 
 impl AbilityId {
@@ -2068,3 +2068,7 @@ pub enum UnitTypeId {
 	DummyUnit013 = 2055,
 	DummyUnit014 = 2056,
 }
+impl UnitTypeId {
+	/// The largest id value, all variants are in range `0..=MAX_ID`.
+	pub const MAX_ID: u32 = 2056;
+}
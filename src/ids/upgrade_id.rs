@@ -328,3 +328,7 @@ pub enum UpgradeId {
 	HurricaneThrusters = 303,
 	InterferenceMatrix = 304,
 }
+impl UpgradeId {
+	/// The largest id value, all variants are in range `0..=MAX_ID`.
+	pub const MAX_ID: u32 = 304;
+}
@@ -61,6 +61,8 @@ fn main() -> SC2Result<()> {
         //     sc2_version: Option<&str>, // Default: None - Latest available patch.
         //     save_replay_as: Option<&str>, // Default: None - Doesn't save replay.
         //     realtime: bool, // Default: false
+        //     sc2_path: Option<&str>, // Default: None - Discovered automatically.
+        //     maps_path: Option<&str>, // Default: None - "Maps" folder of SC2.
         // }
         LaunchOptions::default(),
     )
@@ -150,7 +152,7 @@ All fields are collections of [`Units`]:
 | Field                  | Type                           | Description                                                                    |
 |------------------------|--------------------------------|--------------------------------------------------------------------------------|
 | `self.time`            | `f32`                          | In-game time in seconds.                                                       |
-| `self.expansions`      | `Vec`<([`Point2`],[`Point2`])> | All expansions stored in (location, resource center) pairs.                    |
+| `self.expansions`      | [`Expansions`]                 | All expansions with their resources, owner and townhall. Updated each step.    |
 | `self.vision_blockers` | `Vec`<[`Point2`]>              | Obstacles on map which block vision of ground units, but still pathable.       |
 | `self.game_info`       | [`GameInfo`]                   | Information about map: pathing grid, building placement, terrain height.       |
 | `self.game_data`       | [`GameData`]                   | Constant information about abilities, unit types, upgrades, buffs and effects. |
@@ -219,7 +221,7 @@ Building new CCs might look like:
 // Checking if we have enough minerals for new expand.
 if self.can_afford(UnitTypeId::CommandCenter, false)
     // Checking if we not already building new base.
-    && self.counter().ordered().count(UnitTypeId::CommandCenter) == 0
+    && self.counter().ordered().exact().count(UnitTypeId::CommandCenter) == 0
 {
     // Getting next closest expansion
     if let Some(expansion) = self.get_expansion() {
@@ -301,7 +303,8 @@ The API will do the rest.
 Since [SC2AI] and [AI Arena] run the games on different platforms
 you'll need to provide suitable binaries for each ladder.
 
-Because of version differences ids are conditionally compiled for windows and linux.
+Ids in [`ids`] are generated for the latest game version,
+so some of them may differ on ladders running older game clients.
 
 [SC2AI]: https://sc2ai.net
 [AI Arena]: https://aiarena.net
@@ -313,6 +316,7 @@ Because of version differences ids are conditionally compiled for windows and li
 [`Ramp`]: ramp::Ramp
 [`Units`]: units::Units
 [`PlayerUnits`]: units::PlayerUnits
+[`Expansions`]: bot::Expansions
 [`GameInfo`]: game_info::GameInfo
 [`GameData`]: game_data::GameData
 [`GameState`]: game_state::GameState
@@ -354,39 +358,74 @@ pub mod prelude {
 		game_state::Alliance,
 		geometry::Point2,
 		ids::*,
-		player::{AIBuild, Computer, Difficulty, GameResult, Race},
+		player::{AIBuild, Computer, Difficulty, GameEndReason, GameResult, GameSummary, Race},
+		plugins::Plugin,
+		roles::Role,
 		unit::Unit,
-		units::{iter::UnitsIterator, Units},
+		units::{iter::UnitsIterator, AssignStrategy, UnitTypeSet, Units},
 		Event, Player, PlayerSettings,
 	};
 	#[doc(no_inline)]
 	pub use sc2_macro::{bot, bot_new};
 }
 
+#[cfg(not(feature = "no-launch"))]
 pub mod paths;
 
 pub mod action;
+pub mod action_log;
 pub mod api;
+pub mod army;
+pub mod blink;
 pub mod bot;
+pub mod build_order;
+pub mod capture;
+#[cfg(feature = "clap")]
+pub mod cli;
 pub mod client;
 pub mod consts;
 pub mod debug;
 pub mod distance;
+pub mod dodge;
+pub mod enemy_build;
 pub mod game_data;
 pub mod game_info;
 pub mod game_state;
 pub mod geometry;
+pub mod hidden_enemies;
+#[cfg(feature = "hot_reload")]
+pub mod hot_reload;
 pub mod ids;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod modules;
+pub mod nydus;
+pub mod opening_book;
+pub mod perf;
 pub mod pixel_map;
+pub mod placement;
 pub mod player;
+pub mod plugins;
+pub mod production;
+pub mod query;
 pub mod ramp;
+pub mod raycast;
+pub mod reservation;
+pub mod roles;
 pub mod score;
+pub mod series;
+pub mod supply;
+pub mod timers;
+pub mod transport;
 pub mod unit;
 pub mod units;
 pub mod utils;
+pub mod wall;
+pub mod waypoints;
+pub mod workers;
 
 use game_state::Alliance;
-use player::{GameResult, Race};
+use player::{GameResult, GameSummary, Race};
 
 /**
 `#[bot]` macro implements [`Deref`]`<Target = `[`Bot`]`>` and [`DerefMut`]`<Target = `[`Bot`]`>` for your struct.
@@ -621,6 +660,8 @@ let response = self.api().send(request)?;
 ```
 */
 pub use sc2_proto::sc2api::Request;
+/// Response of the SC2 API, returned by [`send_raw`](bot::Bot::send_raw).
+pub use sc2_proto::sc2api::Response;
 
 /// Settings that must be provided by a player when joining a game.
 ///
@@ -631,15 +672,20 @@ pub use sc2_proto::sc2api::Request;
 /// if `raw_crop_to_playable_area` is `true`, maps will be crooped to the size of
 /// [`self.game_info.playable_area`](game_info::GameInfo::playable_area).
 ///
+/// if `auto_gg` is `true`, bot will say "gg" and leave hopeless games
+/// (see [`auto_gg`](modules::auto_gg) module).
+///
 /// Defaults:
 /// `name`: `None`
 /// `raw_affects_selection`: `false`
 /// `raw_crop_to_playable_area`: `false`
+/// `auto_gg`: `false`
 pub struct PlayerSettings<'a> {
 	pub race: Race,
 	pub name: Option<&'a str>,
 	pub raw_affects_selection: bool,
 	pub raw_crop_to_playable_area: bool,
+	pub auto_gg: bool,
 }
 impl<'a> PlayerSettings<'a> {
 	/// Constructs new settings with given `Race`.
@@ -649,6 +695,7 @@ impl<'a> PlayerSettings<'a> {
 			name: None,
 			raw_affects_selection: false,
 			raw_crop_to_playable_area: false,
+			auto_gg: false,
 		}
 	}
 	/// Sets name of the player.
@@ -666,6 +713,11 @@ impl<'a> PlayerSettings<'a> {
 		self.raw_crop_to_playable_area = val;
 		self
 	}
+	/// Sets `auto_gg` to a given value.
+	pub fn auto_gg(mut self, val: bool) -> Self {
+		self.auto_gg = val;
+		self
+	}
 }
 impl Default for PlayerSettings<'_> {
 	fn default() -> Self {
@@ -674,13 +726,17 @@ impl Default for PlayerSettings<'_> {
 			name: None,
 			raw_affects_selection: false,
 			raw_crop_to_playable_area: false,
+			auto_gg: false,
 		}
 	}
 }
 
 /// Events that happen in game.
 /// Passed to [`on_event`](Player::on_event).
-#[derive(Debug, Clone, Copy)]
+///
+/// New events can be added in future versions, so matches on it should have a wildcard arm.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum Event {
 	/// Unit died or structure destroyed (all units: your, enemy, neutral).
 	UnitDestroyed(u64, Option<Alliance>),
@@ -692,6 +748,19 @@ pub enum Event {
 	ConstructionComplete(u64),
 	/// Detected actual race of random opponent.
 	RandomRaceDetected(Race),
+	/// Message in game chat sent by opponent or observer.
+	ChatMessage {
+		/// Id of player who sent the message.
+		player_id: u32,
+		/// Text of the message.
+		message: String,
+	},
+	/// Timer with given token fired
+	/// (see [`schedule_at`](bot::Bot::schedule_at) and [`schedule_every`](bot::Bot::schedule_every)).
+	Timer(u64),
+	/// Cloaked or burrowed enemy unit appeared near one of your townhalls
+	/// (see [`hidden_enemies`](bot::Bot::hidden_enemies)).
+	HiddenEnemyNearBase(u64),
 }
 
 /// Trait that bots must implement.
@@ -702,14 +771,30 @@ pub trait Player {
 	fn on_start(&mut self) -> SC2Result<()> {
 		Ok(())
 	}
+	/// Called on every game step right after observation is processed,
+	/// before [`on_event`](Self::on_event) and [`on_step`](Self::on_step).
+	fn on_before_step(&mut self, _iteration: usize) -> SC2Result<()> {
+		Ok(())
+	}
 	/// Called on every game step. (Main logic of the bot should be here)
 	fn on_step(&mut self, _iteration: usize) -> SC2Result<()> {
 		Ok(())
 	}
+	/// Called on every game step after [`on_step`](Self::on_step), right before actions are sent.
+	/// Commands given to units here are sent together with ones given in [`on_step`](Self::on_step).
+	fn on_after_step(&mut self, _iteration: usize) -> SC2Result<()> {
+		Ok(())
+	}
 	/// Called once on last step with a result for your bot.
 	fn on_end(&self, _result: GameResult) -> SC2Result<()> {
 		Ok(())
 	}
+	/// Called once on last step with a result, final score and duration of the game.
+	///
+	/// By default calls [`on_end`](Self::on_end) with the result.
+	fn on_end_full(&self, summary: GameSummary) -> SC2Result<()> {
+		self.on_end(summary.result)
+	}
 	/// Called when different events happen.
 	fn on_event(&mut self, _event: Event) -> SC2Result<()> {
 		Ok(())
@@ -0,0 +1,226 @@
+//! Mock SC2 server, which replays responses from [capture](crate::capture) file,
+//! for testing the whole client loop without the game.
+//!
+//! ```ignore
+//! let server = MockServer::from_capture("tests/data/game.sc2capture")?.start()?;
+//! server.set_env();
+//! run_vs_computer(
+//!     &mut MyBot::default(),
+//!     Computer::new(Race::Random, Difficulty::Easy, None),
+//!     "EternalEmpireLE",
+//!     LaunchOptions {
+//!         maps_path: Some("Maps"),
+//!         ..Default::default()
+//!     },
+//! )?;
+//! assert!(server.received().iter().any(|req| req.has_action()));
+//! ```
+//!
+//! Server answers each request with the next recorded response to request of the same type,
+//! so bot doesn't have to send exactly the same requests as during recording.
+//! When recorded responses run out, server answers with defaults:
+//! `CreateGame`, `JoinGame` and other requests with empty responses succeed,
+//! `Observation` returns the last recorded observation with game ended,
+//! so the game loop always terminates.
+
+use crate::{
+	api::request_kind,
+	capture::{CaptureReader, Captured},
+	client::{SC2Result, ENV_EXTERNAL, ENV_HOST, ENV_PORT},
+};
+use protobuf::Message;
+use rustc_hash::FxHashMap;
+use sc2_proto::sc2api::{Request, Response, Status};
+use std::{
+	collections::VecDeque,
+	env,
+	net::{TcpListener, TcpStream},
+	path::Path,
+	sync::{Arc, Mutex},
+	thread,
+};
+use tungstenite::{accept, Message::Binary};
+
+const HOST: &str = "127.0.0.1";
+/// Player id returned in default response to `JoinGame`.
+pub const MOCK_PLAYER_ID: u32 = 1;
+
+/// Recorded responses of mock server.
+#[derive(Default, Clone)]
+pub struct MockServer {
+	responses: FxHashMap<&'static str, VecDeque<Response>>,
+}
+impl MockServer {
+	/// Constructs server without recorded responses, which answers only with defaults.
+	pub fn new() -> Self {
+		Default::default()
+	}
+	/// Constructs server with responses recorded in capture file.
+	pub fn from_capture<P: AsRef<Path>>(path: P) -> SC2Result<Self> {
+		let mut server = Self::new();
+		// Responses come in order of requests, even if several requests were sent before reading
+		let mut pending = VecDeque::new();
+		for msg in CaptureReader::open(path)? {
+			match msg? {
+				Captured::Request(req) => pending.push_back(request_kind(&req)),
+				Captured::Response(res) => {
+					let kind = pending
+						.pop_front()
+						.ok_or("Response without request in capture file")?;
+					server.responses.entry(kind).or_default().push_back(res);
+				}
+			}
+		}
+		Ok(server)
+	}
+	/// Adds response to request of the same type as given one.
+	/// Responses to each type of request are returned in order they were added.
+	pub fn add_response(&mut self, req: &Request, res: Response) {
+		self.responses
+			.entry(request_kind(req))
+			.or_default()
+			.push_back(res);
+	}
+	/// Starts server on unused port in background thread.
+	pub fn start(self) -> SC2Result<MockHandle> {
+		let listener = TcpListener::bind((HOST, 0))?;
+		let port = listener.local_addr()?.port() as i32;
+		let received = Arc::new(Mutex::new(Vec::new()));
+
+		let log = Arc::clone(&received);
+		let mut script = Script {
+			responses: self.responses,
+			last_observation: None,
+		};
+		thread::spawn(move || {
+			for stream in listener.incoming() {
+				let quit = match stream {
+					Ok(stream) => script.serve(stream, &log),
+					Err(e) => {
+						error!("Mock server can't accept connection: {}", e);
+						continue;
+					}
+				};
+				match quit {
+					Ok(true) => break,
+					Ok(false) => {}
+					Err(e) => error!("Mock server connection failed: {}", e),
+				}
+			}
+		});
+		debug!("Mock server started on port {}", port);
+		Ok(MockHandle { port, received })
+	}
+}
+
+/// Running mock server.
+pub struct MockHandle {
+	port: i32,
+	received: Arc<Mutex<Vec<Request>>>,
+}
+impl MockHandle {
+	/// Address of the server.
+	pub fn host(&self) -> &'static str {
+		HOST
+	}
+	/// Port of the server.
+	pub fn port(&self) -> i32 {
+		self.port
+	}
+	/// Sets [`SC2_HOST`](ENV_HOST), [`SC2_PORT`](ENV_PORT) and [`SC2_EXTERNAL`](ENV_EXTERNAL),
+	/// so runners connect to this server instead of launching SC2.
+	pub fn set_env(&self) {
+		env::set_var(ENV_HOST, HOST);
+		env::set_var(ENV_PORT, self.port.to_string());
+		env::set_var(ENV_EXTERNAL, "1");
+	}
+	/// Returns all requests received by the server so far.
+	pub fn received(&self) -> Vec<Request> {
+		self.received.lock().unwrap().clone()
+	}
+}
+
+struct Script {
+	responses: FxHashMap<&'static str, VecDeque<Response>>,
+	last_observation: Option<Response>,
+}
+impl Script {
+	// Returns `true` when client quit
+	fn serve(&mut self, stream: TcpStream, log: &Mutex<Vec<Request>>) -> SC2Result<bool> {
+		let mut ws = accept(stream).map_err(|e| e.to_string())?;
+		loop {
+			let msg = match ws.read_message() {
+				Ok(msg) if msg.is_close() => return Ok(false),
+				Ok(msg) => msg.into_data(),
+				Err(tungstenite::Error::ConnectionClosed) => return Ok(false),
+				Err(e) => return Err(e.into()),
+			};
+			let req = Request::parse_from_bytes(&msg)?;
+			let res = self.respond(&req);
+			let quit = req.has_quit();
+			log.lock().unwrap().push(req);
+
+			ws.write_message(Binary(res.write_to_bytes()?))?;
+			if quit {
+				let _ = ws.close(None);
+				return Ok(true);
+			}
+		}
+	}
+	fn respond(&mut self, req: &Request) -> Response {
+		let kind = request_kind(req);
+		if let Some(res) = self.responses.get_mut(kind).and_then(|queue| queue.pop_front()) {
+			if req.has_observation() {
+				self.last_observation = Some(res.clone());
+			}
+			return res;
+		}
+
+		let mut res = Response::new();
+		res.set_status(Status::in_game);
+		match kind {
+			"observation" => {
+				if let Some(last) = &self.last_observation {
+					res = last.clone();
+				} else {
+					res.mut_observation();
+				}
+				res.set_status(Status::ended);
+			}
+			"create_game" => {
+				res.set_status(Status::init_game);
+				res.mut_create_game();
+			}
+			"join_game" => {
+				res.mut_join_game().set_player_id(MOCK_PLAYER_ID);
+			}
+			"step" => {
+				res.mut_step();
+			}
+			"action" => {
+				res.mut_action();
+			}
+			"debug" => {
+				res.mut_debug();
+			}
+			"leave_game" => {
+				res.set_status(Status::launched);
+				res.mut_leave_game();
+			}
+			"quit" => {
+				res.set_status(Status::quit);
+				res.mut_quit();
+			}
+			"save_replay" => {
+				res.mut_save_replay();
+			}
+			"ping" => {
+				res.mut_ping();
+			}
+			_ => res
+				.mut_error()
+				.push(format!("Mock server has no response to {} request", kind)),
+		}
+		res
+	}
+}
@@ -0,0 +1,147 @@
+//! Auto burrow: hurt units burrow to regenerate and unburrow when healed.
+//!
+//! Units of chosen types (Roaches, Widow Mines and Infestors by default) burrow when
+//! their hits fall below threshold and unburrow when they are healed. Units marked for
+//! [`ambush`](AutoBurrow::ambush) burrow as soon as they're idle and wait burrowed.
+//! Burrowed units unburrow when enemy detection gets close, so they can retreat.
+//! Zerg units need Burrow upgrade to be controlled by the module.
+//!
+//! Module is disabled by default, it can be enabled with [`enable`] in
+//! [`on_start`](crate::Player::on_start):
+//! ```ignore
+//! fn on_start(&mut self) -> SC2Result<()> {
+//!     rust_sc2::modules::auto_burrow::enable(self);
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{
+	action::Target,
+	bot::Bot,
+	consts::UNIT_ALIAS,
+	ids::{AbilityId, UnitTypeId, UpgradeId},
+	player::Race,
+	Event,
+};
+use rustc_hash::FxHashSet;
+
+/// Settings and state of auto burrow module. Stored in bot while module is enabled.
+#[derive(Debug, Clone)]
+pub struct AutoBurrow {
+	/// Unit types controlled by module. [Default: Roach, Widow Mine, Infestor]
+	pub types: FxHashSet<UnitTypeId>,
+	/// Units burrow when their hits percentage falls below this value. [Default: `0.35`]
+	pub burrow_below: f32,
+	/// Units burrowed to regenerate unburrow when their hits percentage reaches this value.
+	/// [Default: `0.95`]
+	pub unburrow_above: f32,
+	/// Burrowed units unburrow when enemy detection is closer than this distance. [Default: `2.0`]
+	pub detection_gap: f32,
+	/// Tags of units, which should burrow when idle and stay burrowed.
+	pub ambush: FxHashSet<u64>,
+	// Units burrowed by module to regenerate
+	healing: FxHashSet<u64>,
+}
+impl Default for AutoBurrow {
+	fn default() -> Self {
+		Self {
+			types: [UnitTypeId::Roach, UnitTypeId::WidowMine, UnitTypeId::Infestor]
+				.into_iter()
+				.collect(),
+			burrow_below: 0.35,
+			unburrow_above: 0.95,
+			detection_gap: 2.0,
+			ambush: Default::default(),
+			healing: Default::default(),
+		}
+	}
+}
+
+/// Enables auto burrow for own units of chosen types.
+pub fn enable(bot: &mut Bot) {
+	bot.auto_burrow.get_or_insert_with(Default::default);
+}
+/// Disables auto burrow. Units stay in their current state.
+pub fn disable(bot: &mut Bot) {
+	bot.auto_burrow = None;
+}
+/// Checks if auto burrow is enabled.
+pub fn is_enabled(bot: &Bot) -> bool {
+	bot.auto_burrow.is_some()
+}
+/// Returns state of auto burrow module if it's enabled.
+pub fn state(bot: &Bot) -> Option<&AutoBurrow> {
+	bot.auto_burrow.as_ref()
+}
+/// Returns mutable state of auto burrow module if it's enabled, used to change its settings.
+pub fn state_mut(bot: &mut Bot) -> Option<&mut AutoBurrow> {
+	bot.auto_burrow.as_mut()
+}
+
+impl AutoBurrow {
+	/// Makes unit with given tag burrow when idle and wait for enemies burrowed.
+	pub fn ambush(&mut self, tag: u64) {
+		self.ambush.insert(tag);
+	}
+	/// Stops ambush of unit with given tag. Unit stays burrowed until it's ordered to unburrow.
+	pub fn cancel_ambush(&mut self, tag: u64) {
+		self.ambush.remove(&tag);
+	}
+	/// Checks if unit with given tag was burrowed by module to regenerate.
+	pub fn is_healing(&self, tag: u64) -> bool {
+		self.healing.contains(&tag)
+	}
+}
+
+impl Bot {
+	pub(crate) fn auto_burrow_step(&mut self, events: &[Event]) {
+		let mut module = match self.auto_burrow.take() {
+			Some(module) => module,
+			None => return,
+		};
+
+		for e in events {
+			if let Event::UnitDestroyed(tag, _) = e {
+				module.healing.remove(tag);
+				module.ambush.remove(tag);
+			}
+		}
+
+		let has_burrow = self.has_upgrade(UpgradeId::Burrow);
+		for u in &self.units.my.units {
+			let tag = u.tag();
+			let type_id = u.type_id();
+			let base_type = if u.is_burrowed() {
+				UNIT_ALIAS.get(&type_id).copied().unwrap_or(type_id)
+			} else {
+				type_id
+			};
+			if !module.types.contains(&base_type) || (u.race() == Race::Zerg && !has_burrow) {
+				continue;
+			}
+			// Burrowing or unburrowing was already ordered on previous steps
+			if u.is_using_ability(AbilityId::BurrowDown) || u.is_using_ability(AbilityId::BurrowUp) {
+				continue;
+			}
+
+			let hits = u.hits_percentage().unwrap_or(1.0);
+			let detected = self.is_detected(u.position(), module.detection_gap);
+			if u.is_burrowed() {
+				let healed = module.healing.contains(&tag) && hits >= module.unburrow_above;
+				if detected || healed {
+					module.healing.remove(&tag);
+					u.command(AbilityId::BurrowUp, Target::None, false);
+				}
+			} else if !detected {
+				if hits < module.burrow_below {
+					module.healing.insert(tag);
+					u.command(AbilityId::BurrowDown, Target::None, false);
+				} else if module.ambush.contains(&tag) && u.is_idle() {
+					u.command(AbilityId::BurrowDown, Target::None, false);
+				}
+			}
+		}
+
+		self.auto_burrow = Some(module);
+	}
+}
@@ -0,0 +1,121 @@
+//! Auto GG: bot says "gg" and leaves the game when position is hopeless.
+//!
+//! Position is considered hopeless when bot has no townhalls, no mineral and vespene income,
+//! and total cost of its army is below threshold. Bot leaves the game a few seconds
+//! after the message, so opponent can see it.
+//!
+//! Module is disabled by default, it can be enabled with [`enable`] in
+//! [`on_start`](crate::Player::on_start), or with
+//! [`PlayerSettings::auto_gg`](crate::PlayerSettings::auto_gg):
+//! ```ignore
+//! fn get_player_settings(&self) -> PlayerSettings {
+//!     PlayerSettings::new(Race::Random).auto_gg(true)
+//! }
+//! ```
+//!
+//! Leaving the game is counted as Defeat, [`on_end`](crate::Player::on_end) is not called.
+
+use crate::{bot::Bot, consts::FRAMES_PER_SECOND};
+
+/// Settings and state of auto GG module. Stored in bot while module is enabled.
+#[derive(Debug, Clone)]
+pub struct AutoGg {
+	/// Position is hopeless only when total cost of army (minerals + vespene)
+	/// is below this value. [Default: `400`]
+	pub max_army_value: u32,
+	/// Message sent to chat before leaving. [Default: `"gg"`]
+	pub message: String,
+	/// Time in seconds between the message and leaving the game. [Default: `3.0`]
+	pub leave_delay: f32,
+	// Game loop when message was sent
+	said_at: Option<u32>,
+}
+impl Default for AutoGg {
+	fn default() -> Self {
+		Self {
+			max_army_value: 400,
+			message: "gg".to_string(),
+			leave_delay: 3.0,
+			said_at: None,
+		}
+	}
+}
+
+/// Enables auto GG.
+pub fn enable(bot: &mut Bot) {
+	bot.auto_gg.get_or_insert_with(Default::default);
+}
+/// Disables auto GG. If message was already sent, bot stays in the game.
+pub fn disable(bot: &mut Bot) {
+	bot.auto_gg = None;
+}
+/// Checks if auto GG is enabled.
+pub fn is_enabled(bot: &Bot) -> bool {
+	bot.auto_gg.is_some()
+}
+/// Returns state of auto GG module if it's enabled.
+pub fn state(bot: &Bot) -> Option<&AutoGg> {
+	bot.auto_gg.as_ref()
+}
+/// Returns mutable state of auto GG module if it's enabled, used to change its settings.
+pub fn state_mut(bot: &mut Bot) -> Option<&mut AutoGg> {
+	bot.auto_gg.as_mut()
+}
+
+impl AutoGg {
+	/// Checks if module has already sent the message and is going to leave the game.
+	pub fn is_leaving(&self) -> bool {
+		self.said_at.is_some()
+	}
+}
+
+impl Bot {
+	/// Checks if bot has no townhalls, no income and total cost of its army
+	/// is below given value.
+	pub fn is_hopeless(&self, max_army_value: u32) -> bool {
+		if !self.units.my.townhalls.is_empty() {
+			return false;
+		}
+		let score = &self.state.observation.score;
+		if score.collection_rate_minerals > 0.0 || score.collection_rate_vespene > 0.0 {
+			return false;
+		}
+		let army_value = self
+			.units
+			.my
+			.units
+			.iter()
+			.filter(|u| !u.is_worker() && !u.is_hallucination())
+			.map(|u| {
+				let cost = self.get_unit_cost(u.type_id());
+				cost.minerals + cost.vespene
+			})
+			.sum::<u32>();
+		army_value < max_army_value
+	}
+
+	pub(crate) fn auto_gg_step(&mut self) {
+		let mut module = match self.auto_gg.take() {
+			Some(module) => module,
+			None => return,
+		};
+
+		let game_loop = self.state.observation.game_loop();
+		match module.said_at {
+			Some(said_at) => {
+				let delay = (module.leave_delay * FRAMES_PER_SECOND) as u32;
+				if game_loop >= said_at + delay {
+					self.game_left = true;
+				}
+			}
+			None => {
+				if self.is_hopeless(module.max_army_value) {
+					self.chat(&module.message);
+					module.said_at = Some(game_loop);
+				}
+			}
+		}
+
+		self.auto_gg = Some(module);
+	}
+}
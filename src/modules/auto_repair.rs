@@ -0,0 +1,213 @@
+//! Auto repair: nearby SCVs repair damaged mechanical units and structures.
+//!
+//! Each damaged target gets a limited number of SCVs, closest free ones are chosen.
+//! SCVs are released and sent back to mining when target is fully repaired, dies,
+//! or when SCV itself gets in range of enemy units.
+//!
+//! Module is disabled by default, it can be enabled with [`enable`] in
+//! [`on_start`](crate::Player::on_start):
+//! ```ignore
+//! fn on_start(&mut self) -> SC2Result<()> {
+//!     rust_sc2::modules::auto_repair::enable(self);
+//!     if let Some(repair) = rust_sc2::modules::auto_repair::state_mut(self) {
+//!         repair.min_minerals = 100;
+//!     }
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Only SCVs which are idle or gathering resources are taken for repair,
+//! so builders and workers given other orders are never interrupted.
+
+use crate::{bot::Bot, distance::*, ids::UnitTypeId, unit::Unit, units::iter::UnitsIterator, Event};
+use rustc_hash::FxHashMap;
+
+/// Extra distance to enemy weapon range, at which SCV is considered in danger.
+const DANGER_GAP: f32 = 1.0;
+
+/// Settings and state of auto repair module. Stored in bot while module is enabled.
+#[derive(Debug, Clone)]
+pub struct AutoRepair {
+	/// Maximum number of SCVs repairing one unit. [Default: `2`]
+	pub max_per_unit: usize,
+	/// Maximum number of SCVs repairing one structure. [Default: `4`]
+	pub max_per_structure: usize,
+	/// New SCVs aren't assigned to repair while bot has less minerals than this. [Default: `0`]
+	pub min_minerals: u32,
+	/// Maximum distance from SCV to target. [Default: `15.0`]
+	pub max_distance: f32,
+	/// Units and structures with hits percentage below this value are repaired. [Default: `1.0`]
+	pub hits_threshold: f32,
+	// (scv, target)
+	repairers: FxHashMap<u64, u64>,
+}
+impl Default for AutoRepair {
+	fn default() -> Self {
+		Self {
+			max_per_unit: 2,
+			max_per_structure: 4,
+			min_minerals: 0,
+			max_distance: 15.0,
+			hits_threshold: 1.0,
+			repairers: Default::default(),
+		}
+	}
+}
+
+/// Enables auto repair for all own mechanical units and structures.
+pub fn enable(bot: &mut Bot) {
+	bot.auto_repair.get_or_insert_with(Default::default);
+}
+/// Disables auto repair. SCVs already repairing finish their current orders.
+pub fn disable(bot: &mut Bot) {
+	bot.auto_repair = None;
+}
+/// Checks if auto repair is enabled.
+pub fn is_enabled(bot: &Bot) -> bool {
+	bot.auto_repair.is_some()
+}
+/// Returns state of auto repair module if it's enabled.
+pub fn state(bot: &Bot) -> Option<&AutoRepair> {
+	bot.auto_repair.as_ref()
+}
+/// Returns mutable state of auto repair module if it's enabled, used to change its settings.
+pub fn state_mut(bot: &mut Bot) -> Option<&mut AutoRepair> {
+	bot.auto_repair.as_mut()
+}
+
+impl AutoRepair {
+	/// Returns target, which given SCV is assigned to repair.
+	pub fn repair_target(&self, scv: u64) -> Option<u64> {
+		self.repairers.get(&scv).copied()
+	}
+	/// Returns number of SCVs assigned to repair given target.
+	pub fn repairers_of(&self, target: u64) -> usize {
+		self.repairers.values().filter(|t| **t == target).count()
+	}
+}
+
+fn needs_repair(u: &Unit, threshold: f32) -> bool {
+	u.is_mechanical()
+		&& u.is_ready()
+		&& !u.is_hallucination()
+		&& u.hits_percentage().is_some_and(|hits| hits < threshold)
+}
+
+impl Bot {
+	pub(crate) fn auto_repair_step(&mut self, events: &[Event]) {
+		let mut module = match self.auto_repair.take() {
+			Some(module) => module,
+			None => return,
+		};
+
+		for e in events {
+			if let Event::UnitDestroyed(tag, _) = e {
+				module.repairers.remove(tag);
+			}
+		}
+
+		let threats = self
+			.units
+			.enemy
+			.all
+			.iter()
+			.filter(|u| u.can_attack_ground())
+			.collect::<Vec<_>>();
+		let in_danger = |w: &Unit| threats.iter().any(|t| w.in_real_range_of(t, DANGER_GAP));
+
+		let threshold = module.hits_threshold;
+
+		// Releasing SCVs which finished repair or are in danger
+		let mut released = Vec::new();
+		module.repairers.retain(|scv, target| {
+			let w = match self.units.my.workers.get(*scv) {
+				Some(w) => w,
+				None => return false,
+			};
+			let keep = self
+				.units
+				.my
+				.all
+				.get(*target)
+				.is_some_and(|t| needs_repair(t, threshold))
+				&& (w.is_repairing() || w.is_idle())
+				&& !in_danger(w);
+			if !keep && w.is_repairing() {
+				released.push(*scv);
+			}
+			keep
+		});
+		for scv in released {
+			if let Some(w) = self.units.my.workers.get(scv) {
+				self.return_to_mining(w);
+			}
+		}
+
+		if self.minerals >= module.min_minerals {
+			let mut free = self
+				.units
+				.my
+				.workers
+				.iter()
+				.of_type(UnitTypeId::SCV)
+				.filter(|w| {
+					!module.repairers.contains_key(&w.tag())
+						&& !w.is_hallucination()
+						&& (w.is_idle() || w.is_gathering() || w.is_returning())
+						&& !in_danger(w)
+				})
+				.collect::<Vec<_>>();
+
+			for target in self.units.my.all.iter().filter(|u| needs_repair(u, threshold)) {
+				let cap = if target.is_structure() {
+					module.max_per_structure
+				} else {
+					module.max_per_unit
+				};
+				let assigned = module.repairers_of(target.tag());
+				if assigned >= cap {
+					continue;
+				}
+				let pos = target.position();
+				free.sort_unstable_by(|a, b| {
+					a.distance_squared(pos)
+						.partial_cmp(&b.distance_squared(pos))
+						.unwrap()
+				});
+				// Damaged SCV can't repair itself, but others standing next to it can
+				let chosen = free
+					.iter()
+					.take_while(|w| w.is_closer(module.max_distance, pos))
+					.filter(|w| w.tag() != target.tag())
+					.take(cap - assigned)
+					.map(|w| w.tag())
+					.collect::<Vec<u64>>();
+				free.retain(|w| {
+					if chosen.contains(&w.tag()) {
+						w.repair(target.tag(), false);
+						module.repairers.insert(w.tag(), target.tag());
+						false
+					} else {
+						true
+					}
+				});
+			}
+		}
+
+		self.auto_repair = Some(module);
+	}
+	fn return_to_mining(&self, w: &Unit) {
+		let mineral = self
+			.units
+			.my
+			.townhalls
+			.iter()
+			.ready()
+			.closest(w)
+			.and_then(|th| self.units.mineral_fields.iter().closest(th));
+		match mineral {
+			Some(m) => w.gather(m.tag(), false),
+			None => w.stop(false),
+		}
+	}
+}
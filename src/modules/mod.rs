@@ -0,0 +1,6 @@
+//! Optional modules, which can be enabled to improve bot's behavior automatically.
+
+pub mod auto_burrow;
+pub mod auto_gg;
+pub mod auto_repair;
+pub mod speed_mining;
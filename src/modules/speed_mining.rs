@@ -0,0 +1,236 @@
+//! Speed mining: faster mineral gathering with precise worker movement.
+//!
+//! Each worker mining at own base gets its own mineral patch (at most 2 workers per patch).
+//! Near the patch or townhall worker is ordered to move to the point right next to it
+//! and then to gather or return cargo with queued command. This skips slowdown of
+//! default movement, which noticeably increases income.
+//!
+//! Module is disabled by default, it can be enabled with [`enable`] in
+//! [`on_start`](crate::Player::on_start):
+//! ```ignore
+//! fn on_start(&mut self) -> SC2Result<()> {
+//!     rust_sc2::modules::speed_mining::enable(self);
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Module only takes control of workers which are already gathering minerals at own bases,
+//! so it works together with manual distribution or [`WorkerManager`](crate::workers::WorkerManager).
+//! Workers given any other order (building, attacking, repairing, moving, gathering vespene
+//! or minerals of other base) are released.
+
+use crate::{action::Target, bot::Bot, distance::*, geometry::Point2, unit::Unit, Event};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+const MINERAL_RADIUS: f32 = 1.35;
+const WORKERS_PER_PATCH: usize = 2;
+
+/// State of speed mining module. Stored in bot while module is enabled.
+#[derive(Debug, Clone, Default)]
+pub struct SpeedMining {
+	// Townhalls, which minerals have move targets
+	bases: FxHashSet<u64>,
+	// (mineral, (move target, townhall))
+	targets: FxHashMap<u64, (Point2, u64)>,
+	// (mineral, workers)
+	assigned: FxHashMap<u64, FxHashSet<u64>>,
+	// (worker, mineral)
+	harvesters: FxHashMap<u64, u64>,
+}
+
+/// Enables speed mining for all own bases.
+pub fn enable(bot: &mut Bot) {
+	bot.speed_mining.get_or_insert_with(Default::default);
+}
+/// Disables speed mining. Workers continue mining with default behavior.
+pub fn disable(bot: &mut Bot) {
+	bot.speed_mining = None;
+}
+/// Checks if speed mining is enabled.
+pub fn is_enabled(bot: &Bot) -> bool {
+	bot.speed_mining.is_some()
+}
+/// Returns state of speed mining module if it's enabled.
+pub fn state(bot: &Bot) -> Option<&SpeedMining> {
+	bot.speed_mining.as_ref()
+}
+
+impl SpeedMining {
+	/// Returns mineral patch, which given worker is assigned to.
+	pub fn assigned_mineral(&self, worker: u64) -> Option<u64> {
+		self.harvesters.get(&worker).copied()
+	}
+	/// Returns point, where worker is ordered to move before gathering from given mineral patch.
+	pub fn move_target(&self, mineral: u64) -> Option<Point2> {
+		self.targets.get(&mineral).map(|(target, _)| *target)
+	}
+
+	fn release_worker(&mut self, worker: u64) {
+		if let Some(m) = self.harvesters.remove(&worker) {
+			if let Some(ws) = self.assigned.get_mut(&m) {
+				ws.remove(&worker);
+			}
+		}
+	}
+	fn remove_mineral(&mut self, mineral: u64) {
+		self.targets.remove(&mineral);
+		if let Some(ws) = self.assigned.remove(&mineral) {
+			for w in ws {
+				self.harvesters.remove(&w);
+			}
+		}
+	}
+	fn remove_base(&mut self, base: u64) {
+		if self.bases.remove(&base) {
+			let minerals = self
+				.targets
+				.iter()
+				.filter(|(_, (_, b))| *b == base)
+				.map(|(m, _)| *m)
+				.collect::<Vec<_>>();
+			for m in minerals {
+				self.remove_mineral(m);
+			}
+		}
+	}
+	fn add_base(&mut self, bot: &Bot, base: &Unit) {
+		let exp = match bot.expansions.of_base(base.tag()) {
+			Some(exp) => exp,
+			None => return,
+		};
+		self.bases.insert(base.tag());
+		let fields = &bot.units.mineral_fields;
+
+		for &m in &exp.minerals {
+			let mineral = match fields.get(m) {
+				Some(mineral) => mineral.position(),
+				None => continue,
+			};
+
+			// Default target point is straight towards the townhall
+			let mut target = mineral.towards(base.position(), MINERAL_RADIUS);
+
+			// Pushing target away from nearby mineral patches, so worker doesn't bump into them
+			let mut offset = Point2::new(0.0, 0.0);
+			for patch in fields
+				.iter()
+				.filter(|p| p.tag() != m && p.is_closer(MINERAL_RADIUS * 1.5, mineral))
+			{
+				let patch = patch.position();
+				offset += (patch.towards(target, 1.0) - patch) / mineral.distance(patch);
+			}
+			target = mineral.towards(target + offset, MINERAL_RADIUS);
+
+			self.targets.insert(m, (target, base.tag()));
+		}
+	}
+	// Picks patch of the same base for worker, preferring its current one.
+	fn assign(&mut self, worker: u64, mineral: u64) -> Option<u64> {
+		let base = self.targets.get(&mineral)?.1;
+		let load = |m: &u64| self.assigned.get(m).map_or(0, |ws| ws.len());
+		let patch = if load(&mineral) < WORKERS_PER_PATCH {
+			mineral
+		} else {
+			self.targets
+				.iter()
+				.filter(|(m, (_, b))| *b == base && load(m) < WORKERS_PER_PATCH)
+				.min_by_key(|(m, _)| load(m))
+				.map(|(m, _)| *m)?
+		};
+		self.harvesters.insert(worker, patch);
+		self.assigned.entry(patch).or_default().insert(worker);
+		Some(patch)
+	}
+}
+
+impl Bot {
+	pub(crate) fn speed_mining_step(&mut self, events: &[Event]) {
+		let mut module = match self.speed_mining.take() {
+			Some(module) => module,
+			None => return,
+		};
+
+		for e in events {
+			if let Event::UnitDestroyed(tag, _) = e {
+				module.release_worker(*tag);
+				module.remove_mineral(*tag);
+				module.remove_base(*tag);
+			}
+		}
+		for th in self.units.my.townhalls.iter() {
+			if th.is_ready() && !module.bases.contains(&th.tag()) {
+				module.add_base(self, th);
+			}
+		}
+
+		for w in self.units.my.workers.iter() {
+			if w.is_hallucination() {
+				continue;
+			}
+			let tag = w.tag();
+			let target = w.target_tag();
+			let base = module
+				.harvesters
+				.get(&tag)
+				.and_then(|m| module.targets.get(m))
+				.map(|(_, b)| *b);
+			// Gathering vespene or minerals of other base
+			let other_resource = || target.is_some_and(|t| module.targets.get(&t).map(|(_, b)| *b) != base);
+			if base.is_some()
+				&& (w.is_constructing()
+					|| (w.is_moving() && w.orders().len() < 2)
+					|| w.is_attacking()
+					|| w.is_repairing()
+					|| (w.is_gathering() && other_resource()))
+			{
+				module.release_worker(tag);
+				continue;
+			}
+
+			let mineral = match module.harvesters.get(&tag) {
+				Some(m) => *m,
+				None => match target.filter(|t| w.is_gathering() && module.targets.contains_key(t)) {
+					Some(t) => match module.assign(tag, t) {
+						Some(m) => m,
+						None => continue,
+					},
+					None => continue,
+				},
+			};
+			let (target, base_tag) = module.targets[&mineral];
+			if let Some(base) = self.units.my.townhalls.get(base_tag) {
+				micro(w, mineral, target, base);
+			}
+		}
+
+		self.speed_mining = Some(module);
+	}
+}
+
+fn micro(w: &Unit, mineral: u64, target: Point2, base: &Unit) {
+	// Only need to change orders if worker doesn't have 2 commands queued already
+	if w.orders().len() >= 2 {
+		return;
+	}
+	if w.is_carrying_resource() {
+		let target = base.position().towards(w.position(), base.radius() * 1.08);
+		let distance = w.distance_squared(target);
+		// Default behavior handles the first half of the trip
+		if distance > 0.5625 && distance < 4.0 {
+			w.move_to(Target::Pos(target), false);
+			w.smart(Target::Tag(base.tag()), true);
+		// Collisions can make worker stop
+		} else if !w.is_returning() {
+			w.smart(Target::Tag(base.tag()), false);
+		}
+	} else {
+		let distance = w.distance_squared(target);
+		if distance > 0.5625 && distance < 4.0 {
+			w.move_to(Target::Pos(target), false);
+			w.smart(Target::Tag(mineral), true);
+		// Game can switch mineral field or worker can drop cargo early
+		} else if !w.is_gathering() || w.target_tag().is_some_and(|t| t != mineral) {
+			w.gather(mineral, false);
+		}
+	}
+}
@@ -0,0 +1,150 @@
+//! Helpers for Nydus Network: placing Nydus Worms and moving army through them.
+//!
+//! Nydus Network and all Nydus Worms (canals) of a player share the same cargo,
+//! so units loaded into any entrance can be unloaded from any other connected one.
+
+use crate::{
+	action::{ActionResult, Target},
+	bot::Bot,
+	distance::*,
+	geometry::Point2,
+	ids::{AbilityId, UnitTypeId},
+	units::iter::UnitsIterator,
+};
+
+/// Minimum distance between placed Nydus Worm and enemy units, which can attack ground.
+const SAFE_DISTANCE: f32 = 10.0;
+/// Maximum number of candidate positions sent with one placement query.
+const MAX_CANDIDATES: usize = 40;
+
+impl Bot {
+	/// Returns tags of own ready Nydus Networks and Nydus Worms,
+	/// which are connected with each other and can be used to transfer units.
+	///
+	/// Worms are connected only while at least one Nydus Network is alive,
+	/// so empty `Vec` is returned when there's no ready network.
+	pub fn connected_nydus(&self) -> Vec<u64> {
+		let structures = &self.units.my.structures;
+		if structures
+			.iter()
+			.of_type(UnitTypeId::NydusNetwork)
+			.ready()
+			.next()
+			.is_none()
+		{
+			return Vec::new();
+		}
+		structures
+			.iter()
+			.of_types(&[UnitTypeId::NydusNetwork, UnitTypeId::NydusCanal])
+			.ready()
+			.map(|u| u.tag())
+			.collect()
+	}
+	/// Checks if given own Nydus Network or Nydus Worm is ready and connected to the network.
+	pub fn is_nydus_connected(&self, tag: u64) -> bool {
+		self.connected_nydus().contains(&tag)
+	}
+	/// Returns position near given point, where Nydus Worm can be placed safely.
+	///
+	/// Position must be visible, pathable, placeable and not closer than 10 to
+	/// known enemy units, which can attack ground. Candidates are checked locally first,
+	/// then the closest ones to given point are validated with a single placement query.
+	/// Errors of placement query are logged and result in `None`.
+	pub fn find_nydus_placement(&self, near: Point2, max_distance: f32) -> Option<Point2> {
+		let ability = self.game_data.units.get(&UnitTypeId::NydusCanal)?.ability?;
+		let size = self.get_building_size(UnitTypeId::NydusCanal)?;
+		let map = self.placement_map();
+		let threats = self
+			.units
+			.enemy
+			.all
+			.iter()
+			.filter(|u| u.can_attack_ground())
+			.map(|u| u.position())
+			.collect::<Vec<_>>();
+
+		let offset = if size % 2 == 0 { 0.0 } else { 0.5 };
+		let (x0, y0) = <(usize, usize)>::from((near - max_distance).floor());
+		let (x1, y1) = <(usize, usize)>::from((near + max_distance).ceil());
+		let mut candidates = iproduct!(x0..=x1, y0..=y1)
+			.map(|c| Point2::from(c).offset(offset, offset))
+			.filter(|pos| {
+				pos.is_closer(max_distance, near)
+					&& self.is_visible(*pos)
+					&& self.is_pathable(*pos)
+					&& map.can_place(*pos, size)
+					&& threats.iter().all(|t| t.is_further(SAFE_DISTANCE, *pos))
+			})
+			.collect::<Vec<_>>();
+		candidates.sort_unstable_by(|a, b| {
+			a.distance_squared(near)
+				.partial_cmp(&b.distance_squared(near))
+				.unwrap()
+		});
+		candidates.truncate(MAX_CANDIDATES);
+		if candidates.is_empty() {
+			return None;
+		}
+
+		let results = match self.query_placement(
+			candidates.iter().map(|pos| (ability, *pos, None)).collect(),
+			false,
+		) {
+			Ok(results) => results,
+			Err(e) => {
+				error!("Can't query placement of Nydus Worm: {}", e);
+				return None;
+			}
+		};
+		candidates
+			.into_iter()
+			.zip(results)
+			.find(|(_, res)| *res == ActionResult::Success)
+			.map(|(pos, _)| pos)
+	}
+	/// Orders idle ready Nydus Network to place Nydus Worm near given point
+	/// (see [`find_nydus_placement`](Self::find_nydus_placement)) and subtracts it's cost.
+	///
+	/// Returns tag of used Nydus Network, or `None` if there's no idle network,
+	/// bot can't afford worm or safe position wasn't found.
+	pub fn place_nydus_worm(&mut self, near: Point2, max_distance: f32) -> Option<u64> {
+		if !self.can_afford(UnitTypeId::NydusCanal, false) {
+			return None;
+		}
+		let network = self
+			.units
+			.my
+			.structures
+			.iter()
+			.of_type(UnitTypeId::NydusNetwork)
+			.ready()
+			.find(|u| u.is_idle())?
+			.tag();
+		let pos = self.find_nydus_placement(near, max_distance)?;
+		self.units
+			.my
+			.structures
+			.get(network)?
+			.build(UnitTypeId::NydusCanal, pos, false);
+		self.subtract_resources(UnitTypeId::NydusCanal, false);
+		Some(network)
+	}
+	/// Orders own units with given tags to enter given Nydus Network or Nydus Worm.
+	pub fn load_nydus(&self, entrance: u64, units: &[u64]) {
+		for u in &self.units.my.units.find_tags(units) {
+			u.smart(Target::Tag(entrance), false);
+		}
+	}
+	/// Orders given Nydus Network or Nydus Worm to unload all units in the network.
+	pub fn unload_nydus(&self, entrance: u64) {
+		if let Some(u) = self.units.my.structures.get(entrance) {
+			let ability = if u.type_id() == UnitTypeId::NydusNetwork {
+				AbilityId::UnloadAllNydasNetwork
+			} else {
+				AbilityId::UnloadAllNydusWorm
+			};
+			u.command(ability, Target::None, false);
+		}
+	}
+}
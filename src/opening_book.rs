@@ -0,0 +1,211 @@
+//! Selection of openings by results of previous games against the same opponent.
+//!
+//! Results are stored in a text file, one line per opponent and opening
+//! with tab-separated opponent id, opening name, wins, losses and ties.
+//! Tabs, line breaks and backslashes in opponent ids and opening names are escaped with backslash.
+//!
+//! Typical usage on ladder: load book and pick opening in [`on_start`](crate::Player::on_start)
+//! with [`suggest`](OpeningBook::suggest), then [`record`](OpeningBook::record) result and
+//! [`save`](OpeningBook::save) book in [`on_end`](crate::Player::on_end).
+//! Ladder usually allows writing files to `data` directory only.
+
+use crate::player::GameResult;
+use rand::prelude::*;
+use rustc_hash::FxHashMap;
+use std::{
+	fs,
+	io::{self, ErrorKind},
+	path::{Path, PathBuf},
+};
+
+/// Results of one opening against one opponent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpeningStats {
+	/// Number of games won.
+	pub wins: u32,
+	/// Number of games lost.
+	pub losses: u32,
+	/// Number of games ended in a tie.
+	pub ties: u32,
+}
+impl OpeningStats {
+	/// Total number of games played with the opening.
+	pub fn games(&self) -> u32 {
+		self.wins + self.losses + self.ties
+	}
+	/// Part of games won (ties count as half of win), or `0.0` if opening wasn't played.
+	pub fn win_rate(&self) -> f32 {
+		match self.games() {
+			0 => 0.0,
+			games => (self.wins as f32 + self.ties as f32 * 0.5) / games as f32,
+		}
+	}
+}
+
+/// Results of bot's openings against each opponent, persisted to file.
+#[derive(Debug, Clone)]
+pub struct OpeningBook {
+	path: PathBuf,
+	records: FxHashMap<String, FxHashMap<String, OpeningStats>>,
+	/// How much rarely played openings are preferred over ones with the best win rate,
+	/// when all openings were played at least once (untried ones are always picked first).
+	/// `0.0` always picks the best known opening. [Default: `1.0`]
+	pub exploration: f32,
+}
+impl OpeningBook {
+	/// Loads book from given file. If file doesn't exist, creates empty book,
+	/// which will be saved to that file.
+	pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+		let path = path.as_ref().to_path_buf();
+		let mut records = FxHashMap::<String, FxHashMap<String, OpeningStats>>::default();
+
+		match fs::read_to_string(&path) {
+			Ok(text) => {
+				for (i, line) in text.lines().enumerate() {
+					if line.trim().is_empty() {
+						continue;
+					}
+					let error = || {
+						io::Error::new(
+							ErrorKind::InvalidData,
+							format!("Invalid opening book record at line {}: {:?}", i + 1, line),
+						)
+					};
+					let fields = line.split('\t').collect::<Vec<_>>();
+					if fields.len() != 5 {
+						return Err(error());
+					}
+					let parse = |s: &str| s.parse::<u32>().map_err(|_| error());
+					let stats = OpeningStats {
+						wins: parse(fields[2])?,
+						losses: parse(fields[3])?,
+						ties: parse(fields[4])?,
+					};
+					records
+						.entry(unescape(fields[0]))
+						.or_default()
+						.insert(unescape(fields[1]), stats);
+				}
+			}
+			Err(e) if e.kind() == ErrorKind::NotFound => {}
+			Err(e) => return Err(e),
+		}
+
+		Ok(Self {
+			path,
+			records,
+			exploration: 1.0,
+		})
+	}
+	/// Writes book to the file it was loaded from.
+	pub fn save(&self) -> io::Result<()> {
+		let mut text = String::new();
+		for (opponent, openings) in &self.records {
+			for (opening, stats) in openings {
+				text.push_str(&format!(
+					"{}\t{}\t{}\t{}\t{}\n",
+					escape(opponent),
+					escape(opening),
+					stats.wins,
+					stats.losses,
+					stats.ties
+				));
+			}
+		}
+		if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+			fs::create_dir_all(dir)?;
+		}
+		fs::write(&self.path, text)
+	}
+	/// Returns results of given opening against given opponent.
+	pub fn stats(&self, opponent: &str, opening: &str) -> OpeningStats {
+		self.records
+			.get(opponent)
+			.and_then(|openings| openings.get(opening))
+			.copied()
+			.unwrap_or_default()
+	}
+	/// Adds result of a game to the book.
+	/// [`Undecided`](GameResult::Undecided) results are ignored.
+	pub fn record(&mut self, opponent: &str, opening: &str, result: GameResult) {
+		if result.is_undecided() {
+			return;
+		}
+		let stats = self
+			.records
+			.entry(opponent.to_string())
+			.or_default()
+			.entry(opening.to_string())
+			.or_default();
+		match result {
+			GameResult::Victory => stats.wins += 1,
+			GameResult::Defeat => stats.losses += 1,
+			GameResult::Tie => stats.ties += 1,
+			GameResult::Undecided => {}
+		}
+	}
+	/// Suggests opening against given opponent from given list.
+	///
+	/// Openings which weren't played yet are tried first (in random order),
+	/// then opening with the best upper confidence bound of win rate is chosen (UCB1),
+	/// where [`exploration`](Self::exploration) scales the bonus for rarely played openings.
+	///
+	/// Returns `None` if list of openings is empty.
+	pub fn suggest<'a>(&self, opponent: &str, openings: &[&'a str]) -> Option<&'a str> {
+		let stats = openings
+			.iter()
+			.map(|opening| (*opening, self.stats(opponent, opening)))
+			.collect::<Vec<_>>();
+
+		let untried = stats
+			.iter()
+			.filter(|(_, s)| s.games() == 0)
+			.map(|(opening, _)| *opening)
+			.collect::<Vec<_>>();
+		if let Some(opening) = untried.choose(&mut thread_rng()) {
+			return Some(opening);
+		}
+
+		let total = stats.iter().map(|(_, s)| s.games()).sum::<u32>() as f32;
+		stats
+			.into_iter()
+			.map(|(opening, s)| {
+				let bonus = (2.0 * total.ln() / s.games() as f32).sqrt();
+				(opening, s.win_rate() + self.exploration * bonus)
+			})
+			.max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+			.map(|(opening, _)| opening)
+	}
+}
+
+fn escape(s: &str) -> String {
+	let mut escaped = String::with_capacity(s.len());
+	for c in s.chars() {
+		match c {
+			'\\' => escaped.push_str("\\\\"),
+			'\t' => escaped.push_str("\\t"),
+			'\n' => escaped.push_str("\\n"),
+			'\r' => escaped.push_str("\\r"),
+			c => escaped.push(c),
+		}
+	}
+	escaped
+}
+fn unescape(s: &str) -> String {
+	let mut unescaped = String::with_capacity(s.len());
+	let mut chars = s.chars();
+	while let Some(c) = chars.next() {
+		if c != '\\' {
+			unescaped.push(c);
+			continue;
+		}
+		match chars.next() {
+			Some('t') => unescaped.push('\t'),
+			Some('n') => unescaped.push('\n'),
+			Some('r') => unescaped.push('\r'),
+			Some(c) => unescaped.push(c),
+			None => unescaped.push('\\'),
+		}
+	}
+	unescaped
+}
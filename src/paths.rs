@@ -29,7 +29,7 @@ pub fn get_path_to_sc2() -> String {
 			}
 			#[cfg(target_os = "linux")]
 			{
-				format!("{}/StarCraftII", home_dir().unwrap().to_str().unwrap())
+				find_linux_installation()
 			}
 			#[cfg(target_os = "macos")]
 			{
@@ -39,6 +39,57 @@ pub fn get_path_to_sc2() -> String {
 	}
 }
 
+/// Relative path to SC2 inside of Wine prefix.
+#[cfg(target_os = "linux")]
+const WINE_SC2_DIR: &str = "drive_c/Program Files (x86)/StarCraft II";
+
+// Checks native installation first, then Wine prefixes: `$WINEPREFIX`, default Lutris
+// and Wine prefixes, and Proton prefixes of Steam.
+#[cfg(target_os = "linux")]
+fn find_linux_installation() -> String {
+	let home = home_dir().unwrap().to_str().unwrap().to_string();
+	let native = format!("{}/StarCraftII", home);
+
+	let mut candidates = vec![native.clone()];
+	if let Some(prefix) = env::var_os("WINEPREFIX") {
+		candidates.push(format!("{}/{}", prefix.to_str().unwrap(), WINE_SC2_DIR));
+	}
+	candidates.push(format!("{}/Games/starcraft-ii/{}", home, WINE_SC2_DIR));
+	candidates.push(format!("{}/Games/battlenet/{}", home, WINE_SC2_DIR));
+	candidates.push(format!("{}/.wine/{}", home, WINE_SC2_DIR));
+	for steam in [".steam/steam", ".local/share/Steam"] {
+		if let Ok(dirs) = fs::read_dir(format!("{}/{}/steamapps/compatdata", home, steam)) {
+			candidates.extend(dirs.filter_map(|dir| {
+				let path = dir.ok()?.path();
+				Some(format!("{}/pfx/{}", path.to_str()?, WINE_SC2_DIR))
+			}));
+		}
+	}
+
+	candidates
+		.into_iter()
+		.find(|path| Path::new(&format!("{}/Versions", path)).is_dir())
+		.unwrap_or(native)
+}
+
+/// Checks if SC2 at given path is Windows installation, which should be launched through Wine.
+///
+/// Always `true` with `"wine_sc2"` feature and always `false` on Windows and macOS.
+pub fn is_wine_installation(sc2_path: &str) -> bool {
+	#[cfg(target_os = "linux")]
+	{
+		cfg!(feature = "wine_sc2")
+			|| ["Support64", "Support"]
+				.iter()
+				.any(|dir| Path::new(&format!("{}/{}", sc2_path, dir)).is_dir())
+	}
+	#[cfg(not(target_os = "linux"))]
+	{
+		let _ = sc2_path;
+		false
+	}
+}
+
 pub fn get_map_path(sc2_path: &str, map_name: &str) -> String {
 	let maps = {
 		let path = format!("{}/Maps", sc2_path);
@@ -53,9 +104,18 @@ pub fn get_map_path(sc2_path: &str, map_name: &str) -> String {
 			}
 		}
 	};
-	let map_path = format!("{}/{}.SC2Map", maps, map_name);
+	get_map_path_in(&maps, map_name, is_wine_installation(sc2_path))
+}
+
+/// Returns path to map with given name in given maps directory.
+/// If `wine` is `true`, path is converted to Windows one with `winepath`.
+///
+/// # Panics
+/// Panics if the map doesn't exist.
+pub fn get_map_path_in(maps_path: &str, map_name: &str, wine: bool) -> String {
+	let map_path = format!("{}/{}.SC2Map", maps_path, map_name);
 	fs::metadata(&map_path).unwrap_or_else(|_| panic!("Map doesn't exists: {}", map_path));
-	if cfg!(feature = "wine_sc2") {
+	if wine {
 		// Normalize the path using winepath
 		let mut path_cmd = std::process::Command::new("winepath");
 		path_cmd
@@ -0,0 +1,199 @@
+//! Performance statistics of bot: step durations, request round-trip time and actions per minute.
+//!
+//! Useful to make sure bot fits into time limits of ladder when playing in realtime.
+
+use crate::{bot::Bot, consts::FRAMES_PER_SECOND};
+use std::{fmt, time::Duration};
+
+/// Statistics of repeated measurements.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timing {
+	/// Number of measurements.
+	pub count: u32,
+	/// Sum of all measurements.
+	pub total: Duration,
+	/// The longest measurement.
+	pub max: Duration,
+	/// The latest measurement.
+	pub last: Duration,
+}
+impl Timing {
+	/// Adds new measurement.
+	pub fn add(&mut self, elapsed: Duration) {
+		self.count += 1;
+		self.total += elapsed;
+		self.max = self.max.max(elapsed);
+		self.last = elapsed;
+	}
+	/// Returns average of all measurements.
+	pub fn average(&self) -> Duration {
+		if self.count == 0 {
+			Duration::ZERO
+		} else {
+			self.total / self.count
+		}
+	}
+}
+impl fmt::Display for Timing {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"avg {:.2?}, max {:.2?}, total {:.2?} ({} times)",
+			self.average(),
+			self.max,
+			self.total,
+			self.count
+		)
+	}
+}
+
+/// Performance statistics of bot, collected every step.
+/// Can be accessed with [`perf_stats`](Bot::perf_stats).
+///
+/// Built-in sections are: `"update_state"` (processing of observation),
+/// `"managers"` (built-in worker and supply managers, speed mining), `"plugins_before_step"`,
+/// `"on_before_step"`, `"on_event"`, `"on_step"`, `"plugins_after_step"`, `"on_after_step"`
+/// and `"send_actions"`.
+/// Custom sections can be added with [`record`](Self::record).
+#[derive(Debug, Clone, Default)]
+pub struct PerfStats {
+	/// Duration of whole bot's step, excluding waiting for observation.
+	pub step: Timing,
+	/// Round-trip time of requests to SC2.
+	pub requests: Timing,
+	/// Total number of actions sent to the game.
+	pub actions: u64,
+	/// Log statistics with `info` level when game ends. [Default: `false`]
+	pub print_on_end: bool,
+	/// Checks steps against time budget, disabled by default. [Default: `None`]
+	pub watchdog: Option<Watchdog>,
+	sections: Vec<(&'static str, Timing)>,
+}
+impl PerfStats {
+	/// Adds measurement of section with given name.
+	pub fn record(&mut self, name: &'static str, elapsed: Duration) {
+		match self.sections.iter_mut().find(|(n, _)| *n == name) {
+			Some((_, timing)) => timing.add(elapsed),
+			None => {
+				let mut timing = Timing::default();
+				timing.add(elapsed);
+				self.sections.push((name, timing));
+			}
+		}
+	}
+	/// Returns timing of section with given name.
+	pub fn section(&self, name: &str) -> Option<&Timing> {
+		self.sections.iter().find(|(n, _)| *n == name).map(|(_, t)| t)
+	}
+	/// Returns timings of all sections in order of their first measurement.
+	pub fn sections(&self) -> impl Iterator<Item = (&'static str, &Timing)> {
+		self.sections.iter().map(|(n, t)| (*n, t))
+	}
+}
+impl fmt::Display for PerfStats {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "Step: {}", self.step)?;
+		writeln!(f, "Requests: {}", self.requests)?;
+		for (name, timing) in &self.sections {
+			writeln!(f, "  {}: {}", name, timing)?;
+		}
+		write!(f, "Actions: {}", self.actions)
+	}
+}
+
+/// Watchdog, which checks wall-clock time of every step against time budget.
+///
+/// Steps over budget are logged with timings of all sections.
+/// When steps are consistently over budget, watchdog can increase
+/// [`game_step`](Bot::game_step), so bot keeps up with realtime game.
+///
+/// Enabled by setting [`PerfStats::watchdog`]:
+/// ```ignore
+/// self.perf_stats_mut().watchdog = Some(Default::default());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Watchdog {
+	/// Time budget for one game loop, budget of step is multiplied by current game step.
+	/// [Default: duration of game loop in realtime (~44.6ms)]
+	pub budget_per_loop: Duration,
+	/// Log steps over budget with timings of sections. [Default: `true`]
+	pub log: bool,
+	/// Game step is increased by one when this number of steps in a row are over budget,
+	/// `None` disables it. [Default: `Some(10)`]
+	pub adapt_after: Option<u32>,
+	/// Watchdog doesn't increase game step above this value. [Default: `8`]
+	pub max_game_step: u32,
+	/// Total number of steps over budget.
+	pub overruns: u32,
+	streak: u32,
+}
+impl Default for Watchdog {
+	fn default() -> Self {
+		Self {
+			budget_per_loop: Duration::from_secs_f32(1.0 / FRAMES_PER_SECOND),
+			log: true,
+			adapt_after: Some(10),
+			max_game_step: 8,
+			overruns: 0,
+			streak: 0,
+		}
+	}
+}
+
+impl Bot {
+	pub(crate) fn watchdog_step(&mut self) {
+		let game_step = self.game_step();
+		let watchdog = match &mut self.perf.watchdog {
+			Some(watchdog) => watchdog,
+			None => return,
+		};
+		let budget = watchdog.budget_per_loop * game_step;
+		let elapsed = self.perf.step.last;
+		if elapsed <= budget {
+			watchdog.streak = 0;
+			return;
+		}
+
+		watchdog.overruns += 1;
+		watchdog.streak += 1;
+		if watchdog.log {
+			let sections = self
+				.perf
+				.sections
+				.iter()
+				.map(|(name, timing)| format!("{}: {:.2?}", name, timing.last))
+				.collect::<Vec<_>>();
+			warn!(
+				"Step at {:.1}s took {:.2?} (budget {:.2?}): {}",
+				self.time,
+				elapsed,
+				budget,
+				sections.join(", ")
+			);
+		}
+		if watchdog
+			.adapt_after
+			.is_some_and(|after| watchdog.streak >= after && game_step < watchdog.max_game_step)
+		{
+			watchdog.streak = 0;
+			warn!("Steps are over budget, increasing game step to {}", game_step + 1);
+			self.set_game_step(game_step + 1);
+		}
+	}
+	/// Returns performance statistics of bot.
+	pub fn perf_stats(&self) -> &PerfStats {
+		&self.perf
+	}
+	/// Returns mutable performance statistics, to change settings or record custom sections.
+	pub fn perf_stats_mut(&mut self) -> &mut PerfStats {
+		&mut self.perf
+	}
+	/// Average number of actions per minute of game time.
+	pub fn apm(&self) -> f32 {
+		if self.time > 0.0 {
+			self.perf.actions as f32 * 60.0 / self.time
+		} else {
+			0.0
+		}
+	}
+}
@@ -10,6 +10,11 @@ use std::{
 	ops::{Index, IndexMut},
 };
 
+#[cfg(feature = "png")]
+use crate::bot::{Bot, Locked};
+#[cfg(feature = "png")]
+use std::{fs, io, path::Path};
+
 /// 2-Dimensional Array of pixels, where each pixel is `Set` or is `Empty`.
 pub type PixelMap = Array2<Pixel>;
 /// 2-Dimensional Array of bytes.
@@ -47,6 +52,18 @@ impl FromProto<&ImageData> for PixelMap {
 		.reversed_axes()
 	}
 }
+/// Updates pixel map from proto in place, reusing its memory when size didn't change.
+pub(crate) fn update_pixel_map(map: &mut PixelMap, grid: &ImageData) {
+	let size = grid.get_size();
+	if map.dim() != (size.get_x() as usize, size.get_y() as usize) {
+		*map = PixelMap::from_proto(grid);
+		return;
+	}
+	let pixels = grid.get_data().iter().flat_map(|n| to_binary(*n));
+	for (cell, pixel) in map.view_mut().reversed_axes().iter_mut().zip(pixels) {
+		*cell = pixel;
+	}
+}
 impl FromProto<&ImageData> for ByteMap {
 	fn from_proto(grid: &ImageData) -> Self {
 		let size = grid.get_size();
@@ -116,3 +133,256 @@ impl Visibility {
 		!matches!(self, Visibility::Hidden)
 	}
 }
+
+/// Morphological operations and transforms of pixel maps, used in map analysis
+/// (e.g. finding walls, masks of base areas or zones safe from obstacles).
+///
+/// `Set` pixels are treated as foreground (obstacles), cells outside of the map count as `Set`.
+/// Neighborhoods are squares, connectivity is 4-directional.
+pub trait Morphology {
+	/// Expands set areas by given number of cells.
+	fn dilate(&self, radius: usize) -> PixelMap;
+	/// Shrinks set areas by given number of cells.
+	fn erode(&self, radius: usize) -> PixelMap;
+	/// Returns all cells connected to given one and having the same value.
+	fn flood_fill(&self, start: (usize, usize)) -> Vec<(usize, usize)>;
+	/// Returns groups of connected cells with given value.
+	fn connected_components(&self, value: Pixel) -> Vec<Vec<(usize, usize)>>;
+	/// Returns map of approximate distances from each cell to the nearest set cell.
+	fn distance_transform(&self) -> Array2<f32>;
+}
+impl Morphology for PixelMap {
+	fn dilate(&self, radius: usize) -> PixelMap {
+		square_filter(self, radius, Pixel::Set)
+	}
+	fn erode(&self, radius: usize) -> PixelMap {
+		square_filter(self, radius, Pixel::Empty)
+	}
+	fn flood_fill(&self, start: (usize, usize)) -> Vec<(usize, usize)> {
+		let value = match self.get(start) {
+			Some(value) => *value,
+			None => return Vec::new(),
+		};
+		let mut visited = Array2::from_elem(self.dim(), false);
+		fill_region(self, &mut visited, start, value)
+	}
+	fn connected_components(&self, value: Pixel) -> Vec<Vec<(usize, usize)>> {
+		let mut visited = Array2::from_elem(self.dim(), false);
+		let mut components = Vec::new();
+		for ((x, y), pixel) in self.indexed_iter() {
+			if *pixel == value && !visited[(x, y)] {
+				components.push(fill_region(self, &mut visited, (x, y), value));
+			}
+		}
+		components
+	}
+	fn distance_transform(&self) -> Array2<f32> {
+		const DIAGONAL: f32 = std::f32::consts::SQRT_2;
+
+		let (width, height) = self.dim();
+		let mut dist = self.map(|p| if p.is_set() { 0.0 } else { f32::INFINITY });
+		let relax = |dist: &mut Array2<f32>, x: usize, y: usize, dx: isize, dy: isize, cost: f32| {
+			let (nx, ny) = (x as isize + dx, y as isize + dy);
+			let neighbor = if nx < 0 || ny < 0 || nx >= width as isize || ny >= height as isize {
+				0.0
+			} else {
+				dist[(nx as usize, ny as usize)]
+			};
+			if neighbor + cost < dist[(x, y)] {
+				dist[(x, y)] = neighbor + cost;
+			}
+		};
+
+		// Chamfer distance in two passes: forward and backward
+		for y in 0..height {
+			for x in 0..width {
+				relax(&mut dist, x, y, -1, 0, 1.0);
+				relax(&mut dist, x, y, 0, -1, 1.0);
+				relax(&mut dist, x, y, -1, -1, DIAGONAL);
+				relax(&mut dist, x, y, 1, -1, DIAGONAL);
+			}
+		}
+		for y in (0..height).rev() {
+			for x in (0..width).rev() {
+				relax(&mut dist, x, y, 1, 0, 1.0);
+				relax(&mut dist, x, y, 0, 1, 1.0);
+				relax(&mut dist, x, y, 1, 1, DIAGONAL);
+				relax(&mut dist, x, y, -1, 1, DIAGONAL);
+			}
+		}
+		dist
+	}
+}
+
+// Sets cell to `value` if any cell in square around it has that value (outside of map is `Set`).
+fn square_filter(map: &PixelMap, radius: usize, value: Pixel) -> PixelMap {
+	if radius == 0 {
+		return map.clone();
+	}
+	let (width, height) = map.dim();
+	let pass = |src: &PixelMap, horizontal: bool| {
+		let mut dst = src.clone();
+		for ((x, y), cell) in dst.indexed_iter_mut() {
+			let (pos, len) = if horizontal { (x, width) } else { (y, height) };
+			let from = pos as isize - radius as isize;
+			let to = pos + radius;
+			let hit = ((from < 0 || to >= len) && value.is_set())
+				|| (from.max(0) as usize..=to.min(len - 1)).any(|i| {
+					let p = if horizontal { (i, y) } else { (x, i) };
+					src[p] == value
+				});
+			*cell = if hit { value } else { src[(x, y)] };
+		}
+		dst
+	};
+	pass(&pass(map, true), false)
+}
+
+fn fill_region(
+	map: &PixelMap,
+	visited: &mut Array2<bool>,
+	start: (usize, usize),
+	value: Pixel,
+) -> Vec<(usize, usize)> {
+	let (width, height) = map.dim();
+	let mut region = Vec::new();
+	let mut stack = vec![start];
+	visited[start] = true;
+	while let Some((x, y)) = stack.pop() {
+		region.push((x, y));
+		let neighbors = [
+			(x.wrapping_sub(1), y),
+			(x + 1, y),
+			(x, y.wrapping_sub(1)),
+			(x, y + 1),
+		];
+		for n in neighbors {
+			if n.0 < width && n.1 < height && !visited[n] && map[n] == value {
+				visited[n] = true;
+				stack.push(n);
+			}
+		}
+	}
+	region
+}
+
+/// Export of 2d maps to grayscale PNG images, useful for debugging map analysis.
+///
+/// Images are oriented the same way as in game: `y` axis goes up.
+/// Requires `"png"` feature.
+#[cfg(feature = "png")]
+pub trait SavePng {
+	/// Returns brightness of cell at given position.
+	fn brightness(&self, pos: (usize, usize)) -> u8;
+	/// Returns size of map.
+	fn size(&self) -> (usize, usize);
+	/// Saves map as grayscale PNG image to given path.
+	fn save_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+		let (width, height) = self.size();
+		let mut data = Vec::with_capacity(width * height);
+		for y in (0..height).rev() {
+			for x in 0..width {
+				data.push(self.brightness((x, y)));
+			}
+		}
+		write_png(path.as_ref(), width, height, false, &data)
+	}
+}
+/// Empty (free) pixels are white, set ones are black.
+#[cfg(feature = "png")]
+impl SavePng for PixelMap {
+	fn brightness(&self, pos: (usize, usize)) -> u8 {
+		if self[pos].is_empty() {
+			255
+		} else {
+			0
+		}
+	}
+	fn size(&self) -> (usize, usize) {
+		self.dim()
+	}
+}
+#[cfg(feature = "png")]
+impl SavePng for ByteMap {
+	fn brightness(&self, pos: (usize, usize)) -> u8 {
+		self[pos]
+	}
+	fn size(&self) -> (usize, usize) {
+		self.dim()
+	}
+}
+/// Visible positions are white, fogged are gray, hidden are black.
+#[cfg(feature = "png")]
+impl SavePng for VisibilityMap {
+	fn brightness(&self, pos: (usize, usize)) -> u8 {
+		match self[pos] {
+			Visibility::Visible => 255,
+			Visibility::Fogged => 128,
+			Visibility::Hidden | Visibility::FullHidden => 0,
+		}
+	}
+	fn size(&self) -> (usize, usize) {
+		self.dim()
+	}
+}
+
+#[cfg(feature = "png")]
+impl Bot {
+	/// Saves combined image of map layers to given path as PNG.
+	///
+	/// Terrain height is shown in gray, unpathable cells are tinted red, placeable are tinted green,
+	/// creep is tinted purple and cells which aren't visible now are darkened.
+	///
+	/// Requires `"png"` feature.
+	pub fn save_map_layers<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+		let info = &self.game_info;
+		let raw = &self.state.observation.raw;
+		let creep = raw.creep.read_lock();
+		let (width, height) = info.terrain_height.dim();
+
+		let mut data = Vec::with_capacity(width * height * 3);
+		for y in (0..height).rev() {
+			for x in 0..width {
+				let pos = (x, y);
+				let h = info.terrain_height[pos] as f32;
+				let mut color = [h, h, h];
+				let mut tint = |c: [f32; 3]| {
+					for (channel, c) in color.iter_mut().zip(c) {
+						*channel = (*channel + c) / 2.0;
+					}
+				};
+				if !info.pathing_grid.get(pos).is_some_and(|p| p.is_empty()) {
+					tint([255.0, 0.0, 0.0]);
+				}
+				if info.placement_grid.get(pos).is_some_and(|p| p.is_empty()) {
+					tint([0.0, 255.0, 0.0]);
+				}
+				if creep.get(pos).is_some_and(|p| p.is_empty()) {
+					tint([160.0, 0.0, 200.0]);
+				}
+				if !raw.visibility.get(pos).is_some_and(|v| v.is_visible()) {
+					color.iter_mut().for_each(|c| *c /= 2.0);
+				}
+				data.extend(color.iter().map(|c| *c as u8));
+			}
+		}
+		write_png(path.as_ref(), width, height, true, &data)
+	}
+}
+
+// Writes 8-bit grayscale or RGB PNG image
+#[cfg(feature = "png")]
+fn write_png(path: &Path, width: usize, height: usize, rgb: bool, data: &[u8]) -> io::Result<()> {
+	let file = io::BufWriter::new(fs::File::create(path)?);
+	let mut encoder = png::Encoder::new(file, width as u32, height as u32);
+	encoder.set_color(if rgb {
+		png::ColorType::Rgb
+	} else {
+		png::ColorType::Grayscale
+	});
+	encoder.set_depth(png::BitDepth::Eight);
+	let mut writer = encoder.write_header()?;
+	writer.write_image_data(data)?;
+	writer.finish()?;
+	Ok(())
+}
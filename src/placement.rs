@@ -0,0 +1,146 @@
+//! Local building placement engine.
+//!
+//! Validates placements using map data and known units,
+//! without sending [`query_placement`](crate::bot::Bot::query_placement) requests.
+
+use crate::{distance::*, geometry::Point2, ids::UnitTypeId, pixel_map::PixelMap, unit::Unit};
+use ndarray::Array2;
+
+/// Offset of addon from the center of building, which it is attached to.
+pub const ADDON_OFFSET: (f32, f32) = (2.5, -0.5);
+
+/// Terran structures, which can have an addon.
+pub const ADDON_STRUCTURES: [UnitTypeId; 3] =
+	[UnitTypeId::Barracks, UnitTypeId::Factory, UnitTypeId::Starport];
+
+/// Structures, which produce army units.
+pub const PRODUCTION_STRUCTURES: [UnitTypeId; 7] = [
+	UnitTypeId::Barracks,
+	UnitTypeId::Factory,
+	UnitTypeId::Starport,
+	UnitTypeId::Gateway,
+	UnitTypeId::WarpGate,
+	UnitTypeId::Stargate,
+	UnitTypeId::RoboticsFacility,
+];
+
+/// Map of tiles free for buildings.
+/// Can be obtained with [`placement_map`](crate::bot::Bot::placement_map).
+///
+/// Takes into account placement grid, footprints of structures and resources,
+/// and area between resources and townhalls, so buildings never block mining.
+///
+/// Note: it doesn't know about creep, psionic matrix or units standing on position,
+/// so found positions still should be checked with
+/// [`query_placement`](crate::bot::Bot::query_placement) before use.
+#[derive(Clone)]
+pub struct PlacementMap {
+	grid: Array2<bool>,
+}
+impl PlacementMap {
+	/// Creates map where all placeable tiles of given grid are free.
+	pub fn new(placement_grid: &PixelMap) -> Self {
+		Self {
+			grid: placement_grid.map(|p| p.is_empty()),
+		}
+	}
+	/// Checks if given tile is free.
+	pub fn is_free<P: Into<(usize, usize)>>(&self, pos: P) -> bool {
+		self.grid.get(pos.into()).copied().unwrap_or(false)
+	}
+	/// Checks if building of given size can be placed on given position.
+	pub fn can_place(&self, pos: Point2, size: usize) -> bool {
+		footprint(pos, size).is_some_and(|mut cells| cells.all(|c| self.is_free(c)))
+	}
+	/// Checks if building of given size with addon can be placed on given position.
+	pub fn can_place_with_addon(&self, pos: Point2, size: usize) -> bool {
+		self.can_place(pos, size) && self.can_place(pos.offset(ADDON_OFFSET.0, ADDON_OFFSET.1), 2)
+	}
+	/// Marks footprint of building as occupied, so it won't be used by next placements.
+	pub fn reserve(&mut self, pos: Point2, size: usize) {
+		if let Some(cells) = footprint(pos, size) {
+			for c in cells {
+				if let Some(tile) = self.grid.get_mut(c) {
+					*tile = false;
+				}
+			}
+		}
+	}
+	/// Marks footprint of building as free (e.g. when building was destroyed or lifted).
+	/// Doesn't make unplaceable tiles free.
+	pub fn release(&mut self, pos: Point2, size: usize, placement_grid: &PixelMap) {
+		if let Some(cells) = footprint(pos, size) {
+			for c in cells {
+				if let Some(tile) = self.grid.get_mut(c) {
+					*tile = placement_grid.get(c).is_some_and(|p| p.is_empty());
+				}
+			}
+		}
+	}
+	/// Marks all tiles within given circle as occupied.
+	pub fn reserve_circle(&mut self, center: Point2, radius: f32) {
+		let (x0, y0) = <(usize, usize)>::from((center - radius).floor());
+		let (x1, y1) = <(usize, usize)>::from((center + radius).ceil());
+		for c in iproduct!(x0..=x1, y0..=y1) {
+			if Point2::from(c).offset(0.5, 0.5).is_closer(radius, center) {
+				if let Some(tile) = self.grid.get_mut(c) {
+					*tile = false;
+				}
+			}
+		}
+	}
+	/// Marks all tiles in given distance from segment between `from` and `to` as occupied.
+	pub fn reserve_line(&mut self, from: Point2, to: Point2, width: f32) {
+		let min = Point2::new(from.x.min(to.x), from.y.min(to.y));
+		let max = Point2::new(from.x.max(to.x), from.y.max(to.y));
+		let (x0, y0) = <(usize, usize)>::from((min - width).floor());
+		let (x1, y1) = <(usize, usize)>::from((max + width).ceil());
+		let line = to - from;
+		let len_squared = line.dot(line);
+		for c in iproduct!(x0..=x1, y0..=y1) {
+			let p = Point2::from(c).offset(0.5, 0.5);
+			let t = if len_squared > 0.0 {
+				((p - from).dot(line) / len_squared).clamp(0.0, 1.0)
+			} else {
+				0.0
+			};
+			if p.is_closer(width, from + line * t) {
+				if let Some(tile) = self.grid.get_mut(c) {
+					*tile = false;
+				}
+			}
+		}
+	}
+	/// Marks footprint of given unit as occupied.
+	/// Structures, mineral fields and vespene geysers use their real footprints,
+	/// other units use their radius.
+	pub fn reserve_unit(&mut self, u: &Unit) {
+		if u.is_flying() {
+			return;
+		}
+		let pos = u.position();
+		if u.is_mineral() {
+			// Mineral fields are 2x1
+			self.reserve(pos.offset(-0.5, 0.0), 1);
+			self.reserve(pos.offset(0.5, 0.0), 1);
+		} else if u.is_geyser() {
+			self.reserve(pos, 3);
+		} else if let Some(size) = u.building_size() {
+			self.reserve(pos, size);
+		} else {
+			self.reserve_circle(pos, u.radius());
+		}
+	}
+}
+
+/// Returns tiles of building with given size placed on given position.
+fn footprint(pos: Point2, size: usize) -> Option<impl Iterator<Item = (usize, usize)>> {
+	let half = size as f32 / 2.0;
+	let x0 = (pos.x - half).round();
+	let y0 = (pos.y - half).round();
+	if x0 < 0.0 || y0 < 0.0 {
+		return None;
+	}
+	let (x0, y0) = (x0 as usize, y0 as usize);
+	Some(iproduct!(x0..x0 + size, y0..y0 + size))
+}
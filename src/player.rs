@@ -1,7 +1,11 @@
 //! Items representing various player's data.
 #![allow(missing_docs)]
 
-use crate::{FromProto, IntoProto};
+use crate::{
+	consts::FRAMES_PER_SECOND,
+	score::{Category, Score},
+	FromProto, IntoProto,
+};
 use num_traits::FromPrimitive;
 use sc2_proto::{
 	common::Race as ProtoRace,
@@ -201,3 +205,50 @@ impl FromProto<ProtoGameResult> for GameResult {
 		}
 	}
 }
+
+/// Reason why the game ended, guessed from the result and the last observation.
+#[variant_checkers]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GameEndReason {
+	/// Loser has no structures left.
+	Elimination,
+	/// Loser left the game while still having structures.
+	Surrender,
+	/// Game ended in a tie, usually because of time limit.
+	Timeout,
+	/// Result wasn't decided, e.g. opponent disconnected or game was closed.
+	Disconnect,
+}
+
+/// Summary of finished game passed to [`on_end_full`](crate::Player::on_end_full).
+#[derive(Clone)]
+pub struct GameSummary {
+	/// Result of the game for bot.
+	pub result: GameResult,
+	/// Reason why the game ended.
+	pub reason: GameEndReason,
+	/// Game loop on which game ended.
+	pub game_loop: u32,
+	/// Final score of bot.
+	pub score: Score,
+}
+impl GameSummary {
+	/// Game duration in in-game seconds.
+	pub fn duration(&self) -> f32 {
+		self.game_loop as f32 / FRAMES_PER_SECOND
+	}
+	/// Total cost (minerals + vespene) of enemy units killed by bot.
+	pub fn killed_value(&self) -> f32 {
+		self.score.killed_value_units
+	}
+	/// Total cost (minerals + vespene) of enemy structures destroyed by bot.
+	pub fn killed_structures_value(&self) -> f32 {
+		self.score.killed_value_structures
+	}
+	/// Total cost (minerals + vespene) of units and structures lost by bot.
+	pub fn lost_value(&self) -> f32 {
+		let sum = |c: &Category| c.none + c.army + c.economy + c.technology + c.upgrade;
+		sum(&self.score.lost_minerals) + sum(&self.score.lost_vespene)
+	}
+}
@@ -0,0 +1,101 @@
+//! Plugins: user or third-party modules, which are called in the game loop automatically.
+//!
+//! Plugin is registered once with [`add_plugin`](Bot::add_plugin) and then called
+//! every step, so there's no need to wire its calls in `on_step` manually:
+//! ```ignore
+//! struct ChatLogger;
+//! impl Plugin for ChatLogger {
+//!     fn before_step(&mut self, _bot: &mut Bot, events: &[Event]) -> SC2Result<()> {
+//!         for e in events {
+//!             if let Event::ChatMessage { message, .. } = e {
+//!                 println!("{}", message);
+//!             }
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//!
+//! fn on_start(&mut self) -> SC2Result<()> {
+//!     self.add_plugin(ChatLogger);
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Plugins are called in order of registration. With `"rayon"` feature plugins must be `Send + Sync`.
+
+use crate::{bot::Bot, client::SC2Result, Event};
+use std::mem;
+
+/// Module called by the game loop. All hooks are optional.
+pub trait Plugin {
+	/// Called on game start before [`on_start`](crate::Player::on_start)
+	/// or on the next step if plugin was added during the game.
+	fn on_start(&mut self, _bot: &mut Bot) -> SC2Result<()> {
+		Ok(())
+	}
+	/// Called every step after state is updated and built-in managers are run,
+	/// before [`on_event`](crate::Player::on_event) and [`on_step`](crate::Player::on_step).
+	fn before_step(&mut self, _bot: &mut Bot, _events: &[Event]) -> SC2Result<()> {
+		Ok(())
+	}
+	/// Called every step after [`on_step`](crate::Player::on_step), before actions are sent.
+	fn after_step(&mut self, _bot: &mut Bot) -> SC2Result<()> {
+		Ok(())
+	}
+}
+
+#[cfg(feature = "rayon")]
+type BoxedPlugin = Box<dyn Plugin + Send + Sync>;
+#[cfg(not(feature = "rayon"))]
+type BoxedPlugin = Box<dyn Plugin>;
+
+// Plugins are pending until their `on_start` is called
+#[derive(Default)]
+pub(crate) struct Plugins {
+	started: Vec<BoxedPlugin>,
+	pending: Vec<BoxedPlugin>,
+}
+
+impl Bot {
+	/// Registers plugin, which will be called in the game loop (see [`Plugin`]).
+	#[cfg(not(feature = "rayon"))]
+	pub fn add_plugin<P: Plugin + 'static>(&mut self, plugin: P) {
+		self.plugins.pending.push(Box::new(plugin));
+	}
+	/// Registers plugin, which will be called in the game loop (see [`Plugin`]).
+	#[cfg(feature = "rayon")]
+	pub fn add_plugin<P: Plugin + Send + Sync + 'static>(&mut self, plugin: P) {
+		self.plugins.pending.push(Box::new(plugin));
+	}
+	/// Returns number of registered plugins.
+	pub fn plugins_count(&self) -> usize {
+		self.plugins.started.len() + self.plugins.pending.len()
+	}
+
+	pub(crate) fn plugins_start(&mut self) -> SC2Result<()> {
+		while !self.plugins.pending.is_empty() {
+			let pending = mem::take(&mut self.plugins.pending);
+			for mut plugin in pending {
+				plugin.on_start(self)?;
+				self.plugins.started.push(plugin);
+			}
+		}
+		Ok(())
+	}
+	pub(crate) fn plugins_before_step(&mut self, events: &[Event]) -> SC2Result<()> {
+		self.plugins_start()?;
+		self.plugins_call(|plugin, bot| plugin.before_step(bot, events))
+	}
+	pub(crate) fn plugins_after_step(&mut self) -> SC2Result<()> {
+		self.plugins_call(|plugin, bot| plugin.after_step(bot))
+	}
+	fn plugins_call<F>(&mut self, mut f: F) -> SC2Result<()>
+	where
+		F: FnMut(&mut dyn Plugin, &mut Bot) -> SC2Result<()>,
+	{
+		let mut plugins = mem::take(&mut self.plugins.started);
+		let result = plugins.iter_mut().try_for_each(|plugin| f(plugin.as_mut(), self));
+		self.plugins.started = plugins;
+		result
+	}
+}
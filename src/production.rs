@@ -0,0 +1,345 @@
+//! Simple production manager.
+
+use crate::{
+	action::ActionResult,
+	bot::{Bot, UnitAlias},
+	consts::{ALL_PRODUCERS, TECH_REQUIREMENTS, WARPGATE_ABILITIES},
+	distance::*,
+	geometry::Point2,
+	ids::{AbilityId, UnitTypeId},
+	player::Race,
+	units::{iter::UnitsIterator, Units},
+};
+use std::{error::Error, fmt};
+
+/// Distance from hatchery to larva, at which larva is considered to belong to it.
+const LARVA_DISTANCE: f32 = 4.0;
+
+/// Reason why [`produce`](Bot::produce) failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProduceError {
+	/// There's no data for given unit type or it can't be produced.
+	UnknownUnit,
+	/// Given unit type is a structure, which should be built by a worker.
+	NeedsWorker,
+	/// Not enough minerals or vespene.
+	CannotAfford,
+	/// Not enough free supply.
+	NotEnoughSupply,
+	/// Required tech structure isn't complete.
+	TechRequirement(UnitTypeId),
+	/// Bot doesn't have any ready structure or unit which can produce given unit type.
+	NoProducer,
+	/// All producers are busy.
+	ProducersBusy,
+	/// Warp gates are ready, but there's no free space in power fields to warp unit in.
+	NoWarpPlacement,
+	/// Given unit doesn't exist or can't morph into given unit type.
+	InvalidMorph,
+}
+impl fmt::Display for ProduceError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::UnknownUnit => write!(f, "Unit type can't be produced"),
+			Self::NeedsWorker => write!(f, "Structure should be built by a worker"),
+			Self::CannotAfford => write!(f, "Not enough resources"),
+			Self::NotEnoughSupply => write!(f, "Not enough supply"),
+			Self::TechRequirement(tech) => write!(f, "{:?} is required", tech),
+			Self::NoProducer => write!(f, "There're no producers"),
+			Self::ProducersBusy => write!(f, "All producers are busy"),
+			Self::NoWarpPlacement => write!(f, "No place to warp in unit"),
+			Self::InvalidMorph => write!(f, "Unit can't morph into given type"),
+		}
+	}
+}
+impl Error for ProduceError {}
+
+impl Bot {
+	/// Orders to produce unit of given type and subtracts it's cost.
+	///
+	/// Chooses the least busy ready producer: structure (taking techlab and reactor into account),
+	/// larva or unit to morph from. If protoss unit can't be trained in gateway,
+	/// it's warped in with ready warp gate in psionic matrix closest to opponent's start location.
+	///
+	/// Returns tag of producer, or reason why unit can't be produced.
+	pub fn produce(&mut self, unit: UnitTypeId) -> Result<u64, ProduceError> {
+		let data = self.game_data.units.get(&unit).ok_or(ProduceError::UnknownUnit)?;
+		if data.ability.is_none() {
+			return Err(ProduceError::UnknownUnit);
+		}
+		let require_techlab = data.require_attached;
+
+		let producers = ALL_PRODUCERS.get(&unit).ok_or(ProduceError::UnknownUnit)?;
+		if producers.iter().any(|p| p.is_worker()) {
+			return Err(ProduceError::NeedsWorker);
+		}
+		if let Some(&tech) = TECH_REQUIREMENTS.get(&unit) {
+			if !self.has_tech(tech) {
+				return Err(ProduceError::TechRequirement(tech));
+			}
+		}
+		if !self.can_afford(unit, false) {
+			return Err(ProduceError::CannotAfford);
+		}
+		if !self.can_afford(unit, true) {
+			return Err(ProduceError::NotEnoughSupply);
+		}
+
+		let mut has_producers = false;
+		let producer = self
+			.units
+			.my
+			.all
+			.iter()
+			.of_types(producers)
+			.filter(|u| {
+				u.type_id() != UnitTypeId::WarpGate
+					&& u.is_ready() && !u.is_flying()
+					&& (!u.is_structure()
+						|| u.race() != Race::Protoss
+						|| u.type_id() == UnitTypeId::Nexus
+						|| u.is_powered())
+					&& (!require_techlab || u.has_techlab())
+			})
+			.inspect(|_| has_producers = true)
+			.filter(|u| u.orders().len() < if u.has_reactor() { 2 } else { 1 })
+			.min_by_key(|u| u.orders().len())
+			.map(|u| {
+				u.train(unit, false);
+				u.tag()
+			});
+		if let Some(tag) = producer {
+			self.subtract_resources(unit, true);
+			return Ok(tag);
+		}
+
+		if WARPGATE_ABILITIES.contains_key(&unit) {
+			match self.warp_in_near(unit, self.enemy_start) {
+				Err(ProduceError::NoProducer) => {}
+				result => return result,
+			}
+			has_producers |= self
+				.units
+				.my
+				.structures
+				.iter()
+				.of_type(UnitTypeId::WarpGate)
+				.next()
+				.is_some();
+		}
+
+		if has_producers {
+			Err(ProduceError::ProducersBusy)
+		} else {
+			Err(ProduceError::NoProducer)
+		}
+	}
+	/// Orders own unit with given tag to morph into given type and subtracts morph cost.
+	///
+	/// Works for unit morphs (Baneling, Ravager, Overseer, Lurker, Brood Lord)
+	/// and structure morphs (Lair, Hive, Greater Spire). Checks that unit is ready and
+	/// can morph into given type, tech requirement is complete and bot can afford the morph.
+	/// Only the difference in cost and supply between unit and result of the morph is counted,
+	/// so e.g. Baneling costs no supply and Ravager costs only one.
+	///
+	/// Structures must be idle to morph, while units drop their current orders.
+	pub fn morph(&mut self, tag: u64, into: UnitTypeId) -> Result<(), ProduceError> {
+		let data = self.game_data.units.get(&into).ok_or(ProduceError::UnknownUnit)?;
+		if data.ability.is_none() {
+			return Err(ProduceError::UnknownUnit);
+		}
+
+		let u = self.units.my.all.get(tag).ok_or(ProduceError::InvalidMorph)?;
+		if !ALL_PRODUCERS
+			.get(&into)
+			.is_some_and(|producers| producers.contains(&u.type_id()))
+		{
+			return Err(ProduceError::InvalidMorph);
+		}
+		if !u.is_ready() {
+			return Err(ProduceError::NoProducer);
+		}
+		if u.is_structure() && !u.is_idle() {
+			return Err(ProduceError::ProducersBusy);
+		}
+
+		if let Some(&tech) = TECH_REQUIREMENTS.get(&into) {
+			if !self.has_tech(tech) {
+				return Err(ProduceError::TechRequirement(tech));
+			}
+		}
+		if !self.can_afford(into, false) {
+			return Err(ProduceError::CannotAfford);
+		}
+		if !self.can_afford(into, true) {
+			return Err(ProduceError::NotEnoughSupply);
+		}
+
+		u.train(into, false);
+		self.subtract_resources(into, true);
+		Ok(())
+	}
+	/// Orders to warp in unit of given type as close as possible to given position
+	/// and subtracts it's cost.
+	///
+	/// Looks for free pathable place in psionic matrices of pylons and warp prisms,
+	/// starting from the ones closest to given position, and warps unit in
+	/// with the closest ready warp gate.
+	///
+	/// Returns tag of used warp gate, or reason why unit can't be warped in.
+	pub fn warp_in(&mut self, unit: UnitTypeId, near: Point2) -> Result<u64, ProduceError> {
+		if !WARPGATE_ABILITIES.contains_key(&unit) {
+			return Err(ProduceError::UnknownUnit);
+		}
+		if let Some(&tech) = TECH_REQUIREMENTS.get(&unit) {
+			if !self.has_tech(tech) {
+				return Err(ProduceError::TechRequirement(tech));
+			}
+		}
+		if !self.can_afford(unit, false) {
+			return Err(ProduceError::CannotAfford);
+		}
+		if !self.can_afford(unit, true) {
+			return Err(ProduceError::NotEnoughSupply);
+		}
+
+		match self.warp_in_near(unit, near) {
+			Err(ProduceError::NoProducer)
+				if self
+					.units
+					.my
+					.structures
+					.iter()
+					.of_type(UnitTypeId::WarpGate)
+					.next()
+					.is_some() =>
+			{
+				Err(ProduceError::ProducersBusy)
+			}
+			result => result,
+		}
+	}
+	/// Returns own production structures, which could be producing but aren't:
+	/// - Barracks, Factories, Starports, Gateways, Stargates and Robotics Facilities
+	///   without orders (or with one order if structure has reactor)
+	/// - Command Centers, Orbital Commands, Planetary Fortresses and Nexuses without orders
+	/// - Warp Gates, which are ready to warp in units
+	/// - Hatcheries, Lairs and Hives with unused larva nearby
+	///
+	/// Warp gates are checked with [`Unit::has_ability`](crate::unit::Unit::has_ability),
+	/// so they should be in [`abilities_scope`](Self::abilities_scope).
+	pub fn idle_production(&self) -> Units {
+		let larvas = &self.units.my.larvas;
+		self.units
+			.my
+			.structures
+			.iter()
+			.ready()
+			.filter(|u| match u.type_id() {
+				UnitTypeId::Barracks
+				| UnitTypeId::Factory
+				| UnitTypeId::Starport
+				| UnitTypeId::Gateway
+				| UnitTypeId::Stargate
+				| UnitTypeId::RoboticsFacility => u.is_unused(),
+				UnitTypeId::CommandCenter
+				| UnitTypeId::OrbitalCommand
+				| UnitTypeId::PlanetaryFortress
+				| UnitTypeId::Nexus => u.is_idle(),
+				UnitTypeId::WarpGate => u.has_ability(AbilityId::WarpGateTrainZealot),
+				UnitTypeId::Hatchery | UnitTypeId::Lair | UnitTypeId::Hive => {
+					larvas.iter().any(|l| l.is_closer(LARVA_DISTANCE, u))
+				}
+				_ => false,
+			})
+			.cloned()
+			.collect()
+	}
+	// Required structure or its higher tier is complete (Hive satisfies Lair, but Hatchery doesn't)
+	fn has_tech(&self, tech: UnitTypeId) -> bool {
+		let mut counter = self.counter();
+		counter.alias = UnitAlias::Auto;
+		counter.count(tech) > 0
+	}
+	fn warp_in_near(&mut self, unit: UnitTypeId, near: Point2) -> Result<u64, ProduceError> {
+		let ability = WARPGATE_ABILITIES[&unit];
+		let warpgates = self
+			.units
+			.my
+			.structures
+			.iter()
+			.of_type(UnitTypeId::WarpGate)
+			.ready()
+			.filter(|u| u.has_ability(ability))
+			.collect::<Vec<_>>();
+		if warpgates.is_empty() {
+			return Err(ProduceError::NoProducer);
+		}
+
+		let pos = self
+			.find_warp_placement(unit, near)
+			.ok_or(ProduceError::NoWarpPlacement)?;
+		let warpgate = warpgates.into_iter().closest(pos).unwrap();
+		warpgate.warp_in(unit, pos);
+		let tag = warpgate.tag();
+		self.subtract_resources(unit, true);
+		Ok(tag)
+	}
+	fn find_warp_placement(&self, unit: UnitTypeId, target: Point2) -> Option<Point2> {
+		let ability = *WARPGATE_ABILITIES.get(&unit)?;
+		let mut matrix = self
+			.state
+			.observation
+			.raw
+			.psionic_matrix
+			.iter()
+			.collect::<Vec<_>>();
+		matrix.sort_unstable_by(|a, b| {
+			(a.pos.distance(target) - a.radius)
+				.partial_cmp(&(b.pos.distance(target) - b.radius))
+				.unwrap()
+		});
+
+		for source in matrix {
+			let mut positions = Vec::new();
+			let mut distance = 2.0;
+			while distance < source.radius {
+				let count = (distance * 4.0) as usize;
+				for i in 0..count {
+					let angle = i as f32 * std::f32::consts::TAU / count as f32;
+					let pos = source.pos.offset(distance * angle.cos(), distance * angle.sin());
+					if self.is_pathable(pos) {
+						positions.push(pos);
+					}
+				}
+				distance += 1.5;
+			}
+			positions.sort_unstable_by(|a, b| {
+				a.distance_squared(target)
+					.partial_cmp(&b.distance_squared(target))
+					.unwrap()
+			});
+			if positions.is_empty() {
+				continue;
+			}
+
+			let results = match self
+				.query_placement(positions.iter().map(|pos| (ability, *pos, None)).collect(), false)
+			{
+				Ok(results) => results,
+				Err(e) => {
+					error!("Can't query warp-in placement of {:?}: {}", unit, e);
+					return None;
+				}
+			};
+			if let Some((pos, _)) = positions
+				.into_iter()
+				.zip(results)
+				.find(|(_, res)| *res == ActionResult::Success)
+			{
+				return Some(pos);
+			}
+		}
+		None
+	}
+}
@@ -0,0 +1,197 @@
+//! Batching of different queries into a single request.
+//!
+//! Every query method of [`Bot`] makes its own round trip to the game.
+//! If bot needs several query types on the same step, they can be sent together:
+//! ```ignore
+//! let results = self
+//!     .query()
+//!     .pathing(Target::Pos(self.start_location), self.enemy_start)
+//!     .placement(AbilityId::ProtossBuildPylon, pos, None)
+//!     .abilities(caster.tag())
+//!     .send()?;
+//! ```
+
+use crate::{
+	action::{ActionResult, Target},
+	bot::{placement_key, Bot, Locked, PathingResult},
+	geometry::Point2,
+	ids::AbilityId,
+	FromProto, IntoProto, SC2Result,
+};
+use num_traits::{FromPrimitive, ToPrimitive};
+use rustc_hash::{FxHashMap, FxHashSet};
+use sc2_proto::{
+	query::{
+		RequestQueryAvailableAbilities, RequestQueryBuildingPlacement, RequestQueryPathing,
+		ResponseQueryAvailableAbilities, ResponseQueryPathing,
+	},
+	sc2api::Request,
+};
+
+/// Builder of request with pathing, placement and abilities queries.
+/// Created with [`query`](Bot::query).
+pub struct QueryBuilder<'a> {
+	bot: &'a Bot,
+	paths: Vec<(Target, Point2)>,
+	places: Vec<(AbilityId, Point2, Option<u64>)>,
+	units: Vec<u64>,
+	check_resources: bool,
+}
+impl<'a> QueryBuilder<'a> {
+	fn new(bot: &'a Bot) -> Self {
+		Self {
+			bot,
+			paths: Vec::new(),
+			places: Vec::new(),
+			units: Vec::new(),
+			check_resources: false,
+		}
+	}
+	/// Adds pathing query from `start` (position or unit tag) to `goal`.
+	/// See [`query_pathing`](Bot::query_pathing).
+	pub fn pathing(mut self, start: Target, goal: Point2) -> Self {
+		self.paths.push((start, goal));
+		self
+	}
+	/// Adds placement query of given build ability at given position,
+	/// optionally checked for given worker. See [`query_placement`](Bot::query_placement).
+	pub fn placement(mut self, ability: AbilityId, pos: Point2, builder: Option<u64>) -> Self {
+		self.places.push((ability, pos, builder));
+		self
+	}
+	/// Adds query of abilities available for own unit with given tag.
+	/// See [`query_abilities`](Bot::query_abilities).
+	pub fn abilities(mut self, tag: u64) -> Self {
+		self.units.push(tag);
+		self
+	}
+	/// Sets if placement queries should take bot's resources into account. [Default: `false`]
+	pub fn check_resources(mut self, val: bool) -> Self {
+		self.check_resources = val;
+		self
+	}
+	/// Sends all added queries in one request.
+	///
+	/// Placement results are cached the same way as in [`query_placement`](Bot::query_placement)
+	/// and abilities are also accessible with [`Unit::abilities`](crate::unit::Unit::abilities)
+	/// until the next step.
+	pub fn send(self) -> SC2Result<QueryResults> {
+		if self.paths.is_empty() && self.places.is_empty() && self.units.is_empty() {
+			return Ok(Default::default());
+		}
+
+		let mut req = Request::new();
+		let req_query = req.mut_query();
+		req_query.set_ignore_resource_requirements(!self.check_resources);
+
+		let req_pathing = req_query.mut_pathing();
+		for path in &self.paths {
+			req_pathing.push(pathing_request(*path));
+		}
+		let req_placement = req_query.mut_placements();
+		for place in &self.places {
+			req_placement.push(placement_request(*place));
+		}
+		let req_abilities = req_query.mut_abilities();
+		for tag in &self.units {
+			req_abilities.push(abilities_request(*tag));
+		}
+
+		let res = self.bot.api().send(req)?;
+		let res_query = res.get_query();
+
+		let pathing = res_query.get_pathing().iter().map(pathing_result).collect();
+
+		let placements = res_query
+			.get_placements()
+			.iter()
+			.map(|result| ActionResult::from_proto(result.get_result()))
+			.collect::<Vec<_>>();
+		let mut cache = self.bot.placement_cache.write_lock();
+		for (place, result) in self.places.iter().zip(&placements) {
+			cache.insert(placement_key(*place, self.check_resources), *result);
+		}
+		drop(cache);
+
+		// SC2 doesn't return abilities of dead or invisible units, so results are matched by tags
+		let mut by_tag = res_query
+			.get_abilities()
+			.iter()
+			.map(abilities_result)
+			.collect::<FxHashMap<_, _>>();
+		let abilities = self
+			.units
+			.iter()
+			.map(|tag| by_tag.get(tag).cloned().unwrap_or_default())
+			.collect();
+		self.bot.abilities_units.write_lock().extend(by_tag.drain());
+
+		Ok(QueryResults {
+			pathing,
+			placements,
+			abilities,
+		})
+	}
+}
+
+/// Results of queries sent with [`QueryBuilder`], ordered the same way as queries were added.
+#[derive(Debug, Clone, Default)]
+pub struct QueryResults {
+	/// Results of pathing queries.
+	pub pathing: Vec<PathingResult>,
+	/// Results of placement queries.
+	pub placements: Vec<ActionResult>,
+	/// Available abilities of queried units.
+	pub abilities: Vec<FxHashSet<AbilityId>>,
+}
+
+impl Bot {
+	/// Starts building request with several queries of different types,
+	/// which is sent in one round trip. See [`QueryBuilder`].
+	pub fn query(&self) -> QueryBuilder<'_> {
+		QueryBuilder::new(self)
+	}
+}
+
+pub(crate) fn pathing_request((start, goal): (Target, Point2)) -> RequestQueryPathing {
+	let mut pathing = RequestQueryPathing::new();
+	match start {
+		Target::Tag(tag) => pathing.set_unit_tag(tag),
+		Target::Pos(pos) => pathing.set_start_pos(pos.into_proto()),
+		Target::None => panic!("start pos is not specified in query pathing request"),
+	}
+	pathing.set_end_pos(goal.into_proto());
+	pathing
+}
+pub(crate) fn placement_request(
+	(ability, pos, builder): (AbilityId, Point2, Option<u64>),
+) -> RequestQueryBuildingPlacement {
+	let mut placement = RequestQueryBuildingPlacement::new();
+	placement.set_ability_id(ability.to_i32().unwrap());
+	placement.set_target_pos(pos.into_proto());
+	if let Some(tag) = builder {
+		placement.set_placing_unit_tag(tag);
+	}
+	placement
+}
+pub(crate) fn abilities_request(tag: u64) -> RequestQueryAvailableAbilities {
+	let mut req_unit = RequestQueryAvailableAbilities::new();
+	req_unit.set_unit_tag(tag);
+	req_unit
+}
+
+pub(crate) fn pathing_result(result: &ResponseQueryPathing) -> PathingResult {
+	PathingResult {
+		distance: result.distance,
+	}
+}
+pub(crate) fn abilities_result(result: &ResponseQueryAvailableAbilities) -> (u64, FxHashSet<AbilityId>) {
+	(
+		result.get_unit_tag(),
+		result
+			.get_abilities()
+			.iter()
+			.filter_map(|ab| AbilityId::from_i32(ab.get_ability_id()))
+			.collect(),
+	)
+}
@@ -0,0 +1,128 @@
+//! Straight line checks on terrain grids.
+//!
+//! Cheap alternative to pathfinding and API queries, when it's enough to know
+//! if unit can walk or see in straight line:
+//! ```ignore
+//! if self.is_line_pathable(unit.position(), target) {
+//!     unit.move_to(Target::Pos(target), false);
+//! }
+//! ```
+
+use crate::{bot::Bot, geometry::Point2};
+
+/// Maximum difference in terrain height, which doesn't block line of sight.
+/// Small differences are smoothed to not block vision on ramps and uneven ground.
+const SIGHT_HEIGHT_TOLERANCE: u8 = 4;
+
+/// Returns tiles crossed by line from `from` to `to` in order from start to end.
+///
+/// Consecutive tiles are always adjacent by side, so line can't slip
+/// between two diagonal obstacles. Tiles can have negative coordinates if line goes out of the map.
+pub fn line_tiles(from: Point2, to: Point2) -> Vec<(isize, isize)> {
+	let (mut x, mut y) = (from.x.floor() as isize, from.y.floor() as isize);
+	let (end_x, end_y) = (to.x.floor() as isize, to.y.floor() as isize);
+	let dir = to - from;
+	let step_x = if dir.x > 0.0 { 1 } else { -1 };
+	let step_y = if dir.y > 0.0 { 1 } else { -1 };
+
+	// Part of the line passed when crossing one tile horizontally or vertically
+	let delta_x = 1.0 / dir.x.abs();
+	let delta_y = 1.0 / dir.y.abs();
+	// Part of the line passed before crossing the first vertical or horizontal tile border
+	let mut next_x = if dir.x > 0.0 {
+		(x as f32 + 1.0 - from.x) * delta_x
+	} else {
+		(from.x - x as f32) * delta_x
+	};
+	let mut next_y = if dir.y > 0.0 {
+		(y as f32 + 1.0 - from.y) * delta_y
+	} else {
+		(from.y - y as f32) * delta_y
+	};
+
+	let steps = (end_x - x).unsigned_abs() + (end_y - y).unsigned_abs();
+	let mut tiles = Vec::with_capacity(steps + 1);
+	tiles.push((x, y));
+	for _ in 0..steps {
+		if y == end_y || (x != end_x && next_x < next_y) {
+			x += step_x;
+			next_x += delta_x;
+		} else {
+			y += step_y;
+			next_y += delta_y;
+		}
+		tiles.push((x, y));
+	}
+	tiles
+}
+
+impl Bot {
+	/// Checks if ground units can walk in straight line from `from` to `to`,
+	/// i.e. all tiles crossed by the line are pathable.
+	pub fn is_line_pathable(&self, from: Point2, to: Point2) -> bool {
+		line_tiles(from, to)
+			.into_iter()
+			.all(|(x, y)| x >= 0 && y >= 0 && self.is_pathable((x as usize, y as usize)))
+	}
+	/// Checks if ground unit at `from` has line of sight to `to`,
+	/// i.e. the line doesn't cross terrain higher than `from`, since units can't see up cliffs.
+	///
+	/// Only terrain is considered, sight range and vision blockers (e.g. doodads) are not checked.
+	pub fn is_line_visible(&self, from: Point2, to: Point2) -> bool {
+		let max_height = self.get_height(from).saturating_add(SIGHT_HEIGHT_TOLERANCE);
+		let heights = &self.game_info.terrain_height;
+		line_tiles(from, to).into_iter().all(|(x, y)| {
+			let height = if x >= 0 && y >= 0 {
+				heights.get((x as usize, y as usize))
+			} else {
+				None
+			};
+			height.map_or(false, |h| *h <= max_height)
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::line_tiles;
+	use crate::geometry::Point2;
+
+	#[test]
+	fn straight_lines() {
+		let tiles = line_tiles(Point2::new(0.5, 0.5), Point2::new(3.5, 0.5));
+		assert_eq!(tiles, vec![(0, 0), (1, 0), (2, 0), (3, 0)]);
+
+		let tiles = line_tiles(Point2::new(2.5, 1.5), Point2::new(0.5, 1.5));
+		assert_eq!(tiles, vec![(2, 1), (1, 1), (0, 1)]);
+
+		let tiles = line_tiles(Point2::new(0.5, -0.5), Point2::new(0.5, 1.5));
+		assert_eq!(tiles, vec![(0, -1), (0, 0), (0, 1)]);
+	}
+
+	#[test]
+	fn same_tile() {
+		assert_eq!(
+			line_tiles(Point2::new(1.2, 1.7), Point2::new(1.8, 1.1)),
+			vec![(1, 1)]
+		);
+	}
+
+	#[test]
+	fn sloped_line() {
+		let tiles = line_tiles(Point2::new(0.5, 0.5), Point2::new(3.5, 1.2));
+		assert_eq!(tiles, vec![(0, 0), (1, 0), (2, 0), (2, 1), (3, 1)]);
+	}
+
+	#[test]
+	fn diagonal_tiles_are_adjacent_by_side() {
+		let (from, to) = (Point2::new(0.5, 0.5), Point2::new(4.5, 4.5));
+		let tiles = line_tiles(from, to);
+		assert_eq!(tiles.first(), Some(&(0, 0)));
+		assert_eq!(tiles.last(), Some(&(4, 4)));
+		assert_eq!(tiles.len(), 9);
+		for pair in tiles.windows(2) {
+			let (a, b) = (pair[0], pair[1]);
+			assert_eq!((a.0 - b.0).abs() + (a.1 - b.1).abs(), 1, "{:?} -> {:?}", a, b);
+		}
+	}
+}
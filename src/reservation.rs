@@ -0,0 +1,99 @@
+//! Reservation of resources and supply.
+//!
+//! Lets different parts of bot (build order, production, expansion)
+//! put aside resources for their plans, so they don't spend the same minerals twice.
+
+use crate::{bot::Bot, game_data::Cost};
+
+/// Resources and supply put aside with [`reserve`](Bot::reserve).
+#[derive(Debug, Clone)]
+pub struct Reservation {
+	/// Name of the plan resources are reserved for.
+	pub purpose: String,
+	/// Reserved minerals, vespene and supply (time is ignored).
+	pub cost: Cost,
+	/// In-game time in seconds after which reservation is released automatically.
+	pub expires: Option<f32>,
+}
+
+impl Bot {
+	/// Reserves resources and supply for given purpose until it's [released](Self::release).
+	///
+	/// Reserved amount is excluded from [`available_minerals`](Self::available_minerals),
+	/// [`available_vespene`](Self::available_vespene), [`available_supply`](Self::available_supply)
+	/// and `can_afford*` checks.
+	/// If something is already reserved for this purpose, it's replaced with new cost.
+	///
+	/// When it's time to spend resources, release reservation first,
+	/// so the plan itself can afford it.
+	pub fn reserve<P: Into<String>>(&mut self, cost: Cost, purpose: P) {
+		self.add_reservation(cost, purpose.into(), None);
+	}
+	/// Same as [`reserve`](Self::reserve), but reservation is released automatically
+	/// after given number of in-game seconds.
+	pub fn reserve_for<P: Into<String>>(&mut self, cost: Cost, purpose: P, duration: f32) {
+		let expires = self.time + duration;
+		self.add_reservation(cost, purpose.into(), Some(expires));
+	}
+	/// Releases reservation made for given purpose.
+	/// Returns reserved cost, or `None` if nothing was reserved.
+	pub fn release(&mut self, purpose: &str) -> Option<Cost> {
+		let index = self.reservations.iter().position(|r| r.purpose == purpose)?;
+		Some(self.reservations.remove(index).cost)
+	}
+	/// Releases all reservations.
+	pub fn release_all(&mut self) {
+		self.reservations.clear();
+	}
+	/// Returns all active reservations.
+	pub fn reservations(&self) -> &[Reservation] {
+		&self.reservations
+	}
+	/// Checks if something is reserved for given purpose.
+	pub fn is_reserved(&self, purpose: &str) -> bool {
+		self.reservations.iter().any(|r| r.purpose == purpose)
+	}
+	/// Returns total amount of reserved resources and supply.
+	pub fn reserved(&self) -> Cost {
+		let mut total = Cost::default();
+		for r in &self.reservations {
+			total.minerals += r.cost.minerals;
+			total.vespene += r.cost.vespene;
+			total.supply += r.cost.supply;
+		}
+		total
+	}
+	/// Amount of minerals not reserved by any purpose.
+	pub fn available_minerals(&self) -> u32 {
+		self.minerals.saturating_sub(self.reserved().minerals)
+	}
+	/// Amount of vespene not reserved by any purpose.
+	pub fn available_vespene(&self) -> u32 {
+		self.vespene.saturating_sub(self.reserved().vespene)
+	}
+	/// Amount of free supply not reserved by any purpose.
+	pub fn available_supply(&self) -> f32 {
+		(self.supply_left as f32 - self.reserved().supply).max(0.0)
+	}
+
+	fn add_reservation(&mut self, cost: Cost, purpose: String, expires: Option<f32>) {
+		let reservation = Reservation {
+			purpose,
+			cost,
+			expires,
+		};
+		match self
+			.reservations
+			.iter_mut()
+			.find(|r| r.purpose == reservation.purpose)
+		{
+			Some(r) => *r = reservation,
+			None => self.reservations.push(reservation),
+		}
+	}
+	pub(crate) fn expire_reservations(&mut self) {
+		let time = self.time;
+		self.reservations
+			.retain(|r| !r.expires.is_some_and(|expires| time >= expires));
+	}
+}
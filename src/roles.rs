@@ -0,0 +1,83 @@
+//! Registry of roles assigned to own units.
+//!
+//! Replaces sets of tags for different groups of units, which are usually kept in bot's struct.
+//! Dead units are removed from the registry automatically:
+//! ```ignore
+//! for u in &self.units.my.units.of_type(UnitTypeId::Reaper) {
+//!     if u.health_percentage().unwrap() < 0.5 {
+//!         self.assign_role(u.tag(), Role::Retreating);
+//!     }
+//! }
+//! for u in &self.units_with_role(Role::Retreating) {
+//!     u.move_to(Target::Pos(self.start_location), false);
+//! }
+//! ```
+
+use crate::{bot::Bot, units::Units, Event};
+
+/// Role of unit, one unit can have only one role at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Role {
+	/// Gathers resources.
+	Gatherer,
+	/// Constructs buildings.
+	Builder,
+	/// Scouts the map.
+	Scout,
+	/// Defends own bases.
+	Defender,
+	/// Attacks the enemy with main army.
+	Attacker,
+	/// Harasses the enemy separately from main army.
+	Harasser,
+	/// Retreats from fight.
+	Retreating,
+	/// Any other role, distinguished by name.
+	Custom(&'static str),
+}
+
+impl Bot {
+	/// Assigns role to own unit with given tag, replacing its previous role.
+	/// Returns previous role of the unit.
+	pub fn assign_role(&mut self, tag: u64, role: Role) -> Option<Role> {
+		self.roles.insert(tag, role)
+	}
+	/// Removes role from unit with given tag. Returns removed role.
+	pub fn unassign_role(&mut self, tag: u64) -> Option<Role> {
+		self.roles.remove(&tag)
+	}
+	/// Returns role of unit with given tag.
+	pub fn role(&self, tag: u64) -> Option<Role> {
+		self.roles.get(&tag).copied()
+	}
+	/// Checks if unit with given tag has given role.
+	pub fn has_role(&self, tag: u64, role: Role) -> bool {
+		self.role(tag) == Some(role)
+	}
+	/// Returns tags of units with given role.
+	pub fn tags_with_role(&self, role: Role) -> impl Iterator<Item = u64> + '_ {
+		self.roles
+			.iter()
+			.filter(move |(_, r)| **r == role)
+			.map(|(tag, _)| *tag)
+	}
+	/// Returns own units with given role.
+	pub fn units_with_role(&self, role: Role) -> Units {
+		self.units
+			.my
+			.all
+			.find_tags(&self.tags_with_role(role).collect::<Vec<u64>>())
+	}
+	/// Removes roles from all units.
+	pub fn clear_roles(&mut self) {
+		self.roles.clear();
+	}
+
+	pub(crate) fn update_roles(&mut self, events: &[Event]) {
+		for e in events {
+			if let Event::UnitDestroyed(tag, _) = e {
+				self.roles.remove(tag);
+			}
+		}
+	}
+}
@@ -0,0 +1,171 @@
+//! Running series of local games vs built-in AI with aggregate statistics.
+//!
+//! ```ignore
+//! let summary = rust_sc2::series::run_series(
+//!     MyBot::default,
+//!     Computer::new(Race::Random, Difficulty::VeryHard, None),
+//!     &["EternalEmpireLE", "EverDreamLE"],
+//!     10,
+//!     Default::default(),
+//! );
+//! println!("{}", summary);
+//! ```
+
+use crate::{
+	bot::Bot,
+	client::{run_vs_computer, LaunchOptions},
+	player::{Computer, GameResult, Race},
+	Player,
+};
+use std::{
+	fmt,
+	ops::{Deref, DerefMut},
+	panic::{self, AssertUnwindSafe},
+};
+
+/// Races cycled through when [`alternate_races`](SeriesOptions::alternate_races) is enabled.
+const RACES: [Race; 3] = [Race::Terran, Race::Zerg, Race::Protoss];
+
+/// Additional options for [`run_series`].
+#[derive(Default, Clone, Copy)]
+pub struct SeriesOptions<'a> {
+	/// Options used to launch each game.
+	pub launch: LaunchOptions<'a>,
+	/// Cycle opponent's race through Terran, Zerg and Protoss
+	/// instead of using race of given computer.
+	pub alternate_races: bool,
+}
+
+/// Outcome of one game in the series.
+#[derive(Debug, Clone)]
+pub struct SeriesGame {
+	/// Map the game was played on.
+	pub map: String,
+	/// Race of computer opponent.
+	pub enemy_race: Race,
+	/// Result of the game, `None` if game crashed.
+	pub result: Option<GameResult>,
+	/// Game duration in in-game seconds, `None` if game crashed.
+	pub duration: Option<f32>,
+	/// Error or panic message if game crashed.
+	pub error: Option<String>,
+}
+impl SeriesGame {
+	/// Checks if bot returned error or panicked during the game.
+	pub fn is_crash(&self) -> bool {
+		self.error.is_some()
+	}
+}
+
+/// Aggregate statistics of games played by [`run_series`].
+#[derive(Debug, Clone, Default)]
+pub struct SeriesSummary {
+	/// Outcomes of all played games in order.
+	pub games: Vec<SeriesGame>,
+}
+impl SeriesSummary {
+	/// Number of games with given result.
+	pub fn count(&self, result: GameResult) -> usize {
+		self.games.iter().filter(|g| g.result == Some(result)).count()
+	}
+	/// Number of games, where bot returned error or panicked.
+	pub fn crashes(&self) -> usize {
+		self.games.iter().filter(|g| g.is_crash()).count()
+	}
+	/// Part of all games won by bot, from `0.0` to `1.0`.
+	pub fn win_rate(&self) -> f32 {
+		if self.games.is_empty() {
+			return 0.0;
+		}
+		self.count(GameResult::Victory) as f32 / self.games.len() as f32
+	}
+	/// Average duration of finished games in in-game seconds.
+	pub fn average_duration(&self) -> Option<f32> {
+		let durations = self.games.iter().filter_map(|g| g.duration).collect::<Vec<_>>();
+		if durations.is_empty() {
+			return None;
+		}
+		Some(durations.iter().sum::<f32>() / durations.len() as f32)
+	}
+}
+impl fmt::Display for SeriesSummary {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		writeln!(
+			f,
+			"Games: {}, Victories: {}, Defeats: {}, Ties: {}, Crashes: {}",
+			self.games.len(),
+			self.count(GameResult::Victory),
+			self.count(GameResult::Defeat),
+			self.count(GameResult::Tie),
+			self.crashes(),
+		)?;
+		write!(f, "Win rate: {:.1}%", self.win_rate() * 100.0)?;
+		if let Some(duration) = self.average_duration() {
+			write!(f, ", Average duration: {:.0}s", duration)?;
+		}
+		Ok(())
+	}
+}
+
+/// Plays `n_games` games vs built-in AI, cycling through given maps.
+/// Fresh bot is created with `bot_factory` for every game.
+///
+/// Errors and panics of bot don't stop the series, they're counted as crashes.
+///
+/// # Panics
+/// Panics if `maps` is empty.
+pub fn run_series<B, F>(
+	mut bot_factory: F,
+	computer: Computer,
+	maps: &[&str],
+	n_games: usize,
+	options: SeriesOptions,
+) -> SeriesSummary
+where
+	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
+	F: FnMut() -> B,
+{
+	assert!(!maps.is_empty(), "Maps for series are not specified");
+
+	let mut summary = SeriesSummary::default();
+	for i in 0..n_games {
+		let map = maps[i % maps.len()];
+		let enemy_race = if options.alternate_races {
+			RACES[i % RACES.len()]
+		} else {
+			computer.race
+		};
+		debug!("Series game {}/{}: {} vs {:?}", i + 1, n_games, map, enemy_race);
+
+		let mut bot = bot_factory();
+		let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+			run_vs_computer(
+				&mut bot,
+				Computer::new(enemy_race, computer.difficulty, computer.ai_build),
+				map,
+				options.launch,
+			)
+			.map_err(|e| e.to_string())
+		}));
+		let error = match outcome {
+			Ok(Ok(())) => None,
+			Ok(Err(e)) => Some(e),
+			Err(payload) => Some(
+				payload
+					.downcast_ref::<&str>()
+					.map(|s| s.to_string())
+					.or_else(|| payload.downcast_ref::<String>().cloned())
+					.unwrap_or_else(|| "Bot panicked".to_string()),
+			),
+		};
+		let game = bot.game_summary();
+		summary.games.push(SeriesGame {
+			map: map.to_string(),
+			enemy_race,
+			result: game.filter(|_| error.is_none()).map(|g| g.result),
+			duration: game.filter(|_| error.is_none()).map(|g| g.duration()),
+			error,
+		});
+	}
+	summary
+}
@@ -0,0 +1,195 @@
+//! Automatic supply management.
+//!
+//! When enabled, bot predicts supply block and builds
+//! supply depots, overlords or pylons ahead of time.
+
+use crate::{
+	bot::{Bot, PlacementOptions},
+	consts::FRAMES_PER_SECOND,
+	distance::*,
+	geometry::Point2,
+	ids::UnitTypeId,
+	player::Race,
+	units::iter::UnitsIterator,
+};
+use rustc_hash::FxHashMap;
+
+// Average mineral income of one worker per second.
+const INCOME_PER_WORKER: f32 = 0.9;
+// Average cost of one supply in minerals.
+const MINERALS_PER_SUPPLY: f32 = 50.0;
+const MAX_SUPPLY: u32 = 200;
+// Units which provide supply.
+const SUPPLY_PROVIDERS: [UnitTypeId; 6] = [
+	UnitTypeId::SupplyDepot,
+	UnitTypeId::Overlord,
+	UnitTypeId::Pylon,
+	UnitTypeId::CommandCenter,
+	UnitTypeId::Hatchery,
+	UnitTypeId::Nexus,
+];
+
+/// Settings of automatic supply manager.
+/// Stored in [`supply_manager`](Bot::supply_manager) field of bot.
+///
+/// Manager is disabled by default, it can be enabled by setting
+/// `self.supply_manager.enabled = true` in [`on_start`](crate::Player::on_start).
+///
+/// When enabled, it runs every step before [`on_step`](crate::Player::on_step)
+/// and subtracts resources spent on supply, so they're not available in `on_step`.
+#[derive(Debug, Clone, Copy)]
+pub struct SupplyManager {
+	/// Build supply automatically. [Default: `false`]
+	pub enabled: bool,
+	/// Additional time in seconds to start supply building before it's needed,
+	/// used to cover time of worker travel. [Default: `4.0`]
+	pub margin: f32,
+	/// Maximum number of supply buildings in progress at the same time. [Default: `3`]
+	pub max_pending: usize,
+	/// Place first terran supply depots at main ramp wall
+	/// (see [`corner_depots`](crate::ramp::Ramp::corner_depots)). [Default: `true`]
+	pub wall_depots: bool,
+}
+impl Default for SupplyManager {
+	fn default() -> Self {
+		Self {
+			enabled: false,
+			margin: 4.0,
+			max_pending: 3,
+			wall_depots: true,
+		}
+	}
+}
+
+impl Bot {
+	/// Supply, which will be provided by supply structures, overlords
+	/// and townhalls currently in production (including eggs morphing into overlords).
+	pub fn supply_pending(&self) -> u32 {
+		SUPPLY_PROVIDERS
+			.iter()
+			.map(|id| {
+				let provided = self
+					.game_data
+					.units
+					.get(id)
+					.map_or(0, |data| data.food_provided as u32);
+				self.counter().ordered().exact().count(*id) as u32 * provided
+			})
+			.sum()
+	}
+	/// Supply left after all supply providers in production are finished
+	/// (see [`supply_pending`](Self::supply_pending)), supply cap is limited to 200.
+	pub fn supply_left_with_pending(&self) -> u32 {
+		(self.supply_cap + self.supply_pending())
+			.min(MAX_SUPPLY)
+			.saturating_sub(self.supply_used)
+	}
+	/// Estimated supply usage per second,
+	/// based on current production and mineral income.
+	pub fn supply_usage_rate(&self) -> f32 {
+		let income = self.supply_workers as f32 * INCOME_PER_WORKER / MINERALS_PER_SUPPLY;
+		if matches!(self.race, Race::Zerg) {
+			return income;
+		}
+
+		let trained = self
+			.game_data
+			.units
+			.values()
+			.filter(|data| data.food_required > 0.0 && data.build_time > 0.0)
+			.filter_map(|data| {
+				Some((
+					data.ability?,
+					data.food_required * FRAMES_PER_SECOND / data.build_time,
+				))
+			})
+			.collect::<FxHashMap<_, _>>();
+
+		let production = self
+			.units
+			.my
+			.structures
+			.iter()
+			.ready()
+			.map(|s| {
+				let slots = if s.has_reactor() { 2 } else { 1 };
+				s.orders()
+					.iter()
+					.take(slots)
+					.filter_map(|order| trained.get(&order.ability))
+					.sum::<f32>()
+			})
+			.sum::<f32>();
+
+		production.min(income)
+	}
+
+	pub(crate) fn manage_supply(&mut self) {
+		let manager = self.supply_manager;
+		if !manager.enabled {
+			return;
+		}
+
+		let supply = self.race_values.supply;
+		let build_time = match self.game_data.units.get(&supply) {
+			Some(data) => data.build_time / FRAMES_PER_SECOND,
+			None => return,
+		};
+
+		let pending = self.counter().ordered().exact().count(supply);
+		let future_cap = self.supply_cap + self.supply_pending();
+		if future_cap >= MAX_SUPPLY || pending >= manager.max_pending {
+			return;
+		}
+
+		let future_used = self.supply_used as f32 + self.supply_usage_rate() * (build_time + manager.margin);
+		if future_used < future_cap as f32 || !self.can_afford(supply, false) {
+			return;
+		}
+
+		if supply == UnitTypeId::Overlord {
+			if let Some(larva) = self.units.my.larvas.first() {
+				larva.train(supply, false);
+				self.subtract_resources(supply, false);
+			}
+			return;
+		}
+
+		let pos = match self.find_supply_placement(supply, manager.wall_depots) {
+			Some(pos) => pos,
+			None => return,
+		};
+		let builder = self
+			.units
+			.my
+			.workers
+			.iter()
+			.filter(|w| !w.is_constructing() && !w.is_returning() && (w.is_idle() || w.is_gathering()))
+			.closest(pos);
+		if let Some(worker) = builder {
+			worker.build(supply, pos, false);
+			self.subtract_resources(supply, false);
+		}
+	}
+	fn find_supply_placement(&self, supply: UnitTypeId, wall_depots: bool) -> Option<Point2> {
+		if wall_depots && supply == UnitTypeId::SupplyDepot {
+			if let Some(depots) = self.ramps.my.corner_depots() {
+				let results = self.can_place_some(depots.iter().map(|pos| (supply, *pos)).collect());
+				if let Some((pos, _)) = depots.iter().zip(results).find(|(_, ok)| *ok) {
+					return Some(*pos);
+				}
+			}
+		}
+
+		// Building on the opposite side of townhall from mineral line, so mining isn't blocked
+		let near = self.start_location.towards(self.start_center, -6.0);
+		self.find_placement(
+			supply,
+			near,
+			PlacementOptions {
+				avoid_expansions: true,
+				..Default::default()
+			},
+		)
+	}
+}
@@ -0,0 +1,87 @@
+//! Scheduled timers, which fire [`Event::Timer`] through [`on_event`](crate::Player::on_event).
+//!
+//! ```ignore
+//! const ATTACK: u64 = 0;
+//! const SCOUT: u64 = 1;
+//!
+//! fn on_start(&mut self) -> SC2Result<()> {
+//!     self.schedule_at(300.0, ATTACK);
+//!     self.schedule_every(60.0, SCOUT);
+//!     Ok(())
+//! }
+//! fn on_event(&mut self, event: Event) -> SC2Result<()> {
+//!     match event {
+//!         Event::Timer(ATTACK) => { /* Attack at 5:00 */ }
+//!         Event::Timer(SCOUT) => { /* Scout every minute */ }
+//!         _ => {}
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{bot::Bot, Event};
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Timer {
+	token: u64,
+	// In-game time in seconds
+	at: f32,
+	every: Option<f32>,
+}
+
+impl Bot {
+	/// Schedules [`Event::Timer`] with given token to fire once at given in-game time (in seconds).
+	/// If time has already passed, timer fires on the next step.
+	pub fn schedule_at(&mut self, time: f32, token: u64) {
+		self.timers.push(Timer {
+			token,
+			at: time,
+			every: None,
+		});
+	}
+	/// Schedules [`Event::Timer`] with given token to fire repeatedly with given interval (in seconds),
+	/// first time after one interval from now.
+	///
+	/// # Panics
+	/// Panics if interval isn't positive.
+	pub fn schedule_every(&mut self, interval: f32, token: u64) {
+		assert!(interval > 0.0, "Timer interval must be positive");
+		self.timers.push(Timer {
+			token,
+			at: self.time + interval,
+			every: Some(interval),
+		});
+	}
+	/// Cancels all timers with given token.
+	pub fn cancel_timer(&mut self, token: u64) {
+		self.timers.retain(|t| t.token != token);
+	}
+	/// Checks if there's a scheduled timer with given token.
+	pub fn is_scheduled(&self, token: u64) -> bool {
+		self.timers.iter().any(|t| t.token == token)
+	}
+
+	// Returns events of timers, which are due, in order of their times
+	pub(crate) fn fire_timers(&mut self) -> Vec<Event> {
+		let now = self.time;
+		let mut fired = Vec::new();
+		self.timers.retain_mut(|t| {
+			if t.at > now {
+				return true;
+			}
+			fired.push((t.at, t.token));
+			match t.every {
+				Some(interval) => {
+					// Timer fires once per step even if several intervals passed
+					while t.at <= now {
+						t.at += interval;
+					}
+					true
+				}
+				None => false,
+			}
+		});
+		fired.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+		fired.into_iter().map(|(_, token)| Event::Timer(token)).collect()
+	}
+}
@@ -0,0 +1,190 @@
+//! Drops with Medivacs, Warp Prisms and Overlords.
+//!
+//! [`Transport`] loads given units, flies to the target avoiding known anti-air,
+//! unloads there and optionally picks hurt units back up.
+//! It should be stored in the bot and updated every step:
+//! ```ignore
+//! if let Some(event) = self.drop.step(&self.bot) {
+//!     match event {
+//!         TransportEvent::DropCompleted => { /* control dropped units */ }
+//!         TransportEvent::Lost => { /* pick another transport */ }
+//!         TransportEvent::Loaded => {}
+//!     }
+//! }
+//! ```
+
+use crate::{action::Target, bot::Bot, distance::*, geometry::Point2, ids::AbilityId, unit::Unit};
+use rustc_hash::FxHashSet;
+
+/// Distance to target at which transport starts unloading.
+const UNLOAD_DISTANCE: f32 = 1.5;
+/// How far ahead route is checked for threats.
+const LOOKAHEAD: f32 = 4.0;
+/// Extra distance kept from the range of anti-air units.
+const SAFETY_GAP: f32 = 2.0;
+/// Deviations from the straight direction to target (in radians), tried when it's unsafe.
+const DETOURS: [f32; 6] = [0.5, -0.5, 1.0, -1.0, 1.5, -1.5];
+
+/// Stage of the drop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportState {
+	/// Cargo units are entering transport.
+	Loading,
+	/// Transport is flying to target.
+	Moving,
+	/// Transport is unloading at target.
+	Unloading,
+	/// All units were unloaded.
+	Dropped,
+	/// Transport died.
+	Lost,
+}
+
+/// Notable changes of the drop, returned by [`Transport::step`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportEvent {
+	/// All alive cargo units are inside transport, it starts moving to target.
+	Loaded,
+	/// All units were unloaded at target.
+	DropCompleted,
+	/// Transport died.
+	Lost,
+}
+
+/// Drop of given units with given transport to given position.
+#[derive(Debug, Clone)]
+pub struct Transport {
+	/// Tag of Medivac, Warp Prism or Overlord.
+	pub tag: u64,
+	/// Position where units should be unloaded.
+	pub target: Point2,
+	/// When set, dropped units with hits percentage below this value
+	/// are picked back up by transport. [Default: `None`]
+	pub pickup_hurt: Option<f32>,
+	cargo: FxHashSet<u64>,
+	state: TransportState,
+}
+impl Transport {
+	/// Creates drop of units with given tags by given transport to given target.
+	pub fn new(tag: u64, cargo: &[u64], target: Point2) -> Self {
+		Self {
+			tag,
+			target,
+			pickup_hurt: None,
+			cargo: cargo.iter().copied().collect(),
+			state: TransportState::Loading,
+		}
+	}
+	/// Sets hits percentage, below which dropped units are picked back up.
+	pub fn with_pickup_hurt(mut self, hits_percentage: f32) -> Self {
+		self.pickup_hurt = Some(hits_percentage);
+		self
+	}
+	/// Current stage of the drop.
+	pub fn state(&self) -> TransportState {
+		self.state
+	}
+	/// Tags of alive units, which take part in the drop.
+	pub fn cargo(&self) -> &FxHashSet<u64> {
+		&self.cargo
+	}
+
+	/// Gives orders to transport and cargo units for current step.
+	///
+	/// Returns event if the drop moved to the next stage.
+	pub fn step(&mut self, bot: &Bot) -> Option<TransportEvent> {
+		if self.state == TransportState::Lost {
+			return None;
+		}
+		let transport = match bot.units.my.units.get(self.tag) {
+			Some(u) => u,
+			None => {
+				self.state = TransportState::Lost;
+				return Some(TransportEvent::Lost);
+			}
+		};
+		let passengers = transport
+			.passengers()
+			.iter()
+			.map(|p| p.tag)
+			.collect::<FxHashSet<_>>();
+		let units = &bot.units.my.units;
+		self.cargo
+			.retain(|tag| passengers.contains(tag) || units.contains_tag(*tag));
+
+		match self.state {
+			TransportState::Loading => {
+				if self.cargo.iter().all(|tag| passengers.contains(tag)) {
+					self.state = TransportState::Moving;
+					self.move_to_target(bot, transport);
+					return Some(TransportEvent::Loaded);
+				}
+				for u in units.find_tags(&self.cargo).iter() {
+					if u.target_tag() != Some(self.tag) {
+						u.smart(Target::Tag(self.tag), false);
+					}
+				}
+			}
+			TransportState::Moving => {
+				if transport.is_closer(UNLOAD_DISTANCE, self.target) {
+					self.state = TransportState::Unloading;
+					transport.command(AbilityId::UnloadAllAt, Target::Pos(transport.position()), false);
+				} else {
+					self.move_to_target(bot, transport);
+				}
+			}
+			TransportState::Unloading => {
+				if passengers.is_empty() {
+					self.state = TransportState::Dropped;
+					return Some(TransportEvent::DropCompleted);
+				}
+				if transport.is_idle() {
+					transport.command(AbilityId::UnloadAllAt, Target::Pos(transport.position()), false);
+				}
+			}
+			TransportState::Dropped => {
+				if let Some(threshold) = self.pickup_hurt {
+					for u in units.find_tags(&self.cargo).iter() {
+						if u.hits_percentage().is_some_and(|hits| hits < threshold)
+							&& u.target_tag() != Some(self.tag)
+						{
+							u.smart(Target::Tag(self.tag), false);
+						}
+					}
+				}
+			}
+			TransportState::Lost => {}
+		}
+		None
+	}
+
+	fn move_to_target(&self, bot: &Bot, transport: &Unit) {
+		let pos = transport.position();
+		let threats = bot
+			.units
+			.enemy
+			.all
+			.iter()
+			.filter(|u| u.can_attack_air())
+			.collect::<Vec<_>>();
+		let is_safe = |p: Point2| {
+			threats.iter().all(|t| {
+				t.position()
+					.is_further(t.real_air_range() + t.radius() + SAFETY_GAP, p)
+			})
+		};
+
+		let ahead = pos.towards(self.target, LOOKAHEAD.min(pos.distance(self.target)));
+		if is_safe(ahead) {
+			transport.move_to(Target::Pos(self.target), false);
+			return;
+		}
+		let direction = ahead - pos;
+		let next = DETOURS
+			.iter()
+			.map(|angle| pos + direction.rotate(*angle))
+			.find(|p| is_safe(*p))
+			.unwrap_or(self.target);
+		transport.move_to(Target::Pos(next), false);
+	}
+}
@@ -2,11 +2,12 @@
 #![allow(missing_docs)]
 
 use crate::{
-	action::{Commander, Target},
-	bot::{LockBool, LockOwned, LockU32, Locked, Reader, Rl, Rs, Rw},
+	action::{Commander, InvalidTarget, Target},
+	bot::{Bot, LockBool, LockOwned, LockU32, Locked, Reader, Rl, Rs, Rw},
 	consts::{
-		RaceValues, ANTI_ARMOR_BUFF, DAMAGE_BONUS_PER_UPGRADE, FRAMES_PER_SECOND, MISSED_WEAPONS,
-		OFF_CREEP_SPEED_UPGRADES, SPEED_BUFFS, SPEED_ON_CREEP, SPEED_UPGRADES, WARPGATE_ABILITIES,
+		RaceValues, ANTI_ARMOR_BUFF, BUFF_DURATIONS, DAMAGE_BONUS_PER_UPGRADE, DETECTION_RANGES,
+		FRAMES_PER_SECOND, MISSED_WEAPONS, OFF_CREEP_SPEED_UPGRADES, SIGHT_UPGRADES, SPEED_BUFFS,
+		SPEED_ON_CREEP, SPEED_UPGRADES, WARPGATE_ABILITIES,
 	},
 	distance::Distance,
 	game_data::{Attribute, Cost, GameData, TargetType, UnitTypeData, Weapon},
@@ -27,6 +28,7 @@ use sc2_proto::raw::{
 	CloakState as ProtoCloakState, DisplayType as ProtoDisplayType, Unit as ProtoUnit,
 	UnitOrder_oneof_target as ProtoTarget,
 };
+use std::{error::Error, fmt};
 
 #[derive(Default, Clone)]
 pub(crate) struct DataForUnit {
@@ -37,6 +39,7 @@ pub(crate) struct DataForUnit {
 	pub race_values: Rs<RaceValues>,
 	pub max_cooldowns: Rw<FxHashMap<UnitTypeId, f32>>,
 	pub last_units_health: Rw<FxHashMap<u64, u32>>,
+	pub buffs_appeared: Rw<FxHashMap<(u64, BuffId), u32>>,
 	pub abilities_units: Rw<FxHashMap<u64, FxHashSet<AbilityId>>>,
 	pub upgrades: Rw<FxHashSet<UpgradeId>>,
 	pub enemy_upgrades: Rw<FxHashSet<UpgradeId>>,
@@ -93,6 +96,8 @@ pub(crate) struct UnitBase {
 	pub buff_duration_remain: Option<u32>,
 	pub buff_duration_max: Option<u32>,
 	pub rally_targets: Vec<RallyTarget>,
+	// Loop of observation, from which unit was created
+	pub game_loop: u32,
 
 	// cache
 	real_speed: LazyInit<f32>,
@@ -111,6 +116,9 @@ pub(crate) type SharedUnitData = Rs<DataForUnit>;
 
 /// Unit structure contains some raw data, helper methods for it's analysis
 /// and some methods for actions execution.
+///
+/// Unit is a handle to data shared with all collections containing it,
+/// so cloning it only increments reference counters.
 #[derive(Clone)]
 pub struct Unit {
 	data: SharedUnitData,
@@ -143,6 +151,14 @@ impl Unit {
 	pub fn tag(&self) -> u64 {
 		self.base.tag
 	}
+	/// Returns handle to this unit, which is valid only during current game step.
+	/// Store handles instead of tags to catch usage of units cached on previous steps.
+	pub fn handle(&self) -> UnitHandle {
+		UnitHandle {
+			tag: self.tag(),
+			game_loop: self.base.game_loop,
+		}
+	}
 	/// The type of unit.
 	#[inline]
 	pub fn type_id(&self) -> UnitTypeId {
@@ -200,6 +216,9 @@ impl Unit {
 	}
 	/// Detection range of detector or `0` if unit is not detector.
 	/// See also [`is_detector`](Self::is_detector).
+	///
+	/// SC2 reports it only for own units, use [`detection_range`](Self::detection_range)
+	/// for enemy ones.
 	#[inline]
 	pub fn detect_range(&self) -> f32 {
 		self.base.detect_range
@@ -470,6 +489,17 @@ impl Unit {
 				UnitTypeId::MissileTurret | UnitTypeId::SporeCrawler
 			) || (matches!(self.type_id(), UnitTypeId::PhotonCannon) && self.is_powered())))
 	}
+	/// Detection range of detector or `0` if unit is not detector.
+	///
+	/// Unlike [`detect_range`](Self::detect_range) works for enemy units too,
+	/// in this case range is taken from [`DETECTION_RANGES`].
+	pub fn detection_range(&self) -> f32 {
+		let range = self.detect_range();
+		if range > 0.0 || !self.is_detector() {
+			return range;
+		}
+		DETECTION_RANGES.get(&self.type_id()).copied().unwrap_or(0.0)
+	}
 	/// Building construction is complete.
 	pub fn is_ready(&self) -> bool {
 		(self.build_progress() - 1.0).abs() < f32::EPSILON
@@ -567,10 +597,20 @@ impl Unit {
 	pub fn cargo_size(&self) -> u32 {
 		self.type_data().map_or(0, |data| data.cargo_size)
 	}
-	/// How far unit can see.
+	/// How far unit can see without considering upgrades.
+	///
+	/// Use [`real_sight_range`](Self::real_sight_range) to get sight range including upgrades.
 	pub fn sight_range(&self) -> f32 {
 		self.type_data().map_or(0.0, |data| data.sight_range)
 	}
+	/// Returns actual sight range of the unit calculated including upgrades.
+	pub fn real_sight_range(&self) -> f32 {
+		let sight = self.sight_range();
+		match SIGHT_UPGRADES.get(&self.type_id()) {
+			Some((upgrade_id, increase)) if self.upgrades().contains(upgrade_id) => sight + increase,
+			_ => sight,
+		}
+	}
 	/// Initial armor of unit without considering upgrades and buffs.
 	pub fn armor(&self) -> i32 {
 		self.type_data().map_or(0, |data| data.armor)
@@ -816,6 +856,26 @@ impl Unit {
 	pub fn has_buff(&self, buff: BuffId) -> bool {
 		self.buffs().contains(&buff)
 	}
+	/// Returns game loop when given buff appeared on unit, or `None` if unit doesn't have it.
+	///
+	/// Buffs which were already on unit when it became visible are counted from that moment.
+	pub fn buff_appeared(&self, buff: BuffId) -> Option<u32> {
+		self.data
+			.buffs_appeared
+			.read_lock()
+			.get(&(self.tag(), buff))
+			.copied()
+	}
+	/// Estimated time (in seconds) left until given buff expires.
+	///
+	/// Returns `None` if unit doesn't have the buff or its duration isn't in
+	/// [`BUFF_DURATIONS`](crate::consts::BUFF_DURATIONS).
+	pub fn buff_remaining(&self, buff: BuffId) -> Option<f32> {
+		let duration = BUFF_DURATIONS.get(&buff)?;
+		let appeared = self.buff_appeared(buff)?;
+		let elapsed = self.data.game_loop.get_locked().saturating_sub(appeared) as f32 / FRAMES_PER_SECOND;
+		Some((duration - elapsed).max(0.0))
+	}
 	/// Checks if unit has any from given buffs.
 	pub fn has_any_buff<'a, B: IntoIterator<Item = &'a BuffId>>(&self, buffs: B) -> bool {
 		buffs.into_iter().any(|b| self.buffs().contains(b))
@@ -844,6 +904,8 @@ impl Unit {
 		self.is_carrying_minerals() || self.is_carrying_vespene()
 	}
 
+	/// Weapons of unit from game data, with fixes for units which have no weapons in API
+	/// (e.g. Baneling, Oracle), see [`Weapon`] for details.
 	#[inline]
 	pub fn weapons(&self) -> &[Weapon] {
 		match self.type_id() {
@@ -1538,6 +1600,36 @@ impl Unit {
 		}
 		false
 	}
+	/// Checks if unit can use given ability now: ability is available in current game version,
+	/// unit has enough energy for it and it's not on cooldown.
+	///
+	/// Cooldown is checked only if unit's abilities were queried
+	/// (see [`abilities_scope`](crate::bot::Bot::abilities_scope)).
+	pub fn can_cast(&self, ability: AbilityId) -> bool {
+		let data = match self.data.game_data.abilities.get(&ability) {
+			Some(data) => data,
+			None => return false,
+		};
+		if !data.available {
+			return false;
+		}
+		let energy = data.energy_cost();
+		if energy > 0 && self.energy().map_or(true, |e| e < energy) {
+			return false;
+		}
+		match self.data.abilities_units.read_lock().get(&self.tag()) {
+			Some(abilities) => abilities.contains(&ability),
+			None => true,
+		}
+	}
+	/// Checks if unit can use given ability now (see [`can_cast`](Self::can_cast))
+	/// and target is in cast range of the ability.
+	pub fn can_cast_on<A>(&self, ability: AbilityId, target: A, gap: f32) -> bool
+	where
+		A: Into<Point2> + Radius,
+	{
+		self.can_cast(ability) && self.in_ability_cast_range(ability, target, gap)
+	}
 	/// Returns (attribute, bonus damage) for first unit's weapon if any.
 	pub fn damage_bonus(&self) -> Option<(Attribute, u32)> {
 		self.weapons()
@@ -1573,6 +1665,14 @@ impl Unit {
 	pub fn ordered_ability(&self) -> Option<AbilityId> {
 		self.orders().first().map(|order| order.ability)
 	}
+	/// Returns orders queued after the current one.
+	pub fn queued_orders(&self) -> &[UnitOrder] {
+		self.orders().get(1..).unwrap_or_default()
+	}
+	/// Returns progress of the current unit order, or `None` if it's idle.
+	pub fn order_progress(&self) -> Option<f32> {
+		self.orders().first().map(|order| order.progress)
+	}
 	/// Checks if unit don't have any orders currently.
 	pub fn is_idle(&self) -> bool {
 		self.orders().is_empty()
@@ -1605,6 +1705,21 @@ impl Unit {
 	pub fn is_using(&self, ability: AbilityId) -> bool {
 		self.ordered_ability() == Some(ability)
 	}
+	/// Checks if unit is using given ability or any specific ability, which is remapped to it
+	/// (e.g. `AttackAttack` for `Attack`, or `HarvestGatherSCV` for `HarvestGather`).
+	///
+	/// Doesn't work with enemies.
+	pub fn is_using_ability(&self, ability: AbilityId) -> bool {
+		self.ordered_ability().map_or(false, |a| {
+			self.general_ability(a) == self.general_ability(ability)
+		})
+	}
+	/// Checks if unit is attacking unit with given tag.
+	///
+	/// Doesn't work with enemies.
+	pub fn is_attacking_tag(&self, tag: u64) -> bool {
+		self.is_attacking() && self.target_tag() == Some(tag)
+	}
 	/// Checks if unit is using any of given abilities.
 	///
 	/// Doesn't work with enemies.
@@ -1777,11 +1892,42 @@ impl Unit {
 			.push(self.tag());
 	}
 	/// Orders unit to execute given command.
+	///
+	/// Command is dropped if [order filter](crate::bot::Bot::set_order_filter) is enabled
+	/// and unit is already executing the same order, or if unit got command with the same ability
+	/// recently and [throttling](crate::bot::Bot::set_command_throttle) is enabled.
+	///
+	/// Commands with target, which ability doesn't accept, are dropped with a warning,
+	/// use [`try_command`](Self::try_command) to handle such errors.
 	pub fn command(&self, ability: AbilityId, target: Target, queue: bool) {
+		if let Err(e) = self.validate_target(ability, target) {
+			warn!("Command of unit {} dropped: {}", self.tag(), e);
+			return;
+		}
+
 		let mut lock = self.data
 			.commander
 			.write_lock();
 
+		if let Some(tolerance) = lock.order_filter {
+			if !queue && self.is_repeated_order(ability, target, tolerance) {
+				return;
+			}
+		}
+		if lock.throttle > 0 && !queue {
+			let game_loop = self.data.game_loop.get_locked();
+			let throttle = lock.throttle;
+			let key = (self.tag(), ability);
+			if lock
+				.last_commands
+				.get(&key)
+				.is_some_and(|issued| game_loop < issued + throttle)
+			{
+				return;
+			}
+			lock.last_commands.insert(key, game_loop);
+		}
+
 		for (a, t, q, u) in &mut lock.commands {
 			// if the same command is already issued for another unit, add ourselves to it
 			if *a == ability && *t == target && *q == queue {
@@ -1793,6 +1939,47 @@ impl Unit {
 		// otherwise add a new command
 		lock.commands.push((ability, target, queue, vec![self.tag()]));
 	}
+	/// Orders unit to execute given command like [`command`](Self::command),
+	/// but returns error if ability doesn't accept given kind of target.
+	pub fn try_command(&self, ability: AbilityId, target: Target, queue: bool) -> Result<(), InvalidTarget> {
+		self.validate_target(ability, target)?;
+		self.command(ability, target, queue);
+		Ok(())
+	}
+	// Abilities without data aren't validated
+	fn validate_target(&self, ability: AbilityId, target: Target) -> Result<(), InvalidTarget> {
+		match self.data.game_data.abilities.get(&ability) {
+			Some(data) if !data.target.accepts(target) => Err(InvalidTarget {
+				ability,
+				target,
+				expected: data.target,
+			}),
+			_ => Ok(()),
+		}
+	}
+	// Returns general ability, which given specific ability is remapped to
+	fn general_ability(&self, ability: AbilityId) -> AbilityId {
+		self.data
+			.game_data
+			.abilities
+			.get(&ability)
+			.and_then(|data| data.remaps_to_ability_id)
+			.unwrap_or(ability)
+	}
+	// Checks if current order of unit is the same as given one
+	fn is_repeated_order(&self, ability: AbilityId, target: Target, tolerance: f32) -> bool {
+		let order = match self.orders().first() {
+			Some(order) => order,
+			None => return false,
+		};
+		if self.general_ability(order.ability) != self.general_ability(ability) {
+			return false;
+		}
+		match (order.target, target) {
+			(Target::Pos(current), Target::Pos(new)) => current.is_closer(tolerance, new),
+			(current, new) => current == new,
+		}
+	}
 	/// Orders unit to use given ability (This is equivalent of `unit.command(ability, Target::None, queue)`).
 	pub fn use_ability(&self, ability: AbilityId, queue: bool) {
 		self.command(ability, Target::None, queue)
@@ -1805,6 +1992,87 @@ impl Unit {
 	pub fn attack(&self, target: Target, queue: bool) {
 		self.command(AbilityId::Attack, target, queue)
 	}
+	/// Attacks given target, unless units already ordered to [`focus_attack`](Self::focus_attack)
+	/// it on current step are going to deal enough damage to kill it.
+	///
+	/// Returns `true` if attack was ordered, so surplus attackers can be redirected to other targets.
+	pub fn focus_attack(&self, target: &Unit) -> bool {
+		let hits = match target.hits() {
+			Some(hits) => hits as f32,
+			None => {
+				self.attack(Target::Tag(target.tag()), false);
+				return true;
+			}
+		};
+		let volley = self.volley_damage_vs(target);
+
+		let mut commander = self.data.commander.write_lock();
+		let projected = commander.damage.entry(target.tag()).or_default();
+		if *projected >= hits {
+			return false;
+		}
+		*projected += volley;
+		drop(commander);
+
+		self.attack(Target::Tag(target.tag()), false);
+		true
+	}
+	/// Returns damage, which units ordered to [`focus_attack`](Self::focus_attack) this unit
+	/// on current step are going to deal with one volley.
+	pub fn projected_damage(&self) -> f32 {
+		self.data
+			.commander
+			.read_lock()
+			.damage
+			.get(&self.tag())
+			.copied()
+			.unwrap_or(0.0)
+	}
+	// Damage of one attack vs given target, including upgrades and armor
+	fn volley_damage_vs(&self, target: &Unit) -> f32 {
+		let not_target = if target.is_flying() {
+			TargetType::Ground
+		} else {
+			TargetType::Air
+		};
+		let speed = self
+			.weapons()
+			.iter()
+			.find(|w| target.type_id() == UnitTypeId::Colossus || w.target != not_target)
+			.map_or(0.0, |w| w.speed);
+		self.real_weapon_vs(target).0 * speed
+	}
+	/// Attacks given target when weapon is ready and steps back from it while weapon
+	/// is on cooldown, or steps closer to it, if target is out of range.
+	///
+	/// Doesn't repeat commands unit is already executing, so it's safe to call it every step.
+	pub fn stutter_attack(&self, target: &Unit) {
+		const STEP: f32 = 2.0;
+
+		if !self.on_cooldown() {
+			if !self.is_attacking_tag(target.tag()) {
+				self.attack(Target::Tag(target.tag()), false);
+			}
+			return;
+		}
+
+		let pos = self.position();
+		if pos.is_closer(f32::EPSILON, target) {
+			return;
+		}
+		let destination = if self.in_real_range(target, 0.0) {
+			pos.towards(target.position(), -STEP)
+		} else {
+			pos.towards(target.position(), STEP)
+		};
+		let already_moving = self.is_moving()
+			&& self
+				.target_pos()
+				.is_some_and(|p| p.is_closer(STEP / 2.0, destination));
+		if !already_moving {
+			self.move_to(Target::Pos(destination), false);
+		}
+	}
 	/// Orders unit to move to given target.
 	pub fn move_to(&self, target: Target, queue: bool) {
 		self.command(AbilityId::MoveMove, target, queue)
@@ -1939,6 +2207,7 @@ impl Unit {
 			let id = u.get_unit_type();
 			UnitTypeId::from_u32(id).unwrap_or_else(|| panic!("There's no `UnitTypeId` with value {}", id))
 		};
+		let game_loop = data.game_loop.get_locked();
 		let is_burrowed = u.get_is_burrowed();
 		let (is_cloaked, is_revealed) = if is_burrowed {
 			(true, false)
@@ -2064,6 +2333,7 @@ impl Unit {
 						tag: t.tag,
 					})
 					.collect(),
+				game_loop,
 
 				// cache
 				real_speed: Default::default(),
@@ -2073,6 +2343,79 @@ impl Unit {
 	}
 }
 
+/// Tag of unit bound to game step it was captured on.
+/// Can be obtained with [`handle`](Unit::handle) method.
+///
+/// Unit found by tag from previous step may be dead or changed already,
+/// so handle returns `None` (or error) when used on later step.
+/// Use [`refresh`](Self::refresh) to get handle for current step if unit is still alive.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct UnitHandle {
+	tag: u64,
+	game_loop: u32,
+}
+impl UnitHandle {
+	/// Tag of the unit.
+	pub fn tag(&self) -> u64 {
+		self.tag
+	}
+	/// Game loop on which handle was captured.
+	pub fn game_loop(&self) -> u32 {
+		self.game_loop
+	}
+	/// Checks if handle was captured on current game step.
+	pub fn is_valid(&self, bot: &Bot) -> bool {
+		self.game_loop == bot.state.observation.game_loop()
+	}
+	/// Returns unit if handle was captured on current game step and unit still exists.
+	pub fn get<'a>(&self, bot: &'a Bot) -> Option<&'a Unit> {
+		self.try_get(bot).ok()
+	}
+	/// Same as [`get`](Self::get), but returns error describing why unit can't be used.
+	pub fn try_get<'a>(&self, bot: &'a Bot) -> Result<&'a Unit, StaleHandle> {
+		let current_loop = bot.state.observation.game_loop();
+		let error = StaleHandle {
+			tag: self.tag,
+			game_loop: self.game_loop,
+			current_loop,
+		};
+		if self.game_loop != current_loop {
+			return Err(error);
+		}
+		bot.units.all.get(self.tag).ok_or(error)
+	}
+	/// Returns handle for current game step if unit with the same tag still exists.
+	pub fn refresh(&self, bot: &Bot) -> Option<UnitHandle> {
+		bot.units.all.get(self.tag).map(|u| u.handle())
+	}
+}
+
+/// Error returned by [`UnitHandle::try_get`]
+/// when handle was captured on previous step or unit doesn't exist anymore.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StaleHandle {
+	/// Tag of the unit.
+	pub tag: u64,
+	/// Game loop on which handle was captured.
+	pub game_loop: u32,
+	/// Current game loop.
+	pub current_loop: u32,
+}
+impl fmt::Display for StaleHandle {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.game_loop == self.current_loop {
+			write!(f, "Unit {} doesn't exist", self.tag)
+		} else {
+			write!(
+				f,
+				"Handle of unit {} captured on loop {} is used on loop {}",
+				self.tag, self.game_loop, self.current_loop
+			)
+		}
+	}
+}
+impl Error for StaleHandle {}
+
 /// The display type of [`Unit`].
 /// Can be accessed through [`display_type`](Unit::display_type) field.
 #[variant_checkers]
@@ -2100,7 +2443,7 @@ impl FromProto<ProtoDisplayType> for DisplayType {
 }
 
 /// Order given to unit. All current orders of unit stored in [`orders`](Unit::orders) field.
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct UnitOrder {
 	/// Ability unit is using.
 	pub ability: AbilityId,
@@ -2109,6 +2452,22 @@ pub struct UnitOrder {
 	/// Progress of train abilities. Value in range from `0` to `1`.
 	pub progress: f32,
 }
+impl UnitOrder {
+	/// Returns target point of the order if any.
+	pub fn target_pos(&self) -> Option<Point2> {
+		match self.target {
+			Target::Pos(pos) => Some(pos),
+			_ => None,
+		}
+	}
+	/// Returns target tag of the order if any.
+	pub fn target_tag(&self) -> Option<u64> {
+		match self.target {
+			Target::Tag(tag) => Some(tag),
+			_ => None,
+		}
+	}
+}
 
 /// Unit inside transport or bunker. All passengers stored in [`passengers`](Unit::passengers) field.
 #[derive(Clone)]
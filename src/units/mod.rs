@@ -1,13 +1,20 @@
 //! Data structures for storing units, fast filtering and finding ones that needed.
 #![warn(missing_docs)]
 
-use crate::{geometry::Point2, ids::UnitTypeId, unit::Unit};
+use crate::{
+	army::Composition,
+	bot::Rs,
+	geometry::Point2,
+	ids::UnitTypeId,
+	unit::Unit,
+	utils::{dbscan, hungarian, range_query},
+};
 use indexmap::{
 	map::{Iter, IterMut, Keys, Values, ValuesMut},
 	IndexMap, IndexSet,
 };
 use iter::IntoUnits;
-use rustc_hash::FxHasher;
+use rustc_hash::{FxHashSet, FxHasher};
 use std::{
 	hash::BuildHasherDefault,
 	iter::FromIterator,
@@ -22,6 +29,9 @@ type FxIndexMap<K, V> = IndexMap<K, V, BuildHasherDefault<FxHasher>>;
 
 /// Structured collection of all possible units. Can be accessed through [`units`] bot's field.
 ///
+/// Collections are updated in place every step: units keep their positions in collections
+/// and new units are added to the end, so iteration order is stable between steps.
+///
 /// [`units`]: crate::bot::Bot::units
 #[derive(Default, Clone)]
 pub struct AllUnits {
@@ -48,16 +58,16 @@ pub struct AllUnits {
 	pub inhibitor_zones: Units,
 }
 impl AllUnits {
-	pub(crate) fn clear(&mut self) {
-		self.all.clear();
-		self.my.clear();
-		self.enemy.clear();
-		self.mineral_fields.clear();
-		self.vespene_geysers.clear();
-		self.resources.clear();
-		self.destructables.clear();
-		self.watchtowers.clear();
-		self.inhibitor_zones.clear();
+	// Removes units, which weren't updated on current step (dead, out of vision or changed category).
+	pub(crate) fn retain_current(&mut self, current: &Units) {
+		self.my.retain_current(current);
+		self.enemy.retain_current(current);
+		self.mineral_fields.retain_current(current);
+		self.vespene_geysers.retain_current(current);
+		self.resources.retain_current(current);
+		self.destructables.retain_current(current);
+		self.watchtowers.retain_current(current);
+		self.inhibitor_zones.retain_current(current);
 	}
 }
 
@@ -82,23 +92,35 @@ pub struct PlayerUnits {
 	pub placeholders: Units,
 }
 impl PlayerUnits {
-	pub(crate) fn clear(&mut self) {
-		self.all.clear();
-		self.units.clear();
-		self.structures.clear();
-		self.townhalls.clear();
-		self.workers.clear();
-		self.gas_buildings.clear();
-		self.larvas.clear();
-		self.placeholders.clear();
+	pub(crate) fn retain_current(&mut self, current: &Units) {
+		self.all.retain_current(current);
+		self.units.retain_current(current);
+		self.structures.retain_current(current);
+		self.townhalls.retain_current(current);
+		self.workers.retain_current(current);
+		self.gas_buildings.retain_current(current);
+		self.larvas.retain_current(current);
+		self.placeholders.retain_current(current);
 	}
 }
 
 /// Convinient [`Unit`]s collection.
 ///
+/// Units are cheap handles to shared data, so cloning collections doesn't copy units themselves.
+///
 // [`Unit`]: crate::unit::Unit
-#[derive(Default, Clone)]
+#[derive(Default)]
 pub struct Units(FxIndexMap<u64, Unit>);
+impl Clone for Units {
+	#[inline]
+	fn clone(&self) -> Self {
+		Self(self.0.clone())
+	}
+	#[inline]
+	fn clone_from(&mut self, source: &Self) {
+		self.0.clone_from(&source.0);
+	}
+}
 impl Units {
 	// HashMap methods
 
@@ -214,6 +236,13 @@ impl Units {
 		self.0.clear()
 	}
 
+	// Keeps only units which are the same objects as in `current` collection,
+	// i.e. were pushed to this collection on current step. Order of remaining units is preserved.
+	pub(crate) fn retain_current(&mut self, current: &Units) {
+		self.0
+			.retain(|tag, u| current.get(*tag).is_some_and(|c| Rs::ptr_eq(&c.base, &u.base)));
+	}
+
 	/// Checks if the collection contains unit with given tag.
 	#[inline]
 	pub fn contains_tag(&self, tag: u64) -> bool {
@@ -274,6 +303,128 @@ impl Units {
 			Some(self.sum(|u| u.position()) / self.len() as f32)
 		}
 	}
+	/// Returns center of all units weighted by their size (area of unit's circle),
+	/// so big units pull the center towards them more than small ones.
+	/// Returns `None` if collection is empty.
+	pub fn center_of_mass(&self) -> Option<Point2> {
+		let (sum, mass) = self.iter().fold((Point2::default(), 0.0), |(sum, mass), u| {
+			let m = u.radius() * u.radius();
+			(sum + u.position() * m, mass + m)
+		});
+		if mass > 0.0 {
+			Some(sum / mass)
+		} else {
+			self.center()
+		}
+	}
+	/// Returns average distance from units to their [`center`](Self::center)
+	/// or `None` if collection is empty. Small spread means units are grouped up.
+	pub fn spread(&self) -> Option<f32> {
+		let center = self.center()?;
+		Some(self.iter().map(|u| (u.position() - center).len()).sum::<f32>() / self.len() as f32)
+	}
+	/// Returns bottom-left and top-right corners of the smallest axis-aligned rectangle,
+	/// containing positions of all units, or `None` if collection is empty.
+	pub fn bounding_box(&self) -> Option<(Point2, Point2)> {
+		let first = self.first()?.position();
+		Some(self.iter().skip(1).fold((first, first), |(min, max), u| {
+			let pos = u.position();
+			(
+				Point2::new(min.x.min(pos.x), min.y.min(pos.y)),
+				Point2::new(max.x.max(pos.x), max.y.max(pos.y)),
+			)
+		}))
+	}
+	/// Splits units into groups with [`dbscan`].
+	///
+	/// Units are neighbors if distance between them is not greater than `epsilon`.
+	/// Unit starts a group if it has at least `min_points` neighbors (including itself),
+	/// units without enough neighbors and not reachable from any group are left out.
+	///
+	/// [`dbscan`]: crate::utils::dbscan
+	pub fn clusters(&self, epsilon: f32, min_points: usize) -> Vec<Units> {
+		let positions = self
+			.iter()
+			.map(|u| (u.position(), u.tag()))
+			.collect::<Vec<(Point2, u64)>>();
+
+		dbscan(
+			&positions,
+			range_query(
+				&positions,
+				|(p1, _), (p2, _)| (*p1 - *p2).len_squared(),
+				epsilon * epsilon,
+			),
+			min_points,
+		)
+		.0
+		.into_iter()
+		.map(|cluster| self.find_tags(cluster.iter().map(|(_, tag)| tag)))
+		.collect()
+	}
+	/// Splits collection into units that match given predicate and units that don't.
+	pub fn partition<F>(&self, f: F) -> (Self, Self)
+	where
+		F: Fn(&Unit) -> bool,
+	{
+		self.iter().cloned().partition(f)
+	}
+	/// Returns closest to each other pair of units, where the first is from this collection
+	/// and the second is from `other`, or `None` if any of collections is empty.
+	pub fn closest_pair_with<'a>(&'a self, other: &'a Units) -> Option<(&'a Unit, &'a Unit)> {
+		iproduct!(self.iter(), other.iter()).min_by(|(u1, t1), (u2, t2)| {
+			let d1 = (u1.position() - t1.position()).len_squared();
+			let d2 = (u2.position() - t2.position()).len_squared();
+			cmp(&d1, &d2)
+		})
+	}
+	/// Assigns targets to units, so every unit gets at most one target
+	/// and every target is assigned to at most one unit.
+	///
+	/// Returns pairs of (unit tag, target tag). If there're more units than targets
+	/// (or vice versa), the rest of units (targets) are left unassigned.
+	pub fn assign_targets(&self, targets: &Units, strategy: AssignStrategy) -> Vec<(u64, u64)> {
+		let distance = |u: &Unit, t: &Unit| (u.position() - t.position()).len_squared();
+		match strategy {
+			AssignStrategy::Greedy => {
+				let mut pairs = iproduct!(self.iter(), targets.iter())
+					.map(|(u, t)| (distance(u, t), u.tag(), t.tag()))
+					.collect::<Vec<_>>();
+				pairs.sort_unstable_by(|a, b| cmp(&a.0, &b.0));
+
+				let mut assigned_units = FxHashSet::default();
+				let mut assigned_targets = FxHashSet::default();
+				let mut result = Vec::with_capacity(self.len().min(targets.len()));
+				for (_, u, t) in pairs {
+					if !assigned_units.contains(&u) && !assigned_targets.contains(&t) {
+						assigned_units.insert(u);
+						assigned_targets.insert(t);
+						result.push((u, t));
+					}
+				}
+				result
+			}
+			AssignStrategy::Optimal => {
+				let costs = self
+					.iter()
+					.map(|u| targets.iter().map(|t| distance(u, t).sqrt()).collect())
+					.collect::<Vec<Vec<f32>>>();
+				hungarian(&costs)
+					.into_iter()
+					.zip(self.tags())
+					.filter_map(|(t, u)| Some((*u, targets[t?].tag())))
+					.collect()
+			}
+		}
+	}
+	/// Returns count and value of units of each type and their summary stats.
+	/// Use [`my_army_composition`] and [`enemy_army_composition`] to get composition of armies.
+	///
+	/// [`my_army_composition`]: crate::bot::Bot::my_army_composition
+	/// [`enemy_army_composition`]: crate::bot::Bot::enemy_army_composition
+	pub fn composition(&self) -> Composition {
+		self.iter().collect()
+	}
 	/// Leaves only non-flying units and makes new collection of them.
 	///
 	/// Warning: This method will clone units in order to create a new collection
@@ -412,7 +563,118 @@ impl Units {
 	pub fn visible(&self) -> Self {
 		self.filter(|u| u.is_visible())
 	}
+	/// Leaves only units with `Light` attribute and makes new collection of them.
+	///
+	/// Warning: This method will clone units in order to create a new collection
+	/// and will be evaluated initially. When applicable prefer using [`light`]
+	/// on the iterator over units, since it's lazily evaluated and doesn't do any cloning operations.
+	///
+	/// [`light`]: UnitsIterator::light
+	pub fn light(&self) -> Self {
+		self.filter(|u| u.is_light())
+	}
+	/// Leaves only units with `Armored` attribute and makes new collection of them.
+	///
+	/// Warning: This method will clone units in order to create a new collection
+	/// and will be evaluated initially. When applicable prefer using [`armored`]
+	/// on the iterator over units, since it's lazily evaluated and doesn't do any cloning operations.
+	///
+	/// [`armored`]: UnitsIterator::armored
+	pub fn armored(&self) -> Self {
+		self.filter(|u| u.is_armored())
+	}
+	/// Leaves only units with `Biological` attribute and makes new collection of them.
+	///
+	/// Warning: This method will clone units in order to create a new collection
+	/// and will be evaluated initially. When applicable prefer using [`biological`]
+	/// on the iterator over units, since it's lazily evaluated and doesn't do any cloning operations.
+	///
+	/// [`biological`]: UnitsIterator::biological
+	pub fn biological(&self) -> Self {
+		self.filter(|u| u.is_biological())
+	}
+	/// Leaves only units with `Mechanical` attribute and makes new collection of them.
+	///
+	/// Warning: This method will clone units in order to create a new collection
+	/// and will be evaluated initially. When applicable prefer using [`mechanical`]
+	/// on the iterator over units, since it's lazily evaluated and doesn't do any cloning operations.
+	///
+	/// [`mechanical`]: UnitsIterator::mechanical
+	pub fn mechanical(&self) -> Self {
+		self.filter(|u| u.is_mechanical())
+	}
+	/// Leaves only units with `Psionic` attribute and makes new collection of them.
+	///
+	/// Warning: This method will clone units in order to create a new collection
+	/// and will be evaluated initially. When applicable prefer using [`psionic`]
+	/// on the iterator over units, since it's lazily evaluated and doesn't do any cloning operations.
+	///
+	/// [`psionic`]: UnitsIterator::psionic
+	pub fn psionic(&self) -> Self {
+		self.filter(|u| u.is_psionic())
+	}
+	/// Leaves only units with `Massive` attribute and makes new collection of them.
+	///
+	/// Warning: This method will clone units in order to create a new collection
+	/// and will be evaluated initially. When applicable prefer using [`massive`]
+	/// on the iterator over units, since it's lazily evaluated and doesn't do any cloning operations.
+	///
+	/// [`massive`]: UnitsIterator::massive
+	pub fn massive(&self) -> Self {
+		self.filter(|u| u.is_massive())
+	}
+	/// Leaves only cloaked or burrowed units and makes new collection of them.
+	///
+	/// Warning: This method will clone units in order to create a new collection
+	/// and will be evaluated initially. When applicable prefer using [`cloaked`]
+	/// on the iterator over units, since it's lazily evaluated and doesn't do any cloning operations.
+	///
+	/// [`cloaked`]: UnitsIterator::cloaked
+	pub fn cloaked(&self) -> Self {
+		self.filter(|u| u.is_cloaked())
+	}
+	/// Leaves only detectors (including structures, which are almost ready) and makes new collection of them.
+	///
+	/// Warning: This method will clone units in order to create a new collection
+	/// and will be evaluated initially. When applicable prefer using [`detectors`]
+	/// on the iterator over units, since it's lazily evaluated and doesn't do any cloning operations.
+	///
+	/// [`detectors`]: UnitsIterator::detectors
+	pub fn detectors(&self) -> Self {
+		self.filter(|u| u.is_detector())
+	}
 
+	/// Orders all units to [`stutter_attack`](Unit::stutter_attack) given target.
+	///
+	/// Units don't get commands they're already executing, so it's safe to call it every step.
+	pub fn stutter_attack(&self, target: &Unit) {
+		for u in self {
+			u.stutter_attack(target);
+		}
+	}
+	/// Focuses fire of all units on given targets without overkill.
+	///
+	/// Each unit [`focus_attack`](Unit::focus_attack)s the first target in given order,
+	/// which isn't going to be killed by units ordered before it, preferring targets
+	/// already in its range. Units, which can't attack any of targets, are left untouched.
+	pub fn focus_fire<'a, T: IntoIterator<Item = &'a Unit>>(&self, targets: T) {
+		let targets = targets.into_iter().collect::<Vec<_>>();
+		for u in self {
+			let can_attack = |t: &&Unit| u.real_range_vs(t) > f32::EPSILON;
+			let attacked_in_range = targets
+				.iter()
+				.copied()
+				.filter(|t| can_attack(t) && u.in_real_range(t, 0.0))
+				.any(|t| u.focus_attack(t));
+			if !attacked_in_range {
+				targets
+					.iter()
+					.copied()
+					.filter(can_attack)
+					.any(|t| u.focus_attack(t));
+			}
+		}
+	}
 	/// Sorts the collection by given function.
 	pub fn sort<T, F>(&mut self, f: F)
 	where
@@ -570,6 +832,9 @@ impl Units {
 	/// and will be evaluated initially. When applicable prefer using [`of_types`]
 	/// on the iterator over units, since it's lazily evaluated and doesn't do any cloning operations.
 	///
+	/// Types can be given in any [`Container`], e.g. slice or set.
+	/// Use [`UnitTypeSet`] for fast filtering by many types.
+	///
 	/// [`of_types`]: UnitsIterator::of_types
 	pub fn of_types<T: Container<UnitTypeId>>(&self, types: &T) -> Self {
 		self.filter(|u| types.contains(&u.type_id()))
@@ -581,6 +846,9 @@ impl Units {
 	/// and will be evaluated initially. When applicable prefer using [`exclude_types`]
 	/// on the iterator over units, since it's lazily evaluated and doesn't do any cloning operations.
 	///
+	/// Types can be given in any [`Container`], e.g. slice or set.
+	/// Use [`UnitTypeSet`] for fast filtering by many types.
+	///
 	/// [`exclude_types`]: UnitsIterator::exclude_types
 	pub fn exclude_types<T: Container<UnitTypeId>>(&self, types: &T) -> Self {
 		self.filter(|u| !types.contains(&u.type_id()))
@@ -680,6 +948,17 @@ impl Units {
 	}
 }
 
+/// Strategy of [`assign_targets`](Units::assign_targets).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AssignStrategy {
+	/// Repeatedly assigns the closest pair of free unit and target. Fast, but not optimal.
+	#[default]
+	Greedy,
+	/// Minimizes total distance between units and their targets with [`hungarian`] algorithm.
+	/// Takes `O(n^3)` time, so should be used for small collections.
+	Optimal,
+}
+
 /// Joins collections functionality to check if given item is present in it.
 /// Used in generics of some units methods.
 pub trait Container<T> {
@@ -687,6 +966,7 @@ pub trait Container<T> {
 	fn contains(&self, item: &T) -> bool;
 }
 
+use num_traits::{FromPrimitive, ToPrimitive};
 use std::{
 	collections::{BTreeMap, BTreeSet, HashMap, HashSet},
 	hash::{BuildHasher, Hash},
@@ -697,6 +977,11 @@ impl<T: PartialEq> Container<T> for &[T] {
 		self.iter().any(|item| item == other)
 	}
 }
+impl<T: PartialEq, const N: usize> Container<T> for [T; N] {
+	fn contains(&self, other: &T) -> bool {
+		self.iter().any(|item| item == other)
+	}
+}
 impl<T: PartialEq> Container<T> for Vec<T> {
 	fn contains(&self, other: &T) -> bool {
 		self.iter().any(|item| item == other)
@@ -732,3 +1017,102 @@ impl<T: Eq + Hash, V> Container<T> for IndexMap<T, V> {
 		self.contains_key(item)
 	}
 }
+
+/// Set of unit types, which checks membership with a single bit lookup.
+///
+/// Faster than slices and hash sets when filtering many units by several types:
+/// ```ignore
+/// let army = UnitTypeSet::from(&[UnitTypeId::Marine, UnitTypeId::Marauder, UnitTypeId::Medivac]);
+/// let bio = self.units.my.units.of_types(&army);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct UnitTypeSet(Vec<u64>);
+impl UnitTypeSet {
+	/// Constructs empty set.
+	pub fn new() -> Self {
+		Default::default()
+	}
+	/// Adds type to the set. Returns `true` if it wasn't present in the set.
+	pub fn insert(&mut self, type_id: UnitTypeId) -> bool {
+		let (word, bit) = Self::position(type_id);
+		if word >= self.0.len() {
+			self.0.resize(word + 1, 0);
+		}
+		let present = self.0[word] & bit != 0;
+		self.0[word] |= bit;
+		!present
+	}
+	/// Removes type from the set. Returns `true` if it was present in the set.
+	pub fn remove(&mut self, type_id: UnitTypeId) -> bool {
+		let (word, bit) = Self::position(type_id);
+		match self.0.get_mut(word) {
+			Some(w) if *w & bit != 0 => {
+				*w &= !bit;
+				// Trailing empty words are trimmed, so equal sets have equal words
+				while self.0.last() == Some(&0) {
+					self.0.pop();
+				}
+				true
+			}
+			_ => false,
+		}
+	}
+	/// Checks if type is present in the set.
+	#[inline]
+	pub fn contains(&self, type_id: UnitTypeId) -> bool {
+		let (word, bit) = Self::position(type_id);
+		self.0.get(word).map_or(false, |w| w & bit != 0)
+	}
+	/// Number of types in the set.
+	pub fn len(&self) -> usize {
+		self.0.iter().map(|w| w.count_ones() as usize).sum()
+	}
+	/// Checks if the set is empty.
+	pub fn is_empty(&self) -> bool {
+		self.0.iter().all(|w| *w == 0)
+	}
+	/// Returns an iterator over types in the set.
+	pub fn iter(&self) -> impl Iterator<Item = UnitTypeId> + '_ {
+		self.0.iter().enumerate().flat_map(|(i, w)| {
+			(0..64)
+				.filter(move |bit| w & (1 << bit) != 0)
+				.filter_map(move |bit| UnitTypeId::from_usize(i * 64 + bit))
+		})
+	}
+
+	#[inline]
+	fn position(type_id: UnitTypeId) -> (usize, u64) {
+		let id = type_id.to_usize().unwrap_or_default();
+		(id / 64, 1 << (id % 64))
+	}
+}
+impl Container<UnitTypeId> for UnitTypeSet {
+	#[inline]
+	fn contains(&self, item: &UnitTypeId) -> bool {
+		UnitTypeSet::contains(self, *item)
+	}
+}
+impl FromIterator<UnitTypeId> for UnitTypeSet {
+	fn from_iter<I: IntoIterator<Item = UnitTypeId>>(iter: I) -> Self {
+		let mut set = Self::new();
+		set.extend(iter);
+		set
+	}
+}
+impl Extend<UnitTypeId> for UnitTypeSet {
+	fn extend<I: IntoIterator<Item = UnitTypeId>>(&mut self, iter: I) {
+		for type_id in iter {
+			self.insert(type_id);
+		}
+	}
+}
+impl From<&[UnitTypeId]> for UnitTypeSet {
+	fn from(types: &[UnitTypeId]) -> Self {
+		types.iter().copied().collect()
+	}
+}
+impl<const N: usize> From<&[UnitTypeId; N]> for UnitTypeSet {
+	fn from(types: &[UnitTypeId; N]) -> Self {
+		types.iter().copied().collect()
+	}
+}
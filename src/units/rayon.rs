@@ -317,6 +317,38 @@ where
 	fn visible(self) -> Visible<Self> {
 		Visible::new(self)
 	}
+	/// Leaves only units with `Light` attribute.
+	fn light(self) -> Light<Self> {
+		Light::new(self)
+	}
+	/// Leaves only units with `Armored` attribute.
+	fn armored(self) -> Armored<Self> {
+		Armored::new(self)
+	}
+	/// Leaves only units with `Biological` attribute.
+	fn biological(self) -> Biological<Self> {
+		Biological::new(self)
+	}
+	/// Leaves only units with `Mechanical` attribute.
+	fn mechanical(self) -> Mechanical<Self> {
+		Mechanical::new(self)
+	}
+	/// Leaves only units with `Psionic` attribute.
+	fn psionic(self) -> Psionic<Self> {
+		Psionic::new(self)
+	}
+	/// Leaves only units with `Massive` attribute.
+	fn massive(self) -> Massive<Self> {
+		Massive::new(self)
+	}
+	/// Leaves only cloaked or burrowed units.
+	fn cloaked(self) -> Cloaked<Self> {
+		Cloaked::new(self)
+	}
+	/// Leaves only detectors (including structures, which are almost ready).
+	fn detectors(self) -> Detectors<Self> {
+		Detectors::new(self)
+	}
 	/// Leaves only units in attack range of given unit.
 	fn in_range_of(self, unit: &Unit, gap: f32) -> InRangeOf<Self> {
 		InRangeOf::new(self, unit, gap)
@@ -701,6 +733,54 @@ make_simple_iterator!(
 	|u| u.is_visible()
 );
 
+make_simple_iterator!(
+	/// An iterator that filters units with `Light` attribute.
+	Light,
+	|u| u.is_light()
+);
+
+make_simple_iterator!(
+	/// An iterator that filters units with `Armored` attribute.
+	Armored,
+	|u| u.is_armored()
+);
+
+make_simple_iterator!(
+	/// An iterator that filters units with `Biological` attribute.
+	Biological,
+	|u| u.is_biological()
+);
+
+make_simple_iterator!(
+	/// An iterator that filters units with `Mechanical` attribute.
+	Mechanical,
+	|u| u.is_mechanical()
+);
+
+make_simple_iterator!(
+	/// An iterator that filters units with `Psionic` attribute.
+	Psionic,
+	|u| u.is_psionic()
+);
+
+make_simple_iterator!(
+	/// An iterator that filters units with `Massive` attribute.
+	Massive,
+	|u| u.is_massive()
+);
+
+make_simple_iterator!(
+	/// An iterator that filters cloaked or burrowed units.
+	Cloaked,
+	|u| u.is_cloaked()
+);
+
+make_simple_iterator!(
+	/// An iterator that filters detectors (including structures, which are almost ready).
+	Detectors,
+	|u| u.is_detector()
+);
+
 /// An iterator that filters units in attack range of given unit.
 #[derive(Clone)]
 pub struct InRangeOf<'a, I> {
@@ -80,6 +80,101 @@ where
 	}
 }
 
+/// Hungarian algorithm for assignment problem.
+///
+/// Takes matrix of costs, where `costs[i][j]` is cost of assigning row `i` to column `j`
+/// (all rows should have the same length).
+///
+/// Returns column assigned to each row, so that every column is assigned at most once
+/// and total cost is minimal. If there're more rows than columns, some rows are left with `None`.
+pub fn hungarian(costs: &[Vec<f32>]) -> Vec<Option<usize>> {
+	let n = costs.len();
+	let m = costs.first().map_or(0, |row| row.len());
+	if m == 0 {
+		return vec![None; n];
+	}
+	if n <= m {
+		return hungarian_rows(costs, n, m);
+	}
+
+	// Algorithm requires rows <= columns, so it's solved for transposed matrix
+	let transposed = (0..m)
+		.map(|j| costs.iter().map(|row| row[j]).collect())
+		.collect::<Vec<Vec<f32>>>();
+	let mut result = vec![None; n];
+	for (j, i) in hungarian_rows(&transposed, m, n).into_iter().enumerate() {
+		if let Some(i) = i {
+			result[i] = Some(j);
+		}
+	}
+	result
+}
+
+// O(n^2 * m) implementation with potentials, indices are 1-based and 0 is a fake column
+fn hungarian_rows(costs: &[Vec<f32>], n: usize, m: usize) -> Vec<Option<usize>> {
+	let mut u = vec![0.0; n + 1];
+	let mut v = vec![0.0; m + 1];
+	let mut p = vec![0; m + 1];
+	let mut way = vec![0; m + 1];
+	for i in 1..=n {
+		p[0] = i;
+		let mut j0 = 0;
+		let mut minv = vec![f32::INFINITY; m + 1];
+		let mut used = vec![false; m + 1];
+		loop {
+			used[j0] = true;
+			let i0 = p[j0];
+			let mut delta = f32::INFINITY;
+			let mut j1 = 0;
+			for j in 1..=m {
+				if !used[j] {
+					let cur = costs[i0 - 1][j - 1] - u[i0] - v[j];
+					if cur < minv[j] {
+						minv[j] = cur;
+						way[j] = j0;
+					}
+					if minv[j] < delta {
+						delta = minv[j];
+						j1 = j;
+					}
+				}
+			}
+			// Costs aren't comparable (NaN or infinite), leaving row unassigned
+			if j1 == 0 {
+				break;
+			}
+			for (j, &is_used) in used.iter().enumerate() {
+				if is_used {
+					u[p[j]] += delta;
+					v[j] -= delta;
+				} else {
+					minv[j] -= delta;
+				}
+			}
+			j0 = j1;
+			if p[j0] == 0 {
+				break;
+			}
+		}
+		if p[j0] != 0 {
+			continue;
+		}
+		while j0 != 0 {
+			let j1 = way[j0];
+			p[j0] = p[j1];
+			j0 = j1;
+		}
+	}
+
+	let mut result = vec![None; n];
+	for j in 1..=m {
+		if p[j] != 0 {
+			result[p[j] - 1] = Some(j - 1);
+		}
+	}
+	result
+}
+
 #[cfg(feature = "parking_lot")]
 use parking_lot::{RwLock, RwLockReadGuard};
 #[cfg(not(feature = "parking_lot"))]
@@ -125,3 +220,53 @@ where
 		read(&self.0).get(k).copied()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::hungarian;
+
+	const INF: f32 = f32::INFINITY;
+
+	fn total(costs: &[Vec<f32>], result: &[Option<usize>]) -> f32 {
+		result
+			.iter()
+			.enumerate()
+			.filter_map(|(i, j)| j.map(|j| costs[i][j]))
+			.sum()
+	}
+
+	#[test]
+	fn square_matrix() {
+		let costs = vec![vec![4.0, 1.0, 3.0], vec![2.0, 0.0, 5.0], vec![3.0, 2.0, 2.0]];
+		let result = hungarian(&costs);
+		assert_eq!(result, vec![Some(1), Some(0), Some(2)]);
+		assert_eq!(total(&costs, &result), 5.0);
+	}
+
+	#[test]
+	fn more_columns_than_rows() {
+		let costs = vec![vec![5.0, 1.0, 4.0], vec![2.0, 6.0, 3.0]];
+		assert_eq!(hungarian(&costs), vec![Some(1), Some(0)]);
+	}
+
+	#[test]
+	fn more_rows_than_columns() {
+		let costs = vec![vec![5.0, 2.0], vec![1.0, 6.0], vec![4.0, 3.0]];
+		assert_eq!(hungarian(&costs), vec![Some(1), Some(0), None]);
+	}
+
+	#[test]
+	fn infinite_costs_are_avoided() {
+		let costs = vec![vec![INF, 3.0, 1.0], vec![2.0, INF, 4.0]];
+		assert_eq!(hungarian(&costs), vec![Some(2), Some(0)]);
+
+		let costs = vec![vec![1.0, 2.0], vec![INF, INF]];
+		assert_eq!(hungarian(&costs), vec![Some(0), None]);
+	}
+
+	#[test]
+	fn empty_matrix() {
+		assert_eq!(hungarian(&[]), vec![]);
+		assert_eq!(hungarian(&[vec![], vec![]]), vec![None, None]);
+	}
+}
@@ -0,0 +1,292 @@
+//! Wall-off solver for the natural expansion.
+//!
+//! Finds the chokepoint between natural and the rest of the map,
+//! then searches for building placements that seal it leaving a 1-tile gap.
+
+use crate::{distance::*, geometry::Point2, pixel_map::PixelMap};
+use rustc_hash::FxHashSet;
+use std::collections::VecDeque;
+
+type Pos = (usize, usize);
+
+/// Placements of protoss wall at natural expansion.
+/// Returned by [`natural_wall`](crate::bot::Bot::natural_wall).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NaturalWall {
+	/// Positions of 3x3 buildings (gateway and cybernetics core).
+	pub buildings: [Point2; 2],
+	/// Position of pylon, which powers both buildings.
+	pub pylon: Point2,
+	/// Position of 1-tile gap, which should be held by zealot.
+	pub gap: Point2,
+}
+
+// Maximum distance from natural to chokepoint.
+const CHOKE_SEARCH_DISTANCE: usize = 22;
+// Half-size of area, where wall is validated.
+const WALL_AREA: isize = 14;
+// Pylon power radius, reduced to make sure that whole footprint is powered.
+const PYLON_POWER: f32 = 6.0;
+
+#[derive(Clone, Copy)]
+struct Footprint {
+	x: usize,
+	y: usize,
+	size: usize,
+}
+impl Footprint {
+	// `(x, y)` is bottom-left cell of footprint.
+	fn new(x: usize, y: usize, size: usize) -> Self {
+		Self { x, y, size }
+	}
+	fn cells(self) -> impl Iterator<Item = Pos> {
+		iproduct!(self.x..self.x + self.size, self.y..self.y + self.size)
+	}
+	fn contains(self, (x, y): Pos) -> bool {
+		self.x <= x && x < self.x + self.size && self.y <= y && y < self.y + self.size
+	}
+	fn overlaps(self, other: Self) -> bool {
+		self.x < other.x + other.size
+			&& other.x < self.x + self.size
+			&& self.y < other.y + other.size
+			&& other.y < self.y + self.size
+	}
+	fn center(self) -> Point2 {
+		let half = self.size as f32 / 2.0;
+		Point2::new(self.x as f32 + half, self.y as f32 + half)
+	}
+}
+
+struct Grids<'a> {
+	pathing: &'a PixelMap,
+	placement: &'a PixelMap,
+}
+impl Grids<'_> {
+	fn is_pathable(&self, pos: Pos) -> bool {
+		self.pathing.get(pos).is_some_and(|p| p.is_empty())
+	}
+	fn is_placeable(&self, pos: Pos) -> bool {
+		self.placement.get(pos).is_some_and(|p| p.is_empty())
+	}
+	fn neighbors(&self, (x, y): Pos) -> impl Iterator<Item = Pos> + '_ {
+		[
+			(0, 1),
+			(1, 0),
+			(0, -1),
+			(-1, 0),
+			(1, 1),
+			(1, -1),
+			(-1, 1),
+			(-1, -1),
+		]
+		.into_iter()
+		.filter_map(move |(dx, dy)| offset((x, y), dx, dy))
+		.filter(move |&p| self.is_pathable(p))
+	}
+	// Walking distances from given position to every pathable cell.
+	fn distances(&self, from: Pos) -> Vec<Vec<usize>> {
+		let (w, h) = self.pathing.dim();
+		let mut dist = vec![vec![usize::MAX; h]; w];
+		let mut queue = VecDeque::new();
+		if self.is_pathable(from) {
+			dist[from.0][from.1] = 0;
+			queue.push_back(from);
+		}
+		while let Some(p) = queue.pop_front() {
+			let d = dist[p.0][p.1] + 1;
+			for n in self.neighbors(p) {
+				if dist[n.0][n.1] == usize::MAX {
+					dist[n.0][n.1] = d;
+					queue.push_back(n);
+				}
+			}
+		}
+		dist
+	}
+	// Checks if `goal` is reachable from `start` without leaving area around `center`.
+	fn connected(&self, start: Pos, goal: Pos, center: Pos, blocked: &[Footprint]) -> bool {
+		let in_area = |(x, y): Pos| {
+			(x as isize - center.0 as isize).abs() <= WALL_AREA
+				&& (y as isize - center.1 as isize).abs() <= WALL_AREA
+		};
+		let free = |p: Pos| !blocked.iter().any(|f| f.contains(p));
+		if !free(start) || !free(goal) {
+			return false;
+		}
+
+		let mut visited = FxHashSet::default();
+		visited.insert(start);
+		let mut queue = VecDeque::from([start]);
+		while let Some(p) = queue.pop_front() {
+			if p == goal {
+				return true;
+			}
+			for n in self.neighbors(p) {
+				if in_area(n) && free(n) && !visited.contains(&n) {
+					// Units can't squeeze diagonally between two blocked cells
+					let (dx, dy) = (n.0 as isize - p.0 as isize, n.1 as isize - p.1 as isize);
+					if dx != 0 && dy != 0 {
+						let side1 = (n.0, p.1);
+						let side2 = (p.0, n.1);
+						let open = |c: Pos| self.is_pathable(c) && free(c);
+						if !open(side1) && !open(side2) {
+							continue;
+						}
+					}
+					visited.insert(n);
+					queue.push_back(n);
+				}
+			}
+		}
+		false
+	}
+}
+
+fn offset((x, y): Pos, dx: isize, dy: isize) -> Option<Pos> {
+	Some((x.checked_add_signed(dx)?, y.checked_add_signed(dy)?))
+}
+
+/// Finds placements of gateway, cybernetics core and pylon which seal chokepoint
+/// in front of `natural`, leaving 1-tile gap for zealot.
+///
+/// Inputs:
+/// - `pathing` and `placement`: grids from [`GameInfo`](crate::game_info::GameInfo).
+/// - `natural`: location of natural expansion.
+/// - `target`: any location outside of natural (usually opponent's start location),
+///   used to determine direction where chokepoint is.
+///
+/// Returns `None` if chokepoint isn't found or it's too wide to be walled.
+pub fn natural_wall(
+	pathing: &PixelMap,
+	placement: &PixelMap,
+	natural: Point2,
+	target: Point2,
+) -> Option<NaturalWall> {
+	let grids = Grids { pathing, placement };
+	let natural_pos = <Pos>::from(natural);
+
+	// Walking from natural toward target to find chokepoint on the way
+	let dist = grids.distances(target.into());
+	let mut path = vec![natural_pos];
+	let mut current = natural_pos;
+	while path.len() <= CHOKE_SEARCH_DISTANCE + WALL_AREA as usize {
+		let next = grids
+			.neighbors(current)
+			.min_by_key(|n| dist[n.0][n.1])
+			.filter(|n| dist[n.0][n.1] < dist[current.0][current.1])?;
+		path.push(next);
+		current = next;
+	}
+
+	let (choke_index, line) = path
+		.iter()
+		.enumerate()
+		.take(CHOKE_SEARCH_DISTANCE)
+		.skip(6)
+		.map(|(i, &p)| (i, cross_section(&grids, p)))
+		.min_by_key(|(_, line)| line.len())?;
+	let choke = path[choke_index];
+	let inside = path[choke_index - 6];
+	let outside = path[choke_index + 6];
+
+	// Candidates should cover at least one cell of cross section
+	// and stay away from natural townhall
+	let candidates = |size: usize| {
+		let mut footprints = line
+			.iter()
+			.flat_map(|&p| {
+				iproduct!(0..size as isize, 0..size as isize)
+					.filter_map(move |(dx, dy)| offset(p, -dx, -dy))
+					.map(move |(x, y)| Footprint::new(x, y, size))
+			})
+			.filter(|f| {
+				f.cells().all(|c| grids.is_placeable(c))
+					&& f.center().distance_squared(natural) > 36.0
+					&& !f.contains(inside)
+					&& !f.contains(outside)
+			})
+			.collect::<Vec<_>>();
+		footprints.sort_by_key(|f| (f.x, f.y));
+		footprints.dedup_by_key(|f| (f.x, f.y));
+		footprints.sort_by(|a, b| {
+			let d = |f: &Footprint| f.center().distance_squared(natural);
+			d(a).partial_cmp(&d(b)).unwrap()
+		});
+		footprints
+	};
+	let buildings = candidates(3);
+	let pylons = {
+		// Pylon can also stand behind the wall
+		let mut pylons = candidates(2);
+		pylons.extend(
+			iproduct!(
+				choke.0.saturating_sub(8)..choke.0 + 8,
+				choke.1.saturating_sub(8)..choke.1 + 8
+			)
+			.map(|(x, y)| Footprint::new(x, y, 2))
+			.filter(|f| {
+				f.cells()
+					.all(|c| grids.is_placeable(c) && dist[c.0][c.1] > dist[choke.0][choke.1])
+					&& f.center().distance_squared(natural) > 36.0
+			}),
+		);
+		pylons
+	};
+
+	for (i, &b1) in buildings.iter().enumerate() {
+		for &b2 in &buildings[i + 1..] {
+			if b1.overlaps(b2) {
+				continue;
+			}
+			for &pylon in &pylons {
+				if pylon.overlaps(b1)
+					|| pylon.overlaps(b2)
+					|| !pylon.center().is_closer(PYLON_POWER, b1.center())
+					|| !pylon.center().is_closer(PYLON_POWER, b2.center())
+				{
+					continue;
+				}
+
+				let wall = [b1, b2, pylon];
+				let mut uncovered = line.iter().filter(|&&p| !wall.iter().any(|f| f.contains(p)));
+				let gap = match (uncovered.next(), uncovered.next()) {
+					(Some(&gap), None) => gap,
+					_ => continue,
+				};
+
+				let sealed = [b1, b2, pylon, Footprint::new(gap.0, gap.1, 1)];
+				if grids.connected(inside, outside, choke, &wall)
+					&& !grids.connected(inside, outside, choke, &sealed)
+				{
+					return Some(NaturalWall {
+						buildings: [b1.center(), b2.center()],
+						pylon: pylon.center(),
+						gap: Point2::new(gap.0 as f32 + 0.5, gap.1 as f32 + 0.5),
+					});
+				}
+			}
+		}
+	}
+	None
+}
+
+// Returns pathable cells of the narrowest line going through given position.
+fn cross_section(grids: &Grids, pos: Pos) -> Vec<Pos> {
+	const MAX_HALF_WIDTH: isize = 8;
+	[(1, 0), (0, 1), (1, 1), (1, -1)]
+		.into_iter()
+		.map(|(dx, dy)| {
+			let mut line = vec![pos];
+			for sign in [1, -1] {
+				for i in 1..=MAX_HALF_WIDTH {
+					match offset(pos, dx * sign * i, dy * sign * i) {
+						Some(p) if grids.is_pathable(p) => line.push(p),
+						_ => break,
+					}
+				}
+			}
+			line
+		})
+		.min_by_key(|line| line.len())
+		.unwrap()
+}
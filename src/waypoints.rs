@@ -0,0 +1,108 @@
+//! Smoothing of grid paths into a few waypoints.
+//!
+//! Paths found on pathing grid go through centers of adjacent tiles,
+//! so units following them tile by tile move in zigzags. Smoothing leaves only points,
+//! where path has to turn around obstacles:
+//! ```ignore
+//! // Once, when path is found
+//! self.route = self.waypoints(&path);
+//!
+//! // Every step
+//! if let Some(pos) = self.route.next_waypoint(unit.position()) {
+//!     unit.move_to(Target::Pos(pos), false);
+//! }
+//! ```
+
+use crate::{bot::Bot, distance::Distance, geometry::Point2};
+
+/// Default value of [`arrive_distance`](Waypoints::arrive_distance).
+const ARRIVE_DISTANCE: f32 = 1.5;
+
+/// Route through waypoints, which tracks the next point to move to.
+#[derive(Debug, Clone)]
+pub struct Waypoints {
+	points: Vec<Point2>,
+	current: usize,
+	/// Distance to waypoint, at which it's considered reached. [Default: `1.5`]
+	pub arrive_distance: f32,
+}
+impl Waypoints {
+	/// Constructs route through given points.
+	pub fn new(points: Vec<Point2>) -> Self {
+		Self {
+			points,
+			current: 0,
+			arrive_distance: ARRIVE_DISTANCE,
+		}
+	}
+	/// Returns waypoint unit at given position should move to,
+	/// skipping waypoints, which are already reached.
+	/// Returns `None` when the last waypoint is reached.
+	pub fn next_waypoint(&mut self, pos: Point2) -> Option<Point2> {
+		while let Some(next) = self.points.get(self.current) {
+			if !pos.is_closer(self.arrive_distance, *next) {
+				return Some(*next);
+			}
+			self.current += 1;
+		}
+		None
+	}
+	/// Returns waypoints, which aren't reached yet.
+	pub fn remaining(&self) -> &[Point2] {
+		&self.points[self.current.min(self.points.len())..]
+	}
+	/// Returns all waypoints of the route.
+	pub fn points(&self) -> &[Point2] {
+		&self.points
+	}
+	/// Checks if all waypoints are reached.
+	pub fn is_finished(&self) -> bool {
+		self.current >= self.points.len()
+	}
+	/// Starts route from the first waypoint again.
+	pub fn reset(&mut self) {
+		self.current = 0;
+	}
+}
+impl Default for Waypoints {
+	fn default() -> Self {
+		Self::new(Vec::new())
+	}
+}
+impl From<Vec<Point2>> for Waypoints {
+	fn from(points: Vec<Point2>) -> Self {
+		Self::new(points)
+	}
+}
+
+impl Bot {
+	/// Smooths path for ground units with string pulling:
+	/// points, which can be skipped by walking in straight line
+	/// (see [`is_line_pathable`](Self::is_line_pathable)), are removed.
+	///
+	/// The first and the last points of path are always kept.
+	pub fn smooth_path(&self, path: &[Point2]) -> Vec<Point2> {
+		let (first, last) = match (path.first(), path.last()) {
+			(Some(first), Some(last)) => (*first, *last),
+			_ => return Vec::new(),
+		};
+
+		let mut smoothed = vec![first];
+		let mut anchor = first;
+		for points in path.windows(3) {
+			// Turning at the current point, if the next one can't be reached directly
+			if !self.is_line_pathable(anchor, points[2]) {
+				anchor = points[1];
+				smoothed.push(anchor);
+			}
+		}
+		if path.len() > 1 {
+			smoothed.push(last);
+		}
+		smoothed
+	}
+	/// Returns route through [smoothed](Self::smooth_path) path.
+	pub fn waypoints(&self, path: &[Point2]) -> Waypoints {
+		Waypoints::new(self.smooth_path(path))
+	}
+}
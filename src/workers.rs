@@ -0,0 +1,243 @@
+//! Automatic distribution of workers between bases and gas buildings.
+//!
+//! Unlike manual distribution in `on_step`, manager doesn't scan workers every step.
+//! It redistributes them only when something changes: new worker is trained,
+//! construction started or finished, base, gas building or worker was lost
+//! or mineral field was depleted.
+
+use crate::{bot::Bot, distance::*, geometry::Point2, unit::Unit, Event};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// Settings of automatic worker manager.
+/// Stored in [`worker_manager`](Bot::worker_manager) field of bot.
+///
+/// Manager is disabled by default, it can be enabled by setting
+/// `self.worker_manager.enabled = true` in [`on_start`](crate::Player::on_start).
+///
+/// When enabled, it runs every step before [`on_step`](crate::Player::on_step):
+/// sends idle workers to mine, transfers surplus workers from oversaturated bases
+/// to undersaturated ones and keeps target number of workers on gas.
+/// Workers which are building, scouting or given other orders are not touched,
+/// unless they become idle.
+#[derive(Debug, Clone)]
+pub struct WorkerManager {
+	/// Distribute workers automatically. [Default: `false`]
+	pub enabled: bool,
+	/// Total number of workers which should gather vespene,
+	/// or `None` to fully saturate all gas buildings. [Default: `None`]
+	///
+	/// Changing it makes manager redistribute workers on next step.
+	pub gas_workers: Option<usize>,
+	tracked: FxHashSet<u64>,
+	applied_gas_workers: Option<usize>,
+	dirty: bool,
+}
+impl Default for WorkerManager {
+	fn default() -> Self {
+		Self {
+			enabled: false,
+			gas_workers: None,
+			tracked: Default::default(),
+			applied_gas_workers: None,
+			dirty: true,
+		}
+	}
+}
+impl WorkerManager {
+	/// Makes manager redistribute workers on next step
+	/// (e.g. after workers were taken from mining manually).
+	pub fn redistribute(&mut self) {
+		self.dirty = true;
+	}
+}
+
+// Group of workers gathering from the same base or gas building.
+struct Site {
+	target: u64,
+	pos: Point2,
+	resources: Vec<u64>,
+	assigned: usize,
+	ideal: usize,
+	workers: Vec<u64>,
+}
+
+impl Bot {
+	pub(crate) fn manage_workers(&mut self, events: &[Event]) {
+		if !self.worker_manager.enabled {
+			return;
+		}
+
+		let manager = &self.worker_manager;
+		let mut dirty = manager.dirty || manager.gas_workers != manager.applied_gas_workers;
+		if !dirty {
+			dirty = events.iter().any(|e| match e {
+				Event::UnitCreated(tag) => self.units.my.workers.contains_tag(*tag),
+				Event::ConstructionStarted(_) | Event::ConstructionComplete(_) => true,
+				Event::UnitDestroyed(tag, _) => manager.tracked.contains(tag),
+				Event::RandomRaceDetected(_)
+				| Event::ChatMessage { .. }
+				| Event::Timer(_)
+				| Event::HiddenEnemyNearBase(_) => false,
+			});
+		}
+		if !dirty {
+			return;
+		}
+
+		let tracked = self.distribute_workers();
+		let manager = &mut self.worker_manager;
+		manager.tracked = tracked;
+		manager.applied_gas_workers = manager.gas_workers;
+		manager.dirty = false;
+	}
+
+	// Gives orders to workers and returns tags of all units, which affect distribution.
+	fn distribute_workers(&self) -> FxHashSet<u64> {
+		let mut tracked = FxHashSet::default();
+		let my = &self.units.my;
+		let minerals = &self.units.mineral_fields;
+
+		let mut bases = Vec::new();
+		let mut base_of = FxHashMap::default();
+		for th in my.townhalls.iter().filter(|th| th.is_ready()) {
+			tracked.insert(th.tag());
+			let resources = self
+				.expansions
+				.of_base(th.tag())
+				.map(|exp| {
+					exp.minerals
+						.iter()
+						.copied()
+						.filter(|m| minerals.contains_tag(*m))
+						.collect::<Vec<_>>()
+				})
+				.unwrap_or_default();
+			if resources.is_empty() {
+				continue;
+			}
+			base_of.insert(th.tag(), bases.len());
+			for m in &resources {
+				tracked.insert(*m);
+				base_of.insert(*m, bases.len());
+			}
+			bases.push(site(th, resources));
+		}
+
+		let mut gases = Vec::new();
+		let mut gas_of = FxHashMap::default();
+		for gas in my
+			.gas_buildings
+			.iter()
+			.filter(|g| g.is_ready() && g.vespene_contents().is_some_and(|v| v > 0))
+		{
+			tracked.insert(gas.tag());
+			gas_of.insert(gas.tag(), gases.len());
+			gases.push(site(gas, vec![gas.tag()]));
+		}
+
+		// Workers which can be sent to other resources
+		let mut free = Vec::new();
+		let mut mineral_load = FxHashMap::<u64, usize>::default();
+		for w in my.workers.iter() {
+			tracked.insert(w.tag());
+			if w.is_constructing() || w.is_hallucination() {
+				continue;
+			}
+			if w.is_idle() {
+				free.push(w);
+				continue;
+			}
+			if !(w.is_gathering() || w.is_returning()) {
+				continue;
+			}
+			match w.target_tag() {
+				Some(target) => {
+					if let Some(&i) = gas_of.get(&target) {
+						gases[i].workers.push(w.tag());
+					} else if let Some(&i) = base_of.get(&target) {
+						bases[i].workers.push(w.tag());
+						if target != bases[i].target {
+							*mineral_load.entry(target).or_default() += 1;
+						}
+					} else {
+						// Base was lost or mineral field depleted
+						free.push(w);
+					}
+				}
+				None => free.push(w),
+			}
+		}
+
+		// Splitting gas workers between gas buildings
+		let mut gas_left = self.worker_manager.gas_workers.unwrap_or(usize::MAX);
+		for gas in &mut gases {
+			gas.ideal = gas.ideal.min(gas_left);
+			gas_left -= gas.ideal;
+		}
+
+		// Taking surplus workers, preferring ones which don't carry resources
+		for site in gases.iter().chain(&bases) {
+			let surplus = site.assigned.saturating_sub(site.ideal);
+			let mut workers = site
+				.workers
+				.iter()
+				.filter_map(|tag| my.workers.get(*tag))
+				.collect::<Vec<_>>();
+			workers.sort_by_key(|w| w.is_carrying_resource());
+			free.extend(workers.into_iter().take(surplus));
+		}
+
+		for site in gases.iter_mut().chain(&mut bases) {
+			let pos = site.pos;
+			for _ in site.assigned..site.ideal {
+				let w = match free.iter().copied().closest(pos) {
+					Some(w) => w,
+					None => break,
+				};
+				free.retain(|u| u.tag() != w.tag());
+				w.gather(pick_resource(site, &mut mineral_load), false);
+				site.assigned += 1;
+			}
+		}
+
+		// Remaining idle workers and surplus gas workers are sent to mine at closest base,
+		// surplus mineral workers keep mining where they are
+		for w in free.into_iter().filter(|w| {
+			w.target_tag()
+				.map_or(true, |target| !base_of.contains_key(&target))
+		}) {
+			if let Some(site) = bases.iter().min_by(|a, b| {
+				w.distance_squared(a.pos)
+					.partial_cmp(&w.distance_squared(b.pos))
+					.unwrap()
+			}) {
+				w.gather(pick_resource(site, &mut mineral_load), false);
+			}
+		}
+
+		tracked
+	}
+}
+
+fn site(u: &Unit, resources: Vec<u64>) -> Site {
+	Site {
+		target: u.tag(),
+		pos: u.position(),
+		resources,
+		assigned: u.assigned_harvesters().unwrap_or_default() as usize,
+		ideal: u.ideal_harvesters().unwrap_or_default() as usize,
+		workers: Vec::new(),
+	}
+}
+
+// Returns the least loaded resource of site (closest to townhall if there're several).
+fn pick_resource(site: &Site, load: &mut FxHashMap<u64, usize>) -> u64 {
+	let resource = site
+		.resources
+		.iter()
+		.copied()
+		.min_by_key(|r| load.get(r).copied().unwrap_or_default())
+		.unwrap_or(site.target);
+	*load.entry(resource).or_default() += 1;
+	resource
+}
@@ -0,0 +1,135 @@
+"""
+Writes `mock_game.sc2capture` used by `tests/mock.rs`.
+
+Capture is a short game on empty 16x16 map: game info, empty game data
+and two observations, the game is won on the next step.
+Messages are encoded by hand, so this script doesn't need protobuf package.
+"""
+
+from pathlib import Path
+from struct import pack
+
+SIZE = 16
+
+REQUEST = 0
+RESPONSE = 1
+
+# Response.status
+INIT_GAME = 2
+IN_GAME = 3
+
+
+def varint(n):
+	out = bytearray()
+	while True:
+		byte = n & 0x7F
+		n >>= 7
+		if n:
+			out.append(byte | 0x80)
+		else:
+			out.append(byte)
+			return bytes(out)
+
+
+def field(number, value):
+	if isinstance(value, bool) or isinstance(value, int):
+		return varint(number << 3) + varint(int(value))
+	if isinstance(value, float):
+		return varint(number << 3 | 5) + pack("<f", value)
+	if isinstance(value, str):
+		value = value.encode()
+	return varint(number << 3 | 2) + varint(len(value)) + value
+
+
+def message(*fields):
+	return b"".join(field(number, value) for number, value in fields)
+
+
+def size2d(x, y):
+	return message((1, x), (2, y))
+
+
+def point2d(x, y):
+	return message((1, float(x)), (2, float(y)))
+
+
+def image(bits, data):
+	return message((1, bits), (2, size2d(SIZE, SIZE)), (3, data))
+
+
+def request(number, body=b""):
+	return message((number, body))
+
+
+def response(number, body=b"", status=IN_GAME):
+	return message((number, body), (99, status))
+
+
+def player_info(player_id, player_type, race_requested, race_actual=None, difficulty=None):
+	fields = [(1, player_id), (2, player_type), (3, race_requested)]
+	if race_actual is not None:
+		fields.append((4, race_actual))
+	if difficulty is not None:
+		fields.append((5, difficulty))
+	return message(*fields)
+
+
+def game_info():
+	pathable = image(1, b"\xFF" * (SIZE * SIZE // 8))
+	start_raw = message(
+		(1, size2d(SIZE, SIZE)),
+		(2, pathable),
+		(3, image(8, bytes([128]) * (SIZE * SIZE))),
+		(4, pathable),
+		(5, message((1, size2d(0, 0)), (2, size2d(SIZE, SIZE)))),
+		(6, point2d(12.5, 12.5)),
+	)
+	return message(
+		(1, "Mock"),
+		(2, "Mock.SC2Map"),
+		# Bot playing Terran and random VeryEasy computer
+		(3, player_info(1, 1, 1, race_actual=1)),
+		(3, player_info(2, 2, 4, difficulty=1)),
+		(4, start_raw),
+	)
+
+
+def observation(game_loop):
+	map_state = message(
+		# Everything is visible
+		(1, image(8, bytes([2]) * (SIZE * SIZE))),
+		(2, image(1, bytes(SIZE * SIZE // 8))),
+	)
+	raw = message((1, message((2, point2d(3.5, 3.5)))), (3, map_state))
+	common = message((1, 1), (2, 50), (4, 15))
+	obs = message((1, common), (5, raw), (9, game_loop))
+	# Victory of the bot, reported when the game ends
+	result = message((1, 1), (2, 1))
+	return message((3, obs), (4, result))
+
+
+def main():
+	records = [
+		(REQUEST, request(1)),
+		(RESPONSE, response(1, status=INIT_GAME)),
+		(REQUEST, request(2)),
+		(RESPONSE, response(2, message((1, 1)))),
+		(REQUEST, request(9)),
+		(RESPONSE, response(9, game_info())),
+		(REQUEST, request(13)),
+		(RESPONSE, response(13)),
+		(REQUEST, request(10)),
+		(RESPONSE, response(10, observation(0))),
+		(REQUEST, request(12, message((1, 1)))),
+		(RESPONSE, response(12)),
+		(REQUEST, request(10)),
+		(RESPONSE, response(10, observation(1))),
+	]
+	path = Path(__file__).with_name("mock_game.sc2capture")
+	with path.open("wb") as f:
+		for direction, msg in records:
+			f.write(bytes([direction]) + pack("<I", len(msg)) + msg)
+
+
+if __name__ == "__main__":
+	main()
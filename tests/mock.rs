@@ -0,0 +1,50 @@
+use rust_sc2::{mock::MockServer, prelude::*};
+
+// Requires `mock` feature: `cargo test --features mock`
+// Capture is written by `tests/data/make_capture.py`
+const CAPTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/mock_game.sc2capture");
+
+#[bot]
+#[derive(Default)]
+struct MockBot {
+	steps: usize,
+}
+impl Player for MockBot {
+	fn get_player_settings(&self) -> PlayerSettings {
+		PlayerSettings::new(Race::Terran)
+	}
+	fn on_step(&mut self, _iteration: usize) -> SC2Result<()> {
+		self.steps += 1;
+		Ok(())
+	}
+}
+
+#[test]
+fn replays_captured_game() -> SC2Result<()> {
+	let server = MockServer::from_capture(CAPTURE)?.start()?;
+	server.set_env();
+
+	let mut bot = MockBot::default();
+	run_vs_computer(
+		&mut bot,
+		Computer::new(Race::Random, Difficulty::VeryEasy, None),
+		"Mock",
+		LaunchOptions {
+			maps_path: Some("Maps"),
+			..Default::default()
+		},
+	)?;
+
+	assert_eq!(bot.steps, 1);
+	assert_eq!(bot.game_info.map_name, "Mock");
+	assert_eq!(bot.race, Race::Terran);
+	let summary = bot.game_summary().expect("Game didn't end");
+	assert_eq!(summary.result, GameResult::Victory);
+	assert_eq!(summary.game_loop, 1);
+
+	let received = server.received();
+	assert!(received.iter().any(|req| req.has_create_game()));
+	assert!(received.iter().any(|req| req.has_join_game()));
+	assert!(received.iter().any(|req| req.has_step()));
+	Ok(())
+}